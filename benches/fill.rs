@@ -0,0 +1,61 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use phoony::{Rect, Vec2D};
+use std::hint::black_box;
+
+// A 256 by 256 level, loaded and cleared cell by cell as the baseline, next
+// to the row-wise bulk edits, in one group each so they report side by side.
+fn fill(c: &mut Criterion) {
+    let level = Rect::new(-128, -128, 127, 127);
+
+    let mut group = c.benchmark_group("load level");
+    group.bench_function("set per cell", |b| {
+        b.iter(|| {
+            let mut grid = Vec2D::new();
+            for (x, y) in level.iter() {
+                grid.set(x, y, black_box(1u8));
+            }
+            grid
+        })
+    });
+    group.bench_function("fill_rect", |b| {
+        b.iter(|| {
+            let mut grid = Vec2D::new();
+            grid.fill_rect(level, black_box(1u8));
+            grid
+        })
+    });
+    group.finish();
+
+    let mut full = Vec2D::new();
+    full.fill_rect(level, 1u8);
+
+    let mut group = c.benchmark_group("clear level");
+    group.bench_function("remove per cell", |b| {
+        b.iter(|| {
+            let mut grid = full.clone();
+            for (x, y) in level.iter() {
+                grid.remove(x, y);
+            }
+            grid
+        })
+    });
+    group.bench_function("clear_rect", |b| {
+        b.iter(|| {
+            let mut grid = full.clone();
+            grid.clear_rect(black_box(level));
+            grid
+        })
+    });
+    group.bench_function("clear_rect everything", |b| {
+        let everything = Rect::new(isize::MIN, isize::MIN, isize::MAX, isize::MAX);
+        b.iter(|| {
+            let mut grid = full.clone();
+            grid.clear_rect(black_box(everything));
+            grid
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, fill);
+criterion_main!(benches);
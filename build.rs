@@ -0,0 +1,20 @@
+// With the `ffi` feature, regenerates include/phoony.h from src/ffi.rs so
+// the header cannot drift from the Rust declarations. cbindgen only rewrites
+// the file when its contents change. The same header comes from
+// `cbindgen --config cbindgen.toml --output include/phoony.h`.
+fn main() {
+    #[cfg(feature = "ffi")]
+    {
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let config = cbindgen::Config::from_file(format!("{crate_dir}/cbindgen.toml")).unwrap();
+        cbindgen::Builder::new()
+            .with_config(config)
+            .with_src(format!("{crate_dir}/src/ffi.rs"))
+            .generate()
+            .expect("src/ffi.rs declares a valid C interface")
+            .write_to_file(format!("{crate_dir}/include/phoony.h"));
+    }
+}
@@ -0,0 +1,140 @@
+/* C interface to the phoony grid, storing one int64_t per cell.
+ *
+ * Generated from src/ffi.rs by cbindgen; do not edit. Build the library with
+ *   cargo rustc --release --features ffi --crate-type cdylib
+ * (or --crate-type staticlib) and link against it. */
+
+#ifndef PHOONY_H
+#define PHOONY_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * A grid of int64_t cells, created by `grid_new` or `grid_clone` and freed
+ * by `grid_free`.
+ */
+typedef struct PhoonyGrid PhoonyGrid;
+
+/**
+ * Walks the stored cells of a grid. It borrows the grid, which must not be
+ * changed or freed while the iterator is alive.
+ */
+typedef struct PhoonyGridIter PhoonyGridIter;
+
+/**
+ * Inclusive bounds, with x the row and y the column.
+ */
+typedef struct PhoonyRect {
+  ptrdiff_t min_x;
+  ptrdiff_t min_y;
+  ptrdiff_t max_x;
+  ptrdiff_t max_y;
+} PhoonyRect;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Free the grid with `grid_free`.
+ */
+struct PhoonyGrid *grid_new(void);
+
+/**
+ * Does nothing when `grid` is null.
+ *
+ * # Safety
+ * `grid` must be null or a live pointer from `grid_new` or `grid_clone`,
+ * with no iterator borrowing it; it is dangling afterwards.
+ */
+void grid_free(struct PhoonyGrid *grid);
+
+/**
+ * Returns false when the grid could not grow to hold the cell.
+ *
+ * # Safety
+ * `grid` must be a live pointer from `grid_new` or `grid_clone`, with no
+ * iterator borrowing it.
+ */
+bool grid_set(struct PhoonyGrid *grid, ptrdiff_t x, ptrdiff_t y, int64_t value);
+
+/**
+ * Returns false when the cell is empty; otherwise writes its value to
+ * `value` unless that is null.
+ *
+ * # Safety
+ * `grid` must be a live pointer from `grid_new` or `grid_clone`.
+ * `value` must be null or valid for writing an int64_t.
+ */
+bool grid_get(const struct PhoonyGrid *grid, ptrdiff_t x, ptrdiff_t y, int64_t *value);
+
+/**
+ * Returns false when the cell was empty; otherwise writes the removed
+ * value to `value` unless that is null.
+ *
+ * # Safety
+ * `grid` must be a live pointer from `grid_new` or `grid_clone`, with no
+ * iterator borrowing it.
+ * `value` must be null or valid for writing an int64_t.
+ */
+bool grid_remove(struct PhoonyGrid *grid, ptrdiff_t x, ptrdiff_t y, int64_t *value);
+
+/**
+ * The number of stored cells.
+ *
+ * # Safety
+ * `grid` must be a live pointer from `grid_new` or `grid_clone`.
+ */
+size_t grid_len(const struct PhoonyGrid *grid);
+
+/**
+ * # Safety
+ * `grid` must be a live pointer from `grid_new` or `grid_clone`.
+ */
+struct PhoonyRect grid_bounds(const struct PhoonyGrid *grid);
+
+/**
+ * Cells come in ascending x, then ascending y. Free the iterator with
+ * `grid_iter_free`.
+ *
+ * # Safety
+ * `grid` must be a live pointer from `grid_new` or `grid_clone`, and stay
+ * alive and unchanged until the iterator is freed.
+ */
+struct PhoonyGridIter *grid_iter_new(const struct PhoonyGrid *grid);
+
+/**
+ * Writes the next stored cell to the out pointers that are not null, and
+ * returns false once every cell has been visited.
+ *
+ * # Safety
+ * `iter` must be a live pointer from `grid_iter_new` whose grid is still
+ * alive and unchanged. `x`, `y` and `value` must each be null or valid for
+ * writing.
+ */
+bool grid_iter_next(struct PhoonyGridIter *iter, ptrdiff_t *x, ptrdiff_t *y, int64_t *value);
+
+/**
+ * Does nothing when `iter` is null.
+ *
+ * # Safety
+ * `iter` must be null or a live pointer from `grid_iter_new`; it is
+ * dangling afterwards.
+ */
+void grid_iter_free(struct PhoonyGridIter *iter);
+
+/**
+ * An independent copy, freed with `grid_free`.
+ *
+ * # Safety
+ * `grid` must be a live pointer from `grid_new` or `grid_clone`.
+ */
+struct PhoonyGrid *grid_clone(const struct PhoonyGrid *grid);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* PHOONY_H */
@@ -0,0 +1,38 @@
+use crate::grid::Grid;
+use crate::rect::Rect;
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+// Grids of up to 16 by 16 cells somewhere near the origin, each cell filled
+// or left empty by the input. Keeping them small keeps fuzzing fast.
+impl<'a, T: Arbitrary<'a>> Arbitrary<'a> for Grid<T> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let (min_x, min_y) = (u.int_in_range(-16..=16)?, u.int_in_range(-16..=16)?);
+        let (height, width) = (u.int_in_range(0..=15)?, u.int_in_range(0..=15)?);
+        let bounds = Rect::new(min_x, min_y, min_x + height, min_y + width);
+
+        let mut grid = Grid::with_bounds(bounds);
+        for (x, y) in bounds.iter() {
+            if u.arbitrary()? {
+                grid.set(x, y, u.arbitrary()?);
+            }
+        }
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::Grid;
+    use arbitrary::{Arbitrary, Unstructured};
+
+    #[test]
+    fn builds_grids_from_bytes() {
+        let bytes: Vec<u8> = (0..=255).cycle().take(1024).collect();
+        let grid = Grid::<u8>::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+        assert!(grid.bounds().area() <= 256);
+        assert_eq!(grid.len(), grid.iter().count());
+
+        let empty = Grid::<u8>::arbitrary(&mut Unstructured::new(&[])).unwrap();
+        assert!(empty.is_empty());
+    }
+}
@@ -0,0 +1,134 @@
+use crate::grid::Grid;
+use bevy_ecs::change_detection::DetectChanges;
+use bevy_ecs::component::Component;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::resource::Resource;
+use bevy_ecs::system::{Commands, Res, ResMut};
+use std::collections::HashMap;
+
+// With the bevy feature `Grid<T>` derives `Resource` and an opaque
+// `Reflect`. Bevy resources are singleton components, so the grid can not
+// also derive a plain `Component` for attaching to many entities.
+
+// The cell a tile entity stands for.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TilePosition {
+    pub x: isize,
+    pub y: isize,
+}
+
+// One entity per stored cell of a grid, each carrying a `TilePosition` and a
+// copy of the cell as a component. Rendering plugins can then react to the
+// usual `Added` and `Changed` filters on the tile component.
+#[derive(Resource)]
+pub struct Tilemap<T: Send + Sync + 'static> {
+    tiles: HashMap<(isize, isize), (Entity, T)>,
+}
+
+impl<T: Send + Sync + 'static> Default for Tilemap<T> {
+    fn default() -> Self {
+        Self {
+            tiles: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Component + Clone + PartialEq> Tilemap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn entity(&self, x: isize, y: isize) -> Option<Entity> {
+        self.tiles.get(&(x, y)).map(|&(entity, _)| entity)
+    }
+
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    // Spawns entities for new cells, replaces the component of changed ones
+    // and despawns the entities of removed cells.
+    pub fn sync(&mut self, grid: &Grid<T>, commands: &mut Commands) {
+        self.tiles.retain(|&(x, y), (entity, _)| {
+            let kept = grid.get(x, y).is_some();
+            if !kept {
+                commands.entity(*entity).despawn();
+            }
+            kept
+        });
+
+        for ((x, y), tile) in grid.iter() {
+            match self.tiles.get_mut(&(x, y)) {
+                Some((_, synced)) if synced == tile => {}
+                Some((entity, synced)) => {
+                    commands.entity(*entity).insert(tile.clone());
+                    *synced = tile.clone();
+                }
+                None => {
+                    let entity = commands.spawn((TilePosition { x, y }, tile.clone())).id();
+                    self.tiles.insert((x, y), (entity, tile.clone()));
+                }
+            }
+        }
+    }
+}
+
+// A system keeping `Tilemap<T>` in step with the `Grid<T>` resource, only
+// doing work on frames where the grid changed.
+pub fn sync_tilemap<T: Component + Clone + PartialEq>(
+    grid: Res<Grid<T>>,
+    mut tilemap: ResMut<Tilemap<T>>,
+    mut commands: Commands,
+) {
+    if grid.is_changed() {
+        tilemap.sync(&grid, &mut commands);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sync_tilemap, TilePosition, Tilemap};
+    use crate::grid::Grid;
+    use bevy_ecs::component::Component;
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_ecs::world::World;
+
+    #[derive(Component, Clone, Copy, Debug, PartialEq)]
+    enum Tile {
+        Grass,
+        Water,
+    }
+
+    #[test]
+    fn syncs_tiles_with_the_grid() {
+        let mut world = World::new();
+        let mut grid = Grid::new();
+        grid.set(0, 0, Tile::Grass);
+        grid.set(1, 2, Tile::Water);
+        world.insert_resource(grid);
+        world.insert_resource(Tilemap::<Tile>::new());
+        world.run_system_once(sync_tilemap::<Tile>).unwrap();
+
+        let water = world.resource::<Tilemap<Tile>>().entity(1, 2).unwrap();
+        assert_eq!(world.get::<Tile>(water), Some(&Tile::Water));
+        assert_eq!(
+            world.get::<TilePosition>(water),
+            Some(&TilePosition { x: 1, y: 2 })
+        );
+
+        let mut grid = world.resource_mut::<Grid<Tile>>();
+        grid.remove(0, 0);
+        grid.set(1, 2, Tile::Grass);
+        world.run_system_once(sync_tilemap::<Tile>).unwrap();
+
+        let tilemap = world.resource::<Tilemap<Tile>>();
+        assert_eq!(tilemap.len(), 1);
+        assert_eq!(tilemap.entity(1, 2), Some(water));
+        assert_eq!(world.get::<Tile>(water), Some(&Tile::Grass));
+        assert_eq!(world.query::<&Tile>().iter(&world).count(), 1);
+    }
+}
@@ -0,0 +1,121 @@
+// A fixed-size grid of booleans packed into 64-bit words, one run of words per
+// row. Rows are 0..height and columns 0..width; bit `i` of word `w` in a row
+// holds column `w * 64 + i`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(64);
+
+        Self {
+            width,
+            height,
+            words_per_row,
+            words: vec![
+                0;
+                words_per_row
+                    .checked_mul(height)
+                    .expect("bit grid size overflows usize")
+            ],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn position(&self, x: isize, y: isize) -> Option<(usize, u64)> {
+        if x < 0 || y < 0 || x as usize >= self.height || y as usize >= self.width {
+            return None;
+        }
+
+        let (x, y) = (x as usize, y as usize);
+        Some((x * self.words_per_row + y / 64, 1 << (y % 64)))
+    }
+
+    // Coordinates outside the grid read as false.
+    pub fn get(&self, x: isize, y: isize) -> bool {
+        self.position(x, y)
+            .is_some_and(|(word, bit)| self.words[word] & bit != 0)
+    }
+
+    pub fn set(&mut self, x: isize, y: isize, value: bool) {
+        let (word, bit) = self
+            .position(x, y)
+            .unwrap_or_else(|| panic!("({x}, {y}) is outside the bit grid"));
+
+        if value {
+            self.words[word] |= bit;
+        } else {
+            self.words[word] &= !bit;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub(crate) fn words_per_row(&self) -> usize {
+        self.words_per_row
+    }
+
+    pub(crate) fn row(&self, x: usize) -> &[u64] {
+        &self.words[x * self.words_per_row..(x + 1) * self.words_per_row]
+    }
+
+    pub(crate) fn row_mut(&mut self, x: usize) -> &mut [u64] {
+        &mut self.words[x * self.words_per_row..(x + 1) * self.words_per_row]
+    }
+
+    // Clears the bits past `width` in the last word of every row.
+    pub(crate) fn mask_padding(&mut self) {
+        let used = self.width % 64;
+        if used == 0 {
+            return;
+        }
+
+        for x in 0..self.height {
+            let last = (x + 1) * self.words_per_row - 1;
+            self.words[last] &= (1 << used) - 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BitGrid;
+
+    #[test]
+    fn bits_round_trip() {
+        let mut grid = BitGrid::new(100, 3);
+
+        grid.set(2, 99, true);
+        grid.set(0, 63, true);
+        grid.set(0, 64, true);
+        grid.set(0, 64, false);
+
+        assert!(grid.get(2, 99) && grid.get(0, 63));
+        assert!(!grid.get(0, 64) && !grid.get(-1, 0) && !grid.get(0, 100));
+        assert_eq!(grid.count_ones(), 2);
+
+        grid.clear();
+        assert_eq!(grid.count_ones(), 0);
+    }
+}
@@ -0,0 +1,256 @@
+use crate::error::GridError;
+use crate::grid::{Grid, GridRead, GridWrite};
+use crate::rect::Rect;
+use bumpalo::collections::Vec as BumpVec;
+use bumpalo::Bump;
+
+// A grid whose cells live in a bump arena, for short-lived scratch grids:
+// allocate many of them from one `Bump` and free them all at once with
+// `Bump::reset`. Cells are stored densely over a storage rect covering the
+// bounds. Growing it at least doubles the overflowing extents and copies the
+// cells into a new block, leaving the old one to the arena, so filling a
+// grid outwards cell by cell uses O(n) arena memory.
+pub struct BumpGrid<'bump, T> {
+    bump: &'bump Bump,
+    bounds: Rect,
+    storage: Rect,
+    cells: BumpVec<'bump, Option<T>>,
+    len: usize,
+}
+
+// The number of cells in `rect`, if it can be counted.
+fn area(rect: Rect) -> Option<usize> {
+    let span = |min: isize, max: isize| max.abs_diff(min).checked_add(1);
+    span(rect.min_x, rect.max_x)?.checked_mul(span(rect.min_y, rect.max_y)?)
+}
+
+impl<'bump, T> BumpGrid<'bump, T> {
+    pub fn new_in(bump: &'bump Bump) -> Self {
+        Self::with_bounds_in(Rect::new(0, 0, 0, 0), bump)
+    }
+
+    // Reserves `bounds` up front so filling them never reallocates.
+    pub fn with_bounds_in(bounds: Rect, bump: &'bump Bump) -> Self {
+        let mut cells = BumpVec::with_capacity_in(bounds.area(), bump);
+        cells.extend((0..bounds.area()).map(|_| None));
+        Self {
+            bump,
+            bounds,
+            storage: bounds,
+            cells,
+            len: 0,
+        }
+    }
+
+    fn index(&self, x: isize, y: isize) -> Option<usize> {
+        self.storage.contains(x, y).then(|| {
+            x.abs_diff(self.storage.min_x) * self.storage.width() + y.abs_diff(self.storage.min_y)
+        })
+    }
+
+    pub fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, x: isize, y: isize) -> Option<&T> {
+        self.cells[self.index(x, y)?].as_ref()
+    }
+
+    pub fn get_mut(&mut self, x: isize, y: isize) -> Option<&mut T> {
+        let index = self.index(x, y)?;
+        self.cells[index].as_mut()
+    }
+
+    pub fn set(&mut self, x: isize, y: isize, item: T) {
+        if let Err(error) = self.try_set(x, y, item) {
+            panic!("cannot set ({x}, {y}): {error}");
+        }
+    }
+
+    // Like `set`, but fails instead of aborting when the storage needed to
+    // reach (x, y) cannot be counted or allocated.
+    pub fn try_set(&mut self, x: isize, y: isize, item: T) -> Result<(), GridError> {
+        if !self.storage.contains(x, y) {
+            self.grow(x, y)?;
+        }
+
+        let index = self.index(x, y).unwrap();
+        if self.cells[index].replace(item).is_none() {
+            self.len += 1;
+        }
+        self.bounds = self.bounds.union(&Rect::new(x, y, x, y));
+        Ok(())
+    }
+
+    pub fn remove(&mut self, x: isize, y: isize) -> Option<T> {
+        let index = self.index(x, y)?;
+        let removed = self.cells[index].take();
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    // Doubles the extents that overflow, like `VisitedGrid`, and falls back
+    // to just covering (x, y) when the doubled storage would not fit.
+    fn grow(&mut self, x: isize, y: isize) -> Result<(), GridError> {
+        let old = self.storage;
+        let exact = old.union(&Rect::new(x, y, x, y));
+        let (height, width) = (old.height() as isize, old.width() as isize);
+        let doubled = Rect::new(
+            if x < old.min_x {
+                x.min(old.min_x.saturating_sub(height))
+            } else {
+                old.min_x
+            },
+            if y < old.min_y {
+                y.min(old.min_y.saturating_sub(width))
+            } else {
+                old.min_y
+            },
+            if x > old.max_x {
+                x.max(old.max_x.saturating_add(height))
+            } else {
+                old.max_x
+            },
+            if y > old.max_y {
+                y.max(old.max_y.saturating_add(width))
+            } else {
+                old.max_y
+            },
+        );
+
+        let mut cells = BumpVec::new_in(self.bump);
+        let mut storage = None;
+        for rect in [doubled, exact] {
+            let Some(area) = area(rect) else {
+                continue;
+            };
+            if cells.try_reserve_exact(area).is_ok() {
+                storage = Some((rect, area));
+                break;
+            }
+        }
+        let Some((storage, area)) = storage else {
+            return Err(if area(exact).is_none() {
+                GridError::CoordinateOverflow { x, y }
+            } else {
+                GridError::CapacityExceeded
+            });
+        };
+
+        cells.extend((0..area).map(|_| None));
+        let old_cells = std::mem::replace(&mut self.cells, cells);
+        self.storage = storage;
+        for ((x, y), cell) in old.iter().zip(old_cells) {
+            if cell.is_some() {
+                let index = self.index(x, y).unwrap();
+                self.cells[index] = cell;
+            }
+        }
+        Ok(())
+    }
+
+    // The stored cells in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = ((isize, isize), &T)> {
+        self.storage
+            .iter()
+            .zip(&self.cells)
+            .filter_map(|(position, cell)| Some((position, cell.as_ref()?)))
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.iter_mut().for_each(|cell| *cell = None);
+        self.len = 0;
+    }
+}
+
+impl<T: Clone> BumpGrid<'_, T> {
+    // A heap-backed copy that outlives the arena.
+    pub fn to_grid(&self) -> Grid<T> {
+        let mut grid = Grid::with_bounds(self.bounds);
+        for ((x, y), item) in self.iter() {
+            grid.set(x, y, item.clone());
+        }
+        grid
+    }
+}
+
+impl<T> GridRead<T> for BumpGrid<'_, T> {
+    fn get(&self, x: isize, y: isize) -> Option<&T> {
+        BumpGrid::get(self, x, y)
+    }
+
+    fn bounds(&self) -> Rect {
+        self.bounds
+    }
+}
+
+impl<T> GridWrite<T> for BumpGrid<'_, T> {
+    fn set(&mut self, x: isize, y: isize, item: T) {
+        BumpGrid::set(self, x, y, item);
+    }
+
+    fn remove(&mut self, x: isize, y: isize) -> Option<T> {
+        BumpGrid::remove(self, x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BumpGrid;
+    use crate::error::GridError;
+    use crate::rect::Rect;
+    use bumpalo::Bump;
+
+    #[test]
+    fn scratch_grids_share_an_arena() {
+        let mut bump = Bump::new();
+        for frame in 0..3 {
+            let mut scratch = BumpGrid::new_in(&bump);
+            scratch.set(-2, 3, frame);
+            scratch.set(4, -1, frame + 1);
+            assert_eq!(scratch.bounds(), Rect::new(-2, -1, 4, 3));
+            assert_eq!(scratch.get(-2, 3), Some(&frame));
+            assert_eq!(scratch.remove(-2, 3), Some(frame));
+            assert_eq!(scratch.len(), 1);
+
+            let grid = scratch.to_grid();
+            assert_eq!(grid.get(4, -1), Some(&(frame + 1)));
+            assert_eq!(grid.bounds(), scratch.bounds());
+            drop(scratch);
+            bump.reset();
+        }
+    }
+
+    #[test]
+    fn grows_geometrically() {
+        let bump = Bump::new();
+        let mut scratch = BumpGrid::new_in(&bump);
+        for y in 0..1000 {
+            scratch.set(0, y, y);
+        }
+        assert_eq!(scratch.bounds(), Rect::new(0, 0, 0, 999));
+        assert!((0..1000).all(|y| scratch.get(0, y) == Some(&y)));
+        assert_eq!(scratch.iter().count(), 1000);
+        // Every block is at most twice the previous one.
+        assert!(bump.allocated_bytes() < 4 * 1024 * size_of::<Option<isize>>());
+
+        assert_eq!(
+            scratch.try_set(isize::MAX, isize::MAX, 0),
+            Err(GridError::CoordinateOverflow {
+                x: isize::MAX,
+                y: isize::MAX
+            })
+        );
+        assert_eq!(scratch.len(), 1000);
+    }
+}
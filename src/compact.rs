@@ -0,0 +1,57 @@
+use crate::grid::Grid;
+use crate::stream::Codec;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use std::io;
+
+// Snapshots are small, so the strongest zstd level costs next to nothing.
+const LEVEL: i32 = 19;
+
+impl<T: Codec> Grid<T> {
+    // The grid stream compressed with zstd and encoded as URL safe base64,
+    // short enough to paste into an issue or a test.
+    pub fn to_compact_string(&self) -> String {
+        let mut stream = vec![];
+        self.write_stream(&mut stream)
+            .expect("writing to a Vec can not fail");
+        let compressed =
+            zstd::encode_all(stream.as_slice(), LEVEL).expect("compressing a slice can not fail");
+        URL_SAFE_NO_PAD.encode(compressed)
+    }
+
+    // Surrounding whitespace is ignored, so snapshots may be pasted with
+    // trailing newlines.
+    pub fn from_compact_string(snapshot: &str) -> io::Result<Grid<T>> {
+        let compressed = URL_SAFE_NO_PAD
+            .decode(snapshot.trim())
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        let stream = zstd::decode_all(compressed.as_slice())?;
+        Grid::read_stream(stream.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::Grid;
+
+    #[test]
+    fn round_trips_snapshots() {
+        let mut grid = Grid::new();
+        for y in -20..20 {
+            grid.set(3, y, y % 4 == 0);
+        }
+        grid.set(-7, 100, true);
+
+        let snapshot = grid.to_compact_string();
+        assert!(snapshot.len() < 128);
+        assert!(snapshot
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_'));
+
+        let restored = Grid::<bool>::from_compact_string(&format!("{snapshot}\n")).unwrap();
+        assert_eq!(restored.bounds(), grid.bounds());
+        assert!(restored.iter().eq(grid.iter()));
+
+        assert!(Grid::<bool>::from_compact_string("not a snapshot!").is_err());
+    }
+}
@@ -0,0 +1,74 @@
+use crate::direction::Direction;
+
+const KNIGHT: [(isize, isize); 8] = [
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+];
+
+// Which cells count as adjacent for fills, labeling and spreading. The
+// plain methods use `Four`; their `_with` variants take one of these.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Connectivity {
+    #[default]
+    Four,
+    Eight,
+    Knight,
+    // Any set of offsets. Fills follow them as given, so an asymmetric set
+    // makes adjacency one-directional.
+    Custom(Vec<(isize, isize)>),
+}
+
+impl Connectivity {
+    pub fn offsets(&self) -> Vec<(isize, isize)> {
+        match self {
+            Connectivity::Four => Direction::CARDINAL.map(Direction::offset).to_vec(),
+            Connectivity::Eight => Direction::ALL.map(Direction::offset).to_vec(),
+            Connectivity::Knight => KNIGHT.to_vec(),
+            Connectivity::Custom(offsets) => offsets.clone(),
+        }
+    }
+
+    // Neighbors past the edge of the coordinate space are left out.
+    pub fn neighbors(&self, cell: (isize, isize)) -> impl Iterator<Item = (isize, isize)> {
+        self.offsets()
+            .into_iter()
+            .filter_map(move |offset| step(cell, offset))
+    }
+}
+
+// `cell` moved by `offset`, or None past the edge of the coordinate space.
+pub(crate) fn step((x, y): (isize, isize), (dx, dy): (isize, isize)) -> Option<(isize, isize)> {
+    Some((x.checked_add(dx)?, y.checked_add(dy)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Connectivity;
+
+    #[test]
+    fn skips_neighbors_past_the_edge() {
+        let corner = (isize::MAX, isize::MIN);
+        let neighbors: Vec<_> = Connectivity::Four.neighbors(corner).collect();
+        assert_eq!(
+            neighbors,
+            [(isize::MAX - 1, isize::MIN), (isize::MAX, isize::MIN + 1)]
+        );
+    }
+
+    #[test]
+    fn offsets_per_kind() {
+        assert_eq!(Connectivity::Four.offsets().len(), 4);
+        assert_eq!(Connectivity::Eight.offsets().len(), 8);
+        assert!(Connectivity::Knight
+            .neighbors((0, 0))
+            .all(|(x, y)| x.abs() + y.abs() == 3));
+        let custom = Connectivity::Custom(vec![(0, 2)]);
+        assert_eq!(custom.neighbors((1, 1)).collect::<Vec<_>>(), [(1, 3)]);
+    }
+}
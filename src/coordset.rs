@@ -0,0 +1,169 @@
+use crate::grid::Grid;
+use crate::rect::Rect;
+use std::collections::BTreeSet;
+
+// A sparse set of cells, iterated in row-major order. Results such as
+// flood fills, fields of view and pattern matches are often easier to
+// combine as sets than as grids.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CoordSet {
+    cells: BTreeSet<(isize, isize)>,
+}
+
+impl CoordSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        self.cells.contains(&(x, y))
+    }
+
+    // Returns whether the cell was new.
+    pub fn insert(&mut self, x: isize, y: isize) -> bool {
+        self.cells.insert((x, y))
+    }
+
+    pub fn remove(&mut self, x: isize, y: isize) -> bool {
+        self.cells.remove(&(x, y))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (isize, isize)> + '_ {
+        self.cells.iter().copied()
+    }
+
+    pub fn union(&self, other: &CoordSet) -> CoordSet {
+        self.cells.union(&other.cells).copied().collect()
+    }
+
+    pub fn intersection(&self, other: &CoordSet) -> CoordSet {
+        self.cells.intersection(&other.cells).copied().collect()
+    }
+
+    pub fn difference(&self, other: &CoordSet) -> CoordSet {
+        self.cells.difference(&other.cells).copied().collect()
+    }
+
+    pub fn symmetric_difference(&self, other: &CoordSet) -> CoordSet {
+        self.cells
+            .symmetric_difference(&other.cells)
+            .copied()
+            .collect()
+    }
+
+    pub fn is_subset(&self, other: &CoordSet) -> bool {
+        self.cells.is_subset(&other.cells)
+    }
+
+    // Every cell moved by (dx, dy).
+    pub fn translated(&self, dx: isize, dy: isize) -> CoordSet {
+        self.iter().map(|(x, y)| (x + dx, y + dy)).collect()
+    }
+
+    // The smallest rect holding every cell; unlike grid bounds it does not
+    // include the origin, and an empty set has none.
+    pub fn bounds(&self) -> Option<Rect> {
+        let (&(min_x, _), &(max_x, _)) = (self.cells.first()?, self.cells.last()?);
+        let (min_y, max_y) = self
+            .iter()
+            .fold((isize::MAX, isize::MIN), |(min, max), (_, y)| {
+                (min.min(y), max.max(y))
+            });
+        Some(Rect::new(min_x, min_y, max_x, max_y))
+    }
+
+    // A grid holding `true` at every cell of the set.
+    pub fn to_grid(&self) -> Grid<bool> {
+        let mut grid = match self.bounds() {
+            Some(bounds) => Grid::with_bounds(bounds),
+            None => Grid::new(),
+        };
+        for (x, y) in self.iter() {
+            grid.set(x, y, true);
+        }
+        grid
+    }
+}
+
+// The cells holding `true`.
+impl From<&Grid<bool>> for CoordSet {
+    fn from(grid: &Grid<bool>) -> Self {
+        grid.iter()
+            .filter(|(_, &value)| value)
+            .map(|(position, _)| position)
+            .collect()
+    }
+}
+
+impl FromIterator<(isize, isize)> for CoordSet {
+    fn from_iter<I: IntoIterator<Item = (isize, isize)>>(iter: I) -> Self {
+        Self {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<(isize, isize)> for CoordSet {
+    fn extend<I: IntoIterator<Item = (isize, isize)>>(&mut self, iter: I) {
+        self.cells.extend(iter);
+    }
+}
+
+impl IntoIterator for CoordSet {
+    type Item = (isize, isize);
+    type IntoIter = std::collections::btree_set::IntoIter<(isize, isize)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CoordSet;
+    use crate::grid::Grid;
+    use crate::rect::Rect;
+
+    #[test]
+    fn combines_sets() {
+        let a: CoordSet = [(0, 0), (1, 2), (3, -1)].into_iter().collect();
+        let b: CoordSet = [(1, 2), (5, 5)].into_iter().collect();
+
+        assert_eq!(a.union(&b).len(), 4);
+        assert_eq!(a.intersection(&b).iter().collect::<Vec<_>>(), [(1, 2)]);
+        assert_eq!(a.difference(&b).len(), 2);
+        assert_eq!(a.symmetric_difference(&b).len(), 3);
+        assert!(a.intersection(&b).is_subset(&b));
+
+        assert_eq!(a.bounds(), Some(Rect::new(0, -1, 3, 2)));
+        assert_eq!(
+            a.translated(10, 10).bounds(),
+            Some(Rect::new(10, 9, 13, 12))
+        );
+        assert_eq!(CoordSet::new().bounds(), None);
+    }
+
+    #[test]
+    fn converts_to_and_from_grids() {
+        let mut grid = Grid::new();
+        grid.set(-2, 3, true);
+        grid.set(1, 1, false);
+        grid.set(4, 0, true);
+
+        let set = CoordSet::from(&grid);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [(-2, 3), (4, 0)]);
+
+        let back = set.to_grid();
+        assert_eq!(back.len(), 2);
+        assert_eq!(back.get(4, 0), Some(&true));
+        assert_eq!(back.get(1, 1), None);
+    }
+}
@@ -0,0 +1,114 @@
+// Grids are indexed as (x, y) with x selecting the row and y the column, so
+// North points towards decreasing x and East towards increasing y.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    // Clockwise, starting at North.
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::North => (-1, 0),
+            Direction::NorthEast => (-1, 1),
+            Direction::East => (0, 1),
+            Direction::SouthEast => (1, 1),
+            Direction::South => (1, 0),
+            Direction::SouthWest => (1, -1),
+            Direction::West => (0, -1),
+            Direction::NorthWest => (-1, -1),
+        }
+    }
+
+    pub fn from_offset(dx: isize, dy: isize) -> Option<Direction> {
+        Direction::ALL
+            .into_iter()
+            .find(|direction| direction.offset() == (dx, dy))
+    }
+
+    // The neighboring cell in this direction, or None at the edge of the
+    // coordinate space.
+    pub fn checked_step(self, (x, y): (isize, isize)) -> Option<(isize, isize)> {
+        let (dx, dy) = self.offset();
+        Some((x.checked_add(dx)?, y.checked_add(dy)?))
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        let (dx, dy) = self.offset();
+        dx != 0 && dy != 0
+    }
+
+    fn rotated(self, steps: usize) -> Direction {
+        Direction::ALL[(self as usize + steps) % 8]
+    }
+
+    // Rotations in 45 degree steps.
+    pub fn clockwise(self) -> Direction {
+        self.rotated(1)
+    }
+
+    pub fn counter_clockwise(self) -> Direction {
+        self.rotated(7)
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.rotated(4)
+    }
+
+    // How many 45 degree steps turning to `other` takes the shorter way
+    // round, from 0 to 4.
+    pub fn turns_to(self, other: Direction) -> usize {
+        let steps = (other as usize + 8 - self as usize) % 8;
+        steps.min(8 - steps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Direction;
+
+    #[test]
+    fn rotations_and_offsets() {
+        for direction in Direction::ALL {
+            let (dx, dy) = direction.offset();
+            assert_eq!(Direction::from_offset(dx, dy), Some(direction));
+            assert_eq!(direction.opposite().offset(), (-dx, -dy));
+            assert_eq!(direction.clockwise().counter_clockwise(), direction);
+        }
+
+        assert_eq!(Direction::NorthWest.clockwise(), Direction::North);
+        assert_eq!(Direction::North.turns_to(Direction::SouthWest), 3);
+        assert_eq!(Direction::West.turns_to(Direction::East), 4);
+        assert_eq!(Direction::from_offset(2, 0), None);
+        assert_eq!(Direction::South.checked_step((isize::MAX, 0)), None);
+        assert_eq!(
+            Direction::North.checked_step((isize::MAX, 0)),
+            Some((isize::MAX - 1, 0))
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GridError {
+    // The coordinate is too far from the grid's other cells to be addressed.
+    CoordinateOverflow { x: isize, y: isize },
+    // The coordinate lies outside the region the grid is limited to.
+    OutOfBounds { x: isize, y: isize },
+    // The storage could not grow to hold the cell.
+    CapacityExceeded,
+    // The options given to a builder contradict each other or are not
+    // supported by the requested storage.
+    InvalidConfiguration { reason: &'static str },
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::CoordinateOverflow { x, y } => {
+                write!(f, "coordinate ({x}, {y}) overflows the grid")
+            }
+            GridError::OutOfBounds { x, y } => write!(f, "coordinate ({x}, {y}) is out of bounds"),
+            GridError::CapacityExceeded => write!(f, "grid storage capacity exceeded"),
+            GridError::InvalidConfiguration { reason } => {
+                write!(f, "invalid grid configuration: {reason}")
+            }
+        }
+    }
+}
+
+impl Error for GridError {}
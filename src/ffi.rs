@@ -0,0 +1,229 @@
+use crate::grid::{Grid, Iter};
+
+// A C ABI over `Grid<i64>`. Build the library with
+// `cargo rustc --release --features ffi --crate-type cdylib` (or
+// `staticlib`) to link it into C or C++ programs. include/phoony.h is
+// generated from this file by build.rs, so the `///` comments below are
+// what C callers read.
+
+/// A grid of int64_t cells, created by `grid_new` or `grid_clone` and freed
+/// by `grid_free`.
+pub struct PhoonyGrid(Grid<i64>);
+
+/// Walks the stored cells of a grid. It borrows the grid, which must not be
+/// changed or freed while the iterator is alive.
+pub struct PhoonyGridIter(Iter<'static, i64>);
+
+/// Inclusive bounds, with x the row and y the column.
+#[repr(C)]
+pub struct PhoonyRect {
+    pub min_x: isize,
+    pub min_y: isize,
+    pub max_x: isize,
+    pub max_y: isize,
+}
+
+unsafe fn write<T>(out: *mut T, value: T) {
+    if !out.is_null() {
+        out.write(value);
+    }
+}
+
+/// Free the grid with `grid_free`.
+#[no_mangle]
+pub extern "C" fn grid_new() -> *mut PhoonyGrid {
+    Box::into_raw(Box::new(PhoonyGrid(Grid::new())))
+}
+
+/// Does nothing when `grid` is null.
+///
+/// # Safety
+/// `grid` must be null or a live pointer from `grid_new` or `grid_clone`,
+/// with no iterator borrowing it; it is dangling afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn grid_free(grid: *mut PhoonyGrid) {
+    if !grid.is_null() {
+        drop(Box::from_raw(grid));
+    }
+}
+
+/// Returns false when the grid could not grow to hold the cell.
+///
+/// # Safety
+/// `grid` must be a live pointer from `grid_new` or `grid_clone`, with no
+/// iterator borrowing it.
+#[no_mangle]
+pub unsafe extern "C" fn grid_set(grid: *mut PhoonyGrid, x: isize, y: isize, value: i64) -> bool {
+    (*grid).0.try_set(x, y, value).is_ok()
+}
+
+/// Returns false when the cell is empty; otherwise writes its value to
+/// `value` unless that is null.
+///
+/// # Safety
+/// `grid` must be a live pointer from `grid_new` or `grid_clone`.
+/// `value` must be null or valid for writing an int64_t.
+#[no_mangle]
+pub unsafe extern "C" fn grid_get(
+    grid: *const PhoonyGrid,
+    x: isize,
+    y: isize,
+    value: *mut i64,
+) -> bool {
+    match (*grid).0.get(x, y) {
+        Some(&item) => {
+            write(value, item);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns false when the cell was empty; otherwise writes the removed
+/// value to `value` unless that is null.
+///
+/// # Safety
+/// `grid` must be a live pointer from `grid_new` or `grid_clone`, with no
+/// iterator borrowing it.
+/// `value` must be null or valid for writing an int64_t.
+#[no_mangle]
+pub unsafe extern "C" fn grid_remove(
+    grid: *mut PhoonyGrid,
+    x: isize,
+    y: isize,
+    value: *mut i64,
+) -> bool {
+    match (*grid).0.remove(x, y) {
+        Some(item) => {
+            write(value, item);
+            true
+        }
+        None => false,
+    }
+}
+
+/// The number of stored cells.
+///
+/// # Safety
+/// `grid` must be a live pointer from `grid_new` or `grid_clone`.
+#[no_mangle]
+pub unsafe extern "C" fn grid_len(grid: *const PhoonyGrid) -> usize {
+    (*grid).0.len()
+}
+
+/// # Safety
+/// `grid` must be a live pointer from `grid_new` or `grid_clone`.
+#[no_mangle]
+pub unsafe extern "C" fn grid_bounds(grid: *const PhoonyGrid) -> PhoonyRect {
+    let bounds = (*grid).0.bounds();
+    PhoonyRect {
+        min_x: bounds.min_x,
+        min_y: bounds.min_y,
+        max_x: bounds.max_x,
+        max_y: bounds.max_y,
+    }
+}
+
+/// Cells come in ascending x, then ascending y. Free the iterator with
+/// `grid_iter_free`.
+///
+/// # Safety
+/// `grid` must be a live pointer from `grid_new` or `grid_clone`, and stay
+/// alive and unchanged until the iterator is freed.
+#[no_mangle]
+pub unsafe extern "C" fn grid_iter_new(grid: *const PhoonyGrid) -> *mut PhoonyGridIter {
+    Box::into_raw(Box::new(PhoonyGridIter((*grid).0.iter())))
+}
+
+/// Writes the next stored cell to the out pointers that are not null, and
+/// returns false once every cell has been visited.
+///
+/// # Safety
+/// `iter` must be a live pointer from `grid_iter_new` whose grid is still
+/// alive and unchanged. `x`, `y` and `value` must each be null or valid for
+/// writing.
+#[no_mangle]
+pub unsafe extern "C" fn grid_iter_next(
+    iter: *mut PhoonyGridIter,
+    x: *mut isize,
+    y: *mut isize,
+    value: *mut i64,
+) -> bool {
+    match (*iter).0.next() {
+        Some(((cell_x, cell_y), &item)) => {
+            write(x, cell_x);
+            write(y, cell_y);
+            write(value, item);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Does nothing when `iter` is null.
+///
+/// # Safety
+/// `iter` must be null or a live pointer from `grid_iter_new`; it is
+/// dangling afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn grid_iter_free(iter: *mut PhoonyGridIter) {
+    if !iter.is_null() {
+        drop(Box::from_raw(iter));
+    }
+}
+
+/// An independent copy, freed with `grid_free`.
+///
+/// # Safety
+/// `grid` must be a live pointer from `grid_new` or `grid_clone`.
+#[no_mangle]
+pub unsafe extern "C" fn grid_clone(grid: *const PhoonyGrid) -> *mut PhoonyGrid {
+    Box::into_raw(Box::new(PhoonyGrid((*grid).0.clone())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    #[test]
+    fn drives_a_grid_through_the_c_abi() {
+        unsafe {
+            let grid = grid_new();
+            assert!(grid_set(grid, 2, -3, 40));
+            assert!(grid_set(grid, 0, 1, 7));
+
+            let mut value = 0;
+            assert!(grid_get(grid, 2, -3, &mut value));
+            assert_eq!(value, 40);
+            assert!(!grid_get(grid, 5, 5, ptr::null_mut()));
+            assert_eq!(grid_len(grid), 2);
+            assert_eq!(grid_bounds(grid).min_y, -3);
+
+            let iter = grid_iter_new(grid);
+            let (mut x, mut y, mut cells) = (0, 0, vec![]);
+            while grid_iter_next(iter, &mut x, &mut y, &mut value) {
+                cells.push((x, y, value));
+            }
+            grid_iter_free(iter);
+            assert_eq!(cells, [(0, 1, 7), (2, -3, 40)]);
+
+            assert!(grid_remove(grid, 0, 1, ptr::null_mut()));
+            assert_eq!(grid_len(grid), 1);
+            grid_free(grid);
+        }
+    }
+
+    #[test]
+    fn header_declares_every_function() {
+        let header = include_str!("../include/phoony.h");
+        let source = include_str!("ffi.rs");
+        for line in source.lines() {
+            let Some(rest) = line.split("extern \"C\" fn ").nth(1) else {
+                continue;
+            };
+            let name = &rest[..rest.find('(').unwrap()];
+            assert!(header.contains(&format!("{name}(")), "{name} missing");
+        }
+    }
+}
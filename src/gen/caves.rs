@@ -0,0 +1,129 @@
+use super::Tile;
+use crate::grid::Grid;
+use rand::rngs::StdRng;
+use rand::{RngExt, SeedableRng};
+
+// Cave maps occupy rows 0..height and columns 0..width. The outer ring is
+// always wall and only the largest connected floor area is kept, so every
+// floor cell is reachable from every other. `fill_prob` is clamped to
+// 0..=1, and NaN fills nothing.
+pub fn caves(
+    width: usize,
+    height: usize,
+    fill_prob: f64,
+    iterations: usize,
+    seed: u64,
+) -> Grid<Tile> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let fill_prob = if fill_prob.is_nan() {
+        0.0
+    } else {
+        fill_prob.clamp(0.0, 1.0)
+    };
+    let (width, height) = (width as isize, height as isize);
+    let border = |x: isize, y: isize| x == 0 || y == 0 || x == height - 1 || y == width - 1;
+
+    let mut grid = Grid::new();
+    for x in 0..height {
+        for y in 0..width {
+            let tile = if border(x, y) || rng.random_bool(fill_prob) {
+                Tile::Wall
+            } else {
+                Tile::Floor
+            };
+            grid.set(x, y, tile);
+        }
+    }
+
+    for _ in 0..iterations {
+        grid = grid.step(|grid, x, y| {
+            let tile = *grid.get(x, y)?;
+            let walls = grid.count_neighbors(x, y, |cell| cell != Some(&Tile::Floor));
+
+            if border(x, y) || walls >= 5 || (tile == Tile::Wall && walls == 4) {
+                Some(Tile::Wall)
+            } else {
+                Some(Tile::Floor)
+            }
+        });
+    }
+
+    remove_pockets(&mut grid);
+    grid
+}
+
+fn remove_pockets(grid: &mut Grid<Tile>) {
+    let mut regions = vec![];
+    let mut assigned = Grid::new();
+
+    for x in grid.min_x()..=grid.max_x() {
+        for y in grid.min_y()..=grid.max_y() {
+            if grid.get(x, y) == Some(&Tile::Floor) && assigned.get(x, y).is_none() {
+                let region = grid.flood_fill((x, y), |tile| *tile == Tile::Floor);
+                for &(x, y) in &region {
+                    assigned.set(x, y, ());
+                }
+                regions.push(region);
+            }
+        }
+    }
+
+    // The first region found wins ties, which keeps the output deterministic.
+    let largest = regions
+        .iter()
+        .enumerate()
+        .max_by_key(|(index, region)| (region.len(), std::cmp::Reverse(*index)))
+        .map(|(index, _)| index);
+
+    for (index, region) in regions.into_iter().enumerate() {
+        if Some(index) != largest {
+            for (x, y) in region {
+                grid.set(x, y, Tile::Wall);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::caves;
+    use crate::gen::Tile;
+
+    #[test]
+    fn caves_are_connected_and_enclosed() {
+        let grid = caves(40, 30, 0.45, 4, 3);
+
+        let floors: Vec<(isize, isize)> = (0..30)
+            .flat_map(|x| (0..40).map(move |y| (x, y)))
+            .filter(|&(x, y)| grid.get(x, y) == Some(&Tile::Floor))
+            .collect();
+
+        assert!(!floors.is_empty());
+        assert_eq!(
+            grid.flood_fill(floors[0], |tile| *tile == Tile::Floor)
+                .len(),
+            floors.len()
+        );
+
+        for &(x, y) in &floors {
+            assert!(x > 0 && y > 0 && x < 29 && y < 39);
+        }
+    }
+
+    #[test]
+    fn caves_are_deterministic() {
+        let a = caves(20, 20, 0.45, 3, 11);
+        let b = caves(20, 20, 0.45, 3, 11);
+
+        for x in 0..20 {
+            for y in 0..20 {
+                assert_eq!(a.get(x, y), b.get(x, y));
+            }
+        }
+
+        // Out-of-range and NaN fill probabilities do not panic.
+        for fill_prob in [f64::NAN, -1.0, 2.0] {
+            caves(10, 10, fill_prob, 1, 11);
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use super::Tile;
+use crate::grid::Grid;
+use crate::rect::Rect;
+use rand::rngs::StdRng;
+use rand::{Rng, RngExt, SeedableRng};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DungeonParams {
+    pub width: usize,
+    pub height: usize,
+    pub room_attempts: usize,
+    pub min_room_size: usize,
+    pub max_room_size: usize,
+    pub seed: u64,
+}
+
+impl Default for DungeonParams {
+    fn default() -> Self {
+        Self {
+            width: 80,
+            height: 50,
+            room_attempts: 30,
+            min_room_size: 4,
+            max_room_size: 10,
+            seed: 0,
+        }
+    }
+}
+
+// Places non-overlapping rooms at random and joins each one to the previous
+// room with an L-shaped corridor, so all floor is connected. Rooms keep at
+// least one wall cell between each other and the map edge.
+pub fn dungeon(params: &DungeonParams) -> (Grid<Tile>, Vec<Rect>) {
+    let mut rng = StdRng::seed_from_u64(params.seed);
+    let (width, height) = (params.width as isize, params.height as isize);
+
+    let mut grid = Grid::new();
+    for x in 0..height {
+        for y in 0..width {
+            grid.set(x, y, Tile::Wall);
+        }
+    }
+
+    let min_size = params.min_room_size.max(1) as i64;
+    let max_size = (params.max_room_size as i64).max(min_size);
+    let mut rooms: Vec<Rect> = vec![];
+
+    for _ in 0..params.room_attempts {
+        let room_height = rng.random_range(min_size..=max_size) as isize;
+        let room_width = rng.random_range(min_size..=max_size) as isize;
+        if room_height > height - 2 || room_width > width - 2 {
+            continue;
+        }
+
+        let x = rng.random_range(1..=(height - 1 - room_height) as i64) as isize;
+        let y = rng.random_range(1..=(width - 1 - room_width) as i64) as isize;
+        let room = Rect::new(x, y, x + room_height - 1, y + room_width - 1);
+
+        let margin = Rect::new(
+            room.min_x - 1,
+            room.min_y - 1,
+            room.max_x + 1,
+            room.max_y + 1,
+        );
+        if rooms.iter().any(|other| other.intersects(&margin)) {
+            continue;
+        }
+
+        for (x, y) in room.iter() {
+            grid.set(x, y, Tile::Floor);
+        }
+
+        if let Some(previous) = rooms.last() {
+            carve_corridor(&mut grid, previous.center(), room.center(), &mut rng);
+        }
+
+        rooms.push(room);
+    }
+
+    (grid, rooms)
+}
+
+fn carve_corridor<R>(grid: &mut Grid<Tile>, from: (isize, isize), to: (isize, isize), rng: &mut R)
+where
+    R: Rng + ?Sized,
+{
+    let corner = if rng.random_bool(0.5) {
+        (from.0, to.1)
+    } else {
+        (to.0, from.1)
+    };
+
+    for (a, b) in [(from, corner), (corner, to)] {
+        for (x, y) in Rect::new(a.0, a.1, b.0, b.1).iter() {
+            grid.set(x, y, Tile::Floor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dungeon, DungeonParams};
+    use crate::gen::Tile;
+
+    #[test]
+    fn rooms_are_separate_and_connected() {
+        let params = DungeonParams {
+            seed: 5,
+            ..DungeonParams::default()
+        };
+        let (grid, rooms) = dungeon(&params);
+
+        assert!(rooms.len() > 3);
+        for (i, room) in rooms.iter().enumerate() {
+            assert!(room
+                .iter()
+                .all(|(x, y)| grid.get(x, y) == Some(&Tile::Floor)));
+            assert!(rooms[i + 1..].iter().all(|other| !other.intersects(room)));
+        }
+
+        let floors = grid
+            .bounds()
+            .iter()
+            .filter(|&(x, y)| grid.get(x, y) == Some(&Tile::Floor))
+            .count();
+        let start = rooms[0].center();
+        assert_eq!(
+            grid.flood_fill(start, |tile| *tile == Tile::Floor).len(),
+            floors
+        );
+
+        for (x, y) in grid.bounds().iter() {
+            if x == 0 || y == 0 || x == 49 || y == 79 {
+                assert_eq!(grid.get(x, y), Some(&Tile::Wall));
+            }
+        }
+    }
+
+    #[test]
+    fn small_maps_without_room_for_rooms() {
+        let params = DungeonParams {
+            width: 3,
+            height: 3,
+            ..DungeonParams::default()
+        };
+        let (grid, rooms) = dungeon(&params);
+
+        assert!(rooms.is_empty());
+        assert_eq!(grid.get(1, 1), Some(&Tile::Wall));
+    }
+}
@@ -0,0 +1,269 @@
+use crate::direction::Direction;
+use crate::grid::Grid;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, RngExt, SeedableRng};
+
+// A maze cell records which of its four sides are open passages.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Cell {
+    passages: u8,
+}
+
+impl Cell {
+    fn bit(direction: Direction) -> u8 {
+        match direction {
+            Direction::North => 1,
+            Direction::East => 2,
+            Direction::South => 4,
+            Direction::West => 8,
+            _ => 0,
+        }
+    }
+
+    pub fn is_open(&self, direction: Direction) -> bool {
+        self.passages & Cell::bit(direction) != 0
+    }
+
+    pub fn open(&mut self, direction: Direction) {
+        assert!(!direction.is_diagonal(), "maze passages are cardinal");
+        self.passages |= Cell::bit(direction);
+    }
+
+    pub fn passages(&self) -> impl Iterator<Item = Direction> + '_ {
+        Direction::CARDINAL
+            .into_iter()
+            .filter(|direction| self.is_open(*direction))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    RecursiveBacktracker,
+    Prim,
+    Kruskal,
+}
+
+// Mazes occupy rows 0..height and columns 0..width, with every cell reachable
+// from every other through exactly one path.
+pub fn generate(width: usize, height: usize, algorithm: Algorithm, seed: u64) -> Grid<Cell> {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    match algorithm {
+        Algorithm::RecursiveBacktracker => recursive_backtracker(width, height, &mut rng),
+        Algorithm::Prim => prim(width, height, &mut rng),
+        Algorithm::Kruskal => kruskal(width, height, &mut rng),
+    }
+}
+
+struct Layout {
+    width: isize,
+    height: isize,
+}
+
+impl Layout {
+    fn new(width: usize, height: usize) -> (Self, Grid<Cell>) {
+        let mut grid = Grid::new();
+        for x in 0..height as isize {
+            for y in 0..width as isize {
+                grid.set(x, y, Cell::default());
+            }
+        }
+
+        let layout = Self {
+            width: width as isize,
+            height: height as isize,
+        };
+        (layout, grid)
+    }
+
+    fn len(&self) -> usize {
+        (self.width * self.height) as usize
+    }
+
+    fn index(&self, (x, y): (isize, isize)) -> usize {
+        (x * self.width + y) as usize
+    }
+
+    fn neighbor(&self, (x, y): (isize, isize), direction: Direction) -> Option<(isize, isize)> {
+        let (dx, dy) = direction.offset();
+        let (x, y) = (x + dx, y + dy);
+        (x >= 0 && x < self.height && y >= 0 && y < self.width).then_some((x, y))
+    }
+}
+
+fn carve(grid: &mut Grid<Cell>, from: (isize, isize), direction: Direction) {
+    let (dx, dy) = direction.offset();
+    grid.get_mut(from.0, from.1).unwrap().open(direction);
+    grid.get_mut(from.0 + dx, from.1 + dy)
+        .unwrap()
+        .open(direction.opposite());
+}
+
+pub fn recursive_backtracker<R>(width: usize, height: usize, rng: &mut R) -> Grid<Cell>
+where
+    R: Rng + ?Sized,
+{
+    let (layout, mut grid) = Layout::new(width, height);
+    if layout.len() == 0 {
+        return grid;
+    }
+
+    let mut visited = vec![false; layout.len()];
+    let mut stack = vec![(0, 0)];
+    visited[0] = true;
+
+    while let Some(&current) = stack.last() {
+        let unvisited: Vec<(Direction, (isize, isize))> = Direction::CARDINAL
+            .into_iter()
+            .filter_map(|direction| Some((direction, layout.neighbor(current, direction)?)))
+            .filter(|(_, neighbor)| !visited[layout.index(*neighbor)])
+            .collect();
+
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+
+        let (direction, next) = unvisited[rng.random_range(0..unvisited.len())];
+        carve(&mut grid, current, direction);
+        visited[layout.index(next)] = true;
+        stack.push(next);
+    }
+
+    grid
+}
+
+pub fn prim<R>(width: usize, height: usize, rng: &mut R) -> Grid<Cell>
+where
+    R: Rng + ?Sized,
+{
+    let (layout, mut grid) = Layout::new(width, height);
+    if layout.len() == 0 {
+        return grid;
+    }
+
+    let mut visited = vec![false; layout.len()];
+    let mut frontier = vec![];
+
+    let visit = |cell: (isize, isize), visited: &mut [bool], frontier: &mut Vec<_>| {
+        visited[layout.index(cell)] = true;
+        for direction in Direction::CARDINAL {
+            if layout.neighbor(cell, direction).is_some() {
+                frontier.push((cell, direction));
+            }
+        }
+    };
+    visit((0, 0), &mut visited, &mut frontier);
+
+    while !frontier.is_empty() {
+        let (cell, direction) = frontier.swap_remove(rng.random_range(0..frontier.len()));
+        let next = layout.neighbor(cell, direction).unwrap();
+
+        if !visited[layout.index(next)] {
+            carve(&mut grid, cell, direction);
+            visit(next, &mut visited, &mut frontier);
+        }
+    }
+
+    grid
+}
+
+pub fn kruskal<R>(width: usize, height: usize, rng: &mut R) -> Grid<Cell>
+where
+    R: Rng + ?Sized,
+{
+    let (layout, mut grid) = Layout::new(width, height);
+
+    let mut walls = vec![];
+    for x in 0..layout.height {
+        for y in 0..layout.width {
+            for direction in [Direction::East, Direction::South] {
+                if layout.neighbor((x, y), direction).is_some() {
+                    walls.push(((x, y), direction));
+                }
+            }
+        }
+    }
+    walls.shuffle(rng);
+
+    let mut parents: Vec<usize> = (0..layout.len()).collect();
+    fn root(parents: &mut [usize], mut index: usize) -> usize {
+        while parents[index] != index {
+            parents[index] = parents[parents[index]];
+            index = parents[index];
+        }
+        index
+    }
+
+    for (cell, direction) in walls {
+        let next = layout.neighbor(cell, direction).unwrap();
+        let a = root(&mut parents, layout.index(cell));
+        let b = root(&mut parents, layout.index(next));
+
+        if a != b {
+            parents[a] = b;
+            carve(&mut grid, cell, direction);
+        }
+    }
+
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate, Algorithm, Cell};
+    use crate::grid::Grid;
+
+    fn assert_perfect(maze: &Grid<Cell>, width: isize, height: isize) {
+        let mut seen = vec![(0, 0)];
+        let mut stack = vec![(0, 0)];
+        let mut passages = 0;
+
+        while let Some((x, y)) = stack.pop() {
+            for direction in maze.get(x, y).unwrap().passages() {
+                passages += 1;
+                let (dx, dy) = direction.offset();
+                let next = (x + dx, y + dy);
+                assert!(maze
+                    .get(next.0, next.1)
+                    .unwrap()
+                    .is_open(direction.opposite()));
+
+                if !seen.contains(&next) {
+                    seen.push(next);
+                    stack.push(next);
+                }
+            }
+        }
+
+        assert_eq!(seen.len() as isize, width * height);
+        assert_eq!(passages / 2, width * height - 1);
+    }
+
+    #[test]
+    fn algorithms_produce_perfect_mazes() {
+        for algorithm in [
+            Algorithm::RecursiveBacktracker,
+            Algorithm::Prim,
+            Algorithm::Kruskal,
+        ] {
+            let maze = generate(12, 7, algorithm, 42);
+
+            assert_eq!((maze.max_x(), maze.max_y()), (6, 11));
+            assert_perfect(&maze, 12, 7);
+        }
+    }
+
+    #[test]
+    fn seeds_are_deterministic() {
+        let a = generate(8, 8, Algorithm::Prim, 7);
+        let b = generate(8, 8, Algorithm::Prim, 7);
+
+        for x in 0..8 {
+            for y in 0..8 {
+                assert_eq!(a.get(x, y), b.get(x, y));
+            }
+        }
+    }
+}
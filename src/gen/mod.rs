@@ -0,0 +1,13 @@
+mod caves;
+mod dungeon;
+pub mod maze;
+pub mod wfc;
+
+pub use caves::caves;
+pub use dungeon::{dungeon, DungeonParams};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Tile {
+    Wall,
+    Floor,
+}
@@ -0,0 +1,341 @@
+use crate::direction::Direction;
+use crate::grid::Grid;
+use rand::rngs::StdRng;
+use rand::{Rng, RngExt, SeedableRng};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+const ATTEMPTS: usize = 10;
+
+// The constraint problem shared by both models: every position of the wave
+// ends up holding one pattern, and neighboring positions must hold patterns
+// that are compatible in that direction.
+struct Model {
+    weights: Vec<f64>,
+    // compatible[direction][p][q]: q may sit next to p in that direction.
+    compatible: Vec<Vec<Vec<bool>>>,
+    height: isize,
+    width: isize,
+}
+
+impl Model {
+    fn solve<R>(&self, rng: &mut R) -> Option<Vec<usize>>
+    where
+        R: Rng + ?Sized,
+    {
+        let patterns = self.weights.len();
+        let positions = (self.height * self.width) as usize;
+        let mut wave = vec![vec![true; patterns]; positions];
+
+        loop {
+            let mut best = None;
+            let mut best_entropy = f64::INFINITY;
+
+            for (position, possible) in wave.iter().enumerate() {
+                let options = possible.iter().filter(|possible| **possible).count();
+                if options == 0 {
+                    return None;
+                }
+                if options == 1 {
+                    continue;
+                }
+
+                let (mut sum, mut weighted_log) = (0.0, 0.0);
+                for (pattern, _) in possible.iter().enumerate().filter(|(_, p)| **p) {
+                    let weight = self.weights[pattern];
+                    sum += weight;
+                    weighted_log += weight * weight.ln();
+                }
+
+                let entropy = sum.ln() - weighted_log / sum + rng.random::<f64>() * 1e-6;
+                if entropy < best_entropy {
+                    best_entropy = entropy;
+                    best = Some(position);
+                }
+            }
+
+            let Some(position) = best else {
+                return wave
+                    .iter()
+                    .map(|possible| possible.iter().position(|p| *p))
+                    .collect();
+            };
+
+            let total: f64 = (0..patterns)
+                .filter(|pattern| wave[position][*pattern])
+                .map(|pattern| self.weights[pattern])
+                .sum();
+            let mut pick = rng.random::<f64>() * total;
+            let mut chosen = 0;
+            for pattern in (0..patterns).filter(|pattern| wave[position][*pattern]) {
+                chosen = pattern;
+                pick -= self.weights[pattern];
+                if pick <= 0.0 {
+                    break;
+                }
+            }
+
+            for (pattern, possible) in wave[position].iter_mut().enumerate() {
+                *possible = pattern == chosen;
+            }
+
+            if !self.propagate(&mut wave, position) {
+                return None;
+            }
+        }
+    }
+
+    fn propagate(&self, wave: &mut [Vec<bool>], start: usize) -> bool {
+        let mut stack = vec![start];
+
+        while let Some(position) = stack.pop() {
+            let (x, y) = (
+                position as isize / self.width,
+                position as isize % self.width,
+            );
+
+            for (d, direction) in Direction::CARDINAL.into_iter().enumerate() {
+                let (dx, dy) = direction.offset();
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 0 || ny < 0 || nx >= self.height || ny >= self.width {
+                    continue;
+                }
+
+                let neighbor = (nx * self.width + ny) as usize;
+                let mut changed = false;
+
+                for q in 0..self.weights.len() {
+                    if !wave[neighbor][q] {
+                        continue;
+                    }
+
+                    let supported = (0..self.weights.len())
+                        .any(|p| wave[position][p] && self.compatible[d][p][q]);
+                    if !supported {
+                        wave[neighbor][q] = false;
+                        changed = true;
+                    }
+                }
+
+                if changed {
+                    if !wave[neighbor].iter().any(|p| *p) {
+                        return false;
+                    }
+                    stack.push(neighbor);
+                }
+            }
+        }
+
+        true
+    }
+
+    fn run(&self, seed: u64) -> Option<Vec<usize>> {
+        if self.weights.is_empty() || self.height <= 0 || self.width <= 0 {
+            return None;
+        }
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..ATTEMPTS).find_map(|_| self.solve(&mut rng))
+    }
+}
+
+// Learns which values may touch in each direction from the example and
+// generates a grid of rows 0..height and columns 0..width, appearing with
+// the example's frequencies. Returns None if every attempt hits a
+// contradiction.
+pub fn tiled<T>(example: &Grid<T>, width: usize, height: usize, seed: u64) -> Option<Grid<T>>
+where
+    T: Clone + Eq + Hash,
+{
+    let mut tiles: Vec<T> = vec![];
+    let mut indices = HashMap::new();
+    let mut weights = vec![];
+
+    for (x, y) in example.bounds().iter() {
+        if let Some(tile) = example.get(x, y) {
+            let index = *indices.entry(tile.clone()).or_insert_with(|| {
+                tiles.push(tile.clone());
+                weights.push(0.0);
+                tiles.len() - 1
+            });
+            weights[index] += 1.0;
+        }
+    }
+
+    let mut compatible = vec![vec![vec![false; tiles.len()]; tiles.len()]; 4];
+    for (x, y) in example.bounds().iter() {
+        let Some(tile) = example.get(x, y) else {
+            continue;
+        };
+
+        for (d, direction) in Direction::CARDINAL.into_iter().enumerate() {
+            let (dx, dy) = direction.offset();
+            if let Some(neighbor) = example.get(x + dx, y + dy) {
+                compatible[d][indices[tile]][indices[neighbor]] = true;
+            }
+        }
+    }
+
+    let model = Model {
+        weights,
+        compatible,
+        height: height as isize,
+        width: width as isize,
+    };
+    let solution = model.run(seed)?;
+
+    let mut grid = Grid::new();
+    for (position, tile) in solution.into_iter().enumerate() {
+        let (x, y) = (
+            position as isize / model.width,
+            position as isize % model.width,
+        );
+        grid.set(x, y, tiles[tile].clone());
+    }
+
+    Some(grid)
+}
+
+// Every `pattern_size` x `pattern_size` window of the output also occurs in
+// the example. Windows of the example containing empty cells are ignored.
+pub fn overlapping<T>(
+    example: &Grid<T>,
+    pattern_size: usize,
+    width: usize,
+    height: usize,
+    seed: u64,
+) -> Option<Grid<T>>
+where
+    T: Clone + Eq + Hash,
+{
+    let n = pattern_size as isize;
+    if n == 0 || width < pattern_size || height < pattern_size {
+        return None;
+    }
+
+    let mut patterns: Vec<Vec<T>> = vec![];
+    let mut indices = HashMap::new();
+    let mut weights = vec![];
+
+    let bounds = example.bounds();
+    for x in bounds.min_x..=bounds.max_x - n + 1 {
+        for y in bounds.min_y..=bounds.max_y - n + 1 {
+            let window: Option<Vec<T>> = (0..n)
+                .flat_map(|dx| (0..n).map(move |dy| (dx, dy)))
+                .map(|(dx, dy)| example.get(x + dx, y + dy).cloned())
+                .collect();
+
+            if let Some(window) = window {
+                let index = *indices.entry(window.clone()).or_insert_with(|| {
+                    patterns.push(window);
+                    weights.push(0.0);
+                    patterns.len() - 1
+                });
+                weights[index] += 1.0;
+            }
+        }
+    }
+
+    let agrees = |p: &[T], q: &[T], dx: isize, dy: isize| {
+        (0..n).all(|x| {
+            (0..n).all(|y| {
+                let (qx, qy) = (x - dx, y - dy);
+                qx < 0
+                    || qy < 0
+                    || qx >= n
+                    || qy >= n
+                    || p[(x * n + y) as usize] == q[(qx * n + qy) as usize]
+            })
+        })
+    };
+
+    let compatible = Direction::CARDINAL
+        .into_iter()
+        .map(|direction| {
+            let (dx, dy) = direction.offset();
+            patterns
+                .iter()
+                .map(|p| patterns.iter().map(|q| agrees(p, q, dx, dy)).collect())
+                .collect()
+        })
+        .collect();
+
+    let model = Model {
+        weights,
+        compatible,
+        height: height as isize - n + 1,
+        width: width as isize - n + 1,
+    };
+    let solution = model.run(seed)?;
+
+    // Each output cell is read from the last pattern position covering it.
+    let mut grid = Grid::new();
+    for x in 0..height as isize {
+        for y in 0..width as isize {
+            let (px, py) = (x.min(model.height - 1), y.min(model.width - 1));
+            let pattern = &patterns[solution[(px * model.width + py) as usize]];
+            grid.set(x, y, pattern[((x - px) * n + (y - py)) as usize].clone());
+        }
+    }
+
+    Some(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{overlapping, tiled};
+    use crate::grid::Grid;
+    use std::collections::HashSet;
+
+    #[test]
+    fn tiled_model_keeps_adjacency_rules() {
+        let mut example = Grid::new();
+        for x in 0..4 {
+            for y in 0..4 {
+                example.set(x, y, (x + y) % 2 == 0);
+            }
+        }
+
+        let output = tiled(&example, 10, 6, 1).unwrap();
+        assert_eq!((output.max_x(), output.max_y()), (5, 9));
+
+        for (x, y) in output.bounds().iter() {
+            let cell = output.get(x, y).unwrap();
+            if let Some(right) = output.get(x, y + 1) {
+                assert_ne!(cell, right);
+            }
+            if let Some(below) = output.get(x + 1, y) {
+                assert_ne!(cell, below);
+            }
+        }
+    }
+
+    #[test]
+    fn overlapping_model_reuses_example_windows() {
+        let rows = ["..#..", ".###.", "..#..", ".....", "....."];
+        let mut example = Grid::new();
+        for (x, row) in rows.iter().enumerate() {
+            for (y, cell) in row.chars().enumerate() {
+                example.set(x as isize, y as isize, cell);
+            }
+        }
+
+        let windows = |grid: &Grid<char>| {
+            let mut windows = HashSet::new();
+            for x in grid.min_x()..grid.max_x() {
+                for y in grid.min_y()..grid.max_y() {
+                    let window: Vec<char> = [(0, 0), (0, 1), (1, 0), (1, 1)]
+                        .iter()
+                        .map(|(dx, dy)| *grid.get(x + dx, y + dy).unwrap())
+                        .collect();
+                    windows.insert(window);
+                }
+            }
+            windows
+        };
+
+        let output = overlapping(&example, 2, 12, 12, 7).unwrap();
+        assert!(windows(&output).is_subset(&windows(&example)));
+        assert_eq!(output.bounds().area(), 144);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::connectivity::{step, Connectivity};
+use crate::grid::Grid;
+use std::collections::HashMap;
+
+#[cfg(feature = "petgraph")]
+use petgraph::graph::{DiGraph, NodeIndex};
+
+type Cell = (isize, isize);
+
+// The stored cells of a grid as a directed weighted graph, for algorithms
+// this crate does not ship. Nodes are numbered in row-major order of their
+// cells. With the petgraph feature it converts into a `petgraph` `DiGraph`
+// with the same node numbering, carrying cells as node weights.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GridGraph {
+    cells: Vec<Cell>,
+    index: HashMap<Cell, usize>,
+    adjacent: Vec<Vec<(usize, f64)>>,
+}
+
+impl GridGraph {
+    pub fn node_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.adjacent.iter().map(Vec::len).sum()
+    }
+
+    pub fn cell(&self, node: usize) -> Option<Cell> {
+        self.cells.get(node).copied()
+    }
+
+    pub fn node(&self, x: isize, y: isize) -> Option<usize> {
+        self.index.get(&(x, y)).copied()
+    }
+
+    // The edges leaving `node` as target nodes with weights.
+    pub fn neighbors(&self, node: usize) -> &[(usize, f64)] {
+        self.adjacent.get(node).map_or(&[], Vec::as_slice)
+    }
+
+    // Every edge as `(from, to, weight)`, ordered by `from`.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+        self.adjacent
+            .iter()
+            .enumerate()
+            .flat_map(|(from, edges)| edges.iter().map(move |&(to, weight)| (from, to, weight)))
+    }
+}
+
+#[cfg(feature = "petgraph")]
+impl From<&GridGraph> for DiGraph<Cell, f64> {
+    fn from(graph: &GridGraph) -> Self {
+        let mut converted = Self::with_capacity(graph.node_count(), graph.edge_count());
+        for &cell in &graph.cells {
+            converted.add_node(cell);
+        }
+        for (from, to, weight) in graph.edges() {
+            converted.add_edge(NodeIndex::new(from), NodeIndex::new(to), weight);
+        }
+        converted
+    }
+}
+
+impl<T> Grid<T> {
+    // Builds a graph over the stored cells with an edge from each cell to a
+    // 4-connected neighbor wherever `edge(from, to)` gives a weight. Both
+    // directions are asked about separately, so one-way edges are possible.
+    pub fn to_graph<F>(&self, edge: F) -> GridGraph
+    where
+        F: FnMut((Cell, &T), (Cell, &T)) -> Option<f64>,
+    {
+        self.to_graph_with(&Connectivity::Four, edge)
+    }
+
+    pub fn to_graph_with<F>(&self, connectivity: &Connectivity, mut edge: F) -> GridGraph
+    where
+        F: FnMut((Cell, &T), (Cell, &T)) -> Option<f64>,
+    {
+        let mut graph = GridGraph::default();
+        for (node, (cell, _)) in self.iter().enumerate() {
+            graph.cells.push(cell);
+            graph.index.insert(cell, node);
+        }
+
+        let offsets = connectivity.offsets();
+        for ((x, y), item) in self.iter() {
+            let mut edges = vec![];
+            for &(dx, dy) in &offsets {
+                let Some(next) = step((x, y), (dx, dy)) else {
+                    continue;
+                };
+                // Unstored neighbors read as the default but have no node.
+                let next = self.wrap(next.0, next.1);
+                let (Some(&node), Some(neighbor)) =
+                    (graph.index.get(&next), self.get(next.0, next.1))
+                else {
+                    continue;
+                };
+                if let Some(weight) = edge(((x, y), item), (next, neighbor)) {
+                    edges.push((node, weight));
+                }
+            }
+            graph.adjacent.push(edges);
+        }
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::Grid;
+
+    fn parse(rows: &[&str]) -> Grid<char> {
+        let mut grid = Grid::new();
+        for (x, row) in rows.iter().enumerate() {
+            for (y, cell) in row.chars().enumerate() {
+                grid.set(x as isize, y as isize, cell);
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn exports_passable_edges() {
+        // Entering `~` costs more; walls have no edges.
+        let grid = parse(&["..#", ".~."]);
+        let graph = grid.to_graph(|(_, from), (_, to)| match (from, to) {
+            ('#', _) | (_, '#') => None,
+            (_, '~') => Some(3.0),
+            _ => Some(1.0),
+        });
+
+        assert_eq!(graph.node_count(), 6);
+        assert_eq!(graph.edge_count(), 10);
+        let corner = graph.node(0, 0).unwrap();
+        assert_eq!(graph.cell(corner), Some((0, 0)));
+        assert_eq!(
+            graph.neighbors(graph.node(1, 2).unwrap()),
+            [(graph.node(1, 1).unwrap(), 3.0)]
+        );
+        assert!(graph.neighbors(graph.node(0, 2).unwrap()).is_empty());
+
+        let mut sparse = Grid::new_with_default('.');
+        sparse.set(0, 0, 'a');
+        sparse.set(0, 1, 'b');
+        let graph = sparse.to_graph(|_, _| Some(1.0));
+        assert_eq!((graph.node_count(), graph.edge_count()), (2, 2));
+    }
+
+    #[cfg(feature = "petgraph")]
+    #[test]
+    fn converts_to_petgraph() {
+        use petgraph::graph::{DiGraph, NodeIndex};
+
+        let grid = parse(&["...", ".#.", "..."]);
+        let graph = grid.to_graph(|(_, from), (_, to)| (*from == '.' && *to == '.').then_some(1.0));
+        let converted = DiGraph::from(&graph);
+        let start = graph.node(0, 0).unwrap();
+        let costs = petgraph::algo::dijkstra(&converted, NodeIndex::new(start), None, |edge| {
+            *edge.weight()
+        });
+        assert_eq!(costs[&NodeIndex::new(graph.node(2, 2).unwrap())], 4.0);
+        assert_eq!(converted[NodeIndex::new(start)], (0, 0));
+    }
+}
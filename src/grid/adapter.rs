@@ -0,0 +1,119 @@
+use super::{GridRead, GridWrite};
+use crate::rect::Rect;
+
+// Both helpers work in i128 so bounds spanning most of the coordinate space
+// cannot overflow; the results lie within the bounds again.
+fn wrap(value: isize, min: isize, max: isize) -> isize {
+    let (value, min, max) = (value as i128, min as i128, max as i128);
+    (min + (value - min).rem_euclid(max - min + 1)) as isize
+}
+
+// Reflects with the edge cell repeated, so the row past `max` reads `max`
+// again, then `max - 1` and so on.
+fn mirror(value: isize, min: isize, max: isize) -> isize {
+    let (value, min, max) = (value as i128, min as i128, max as i128);
+    let len = max - min + 1;
+    let offset = (value - min).rem_euclid(2 * len);
+    (min + if offset < len {
+        offset
+    } else {
+        2 * len - 1 - offset
+    }) as isize
+}
+
+// Each adapter owns a grid (or a reference to one) and maps coordinates
+// outside the grid's current bounds back inside before every read and write,
+// so callers can pick the edge behavior of a convolution or automaton per
+// call site. `bounds` still reports the inner grid's bounds.
+macro_rules! edge_adapter {
+    ($name:ident, $map:expr) => {
+        #[derive(Clone, Copy, Debug, Default)]
+        pub struct $name<G>(pub G);
+
+        impl<G> $name<G> {
+            pub fn new(grid: G) -> Self {
+                Self(grid)
+            }
+
+            pub fn into_inner(self) -> G {
+                self.0
+            }
+
+            fn resolve(bounds: Rect, x: isize, y: isize) -> (isize, isize) {
+                let map: fn(isize, isize, isize) -> isize = $map;
+                (
+                    map(x, bounds.min_x, bounds.max_x),
+                    map(y, bounds.min_y, bounds.max_y),
+                )
+            }
+        }
+
+        impl<T, G: GridRead<T>> GridRead<T> for $name<G> {
+            fn get(&self, x: isize, y: isize) -> Option<&T> {
+                let (x, y) = Self::resolve(self.0.bounds(), x, y);
+                self.0.get(x, y)
+            }
+
+            fn bounds(&self) -> Rect {
+                self.0.bounds()
+            }
+        }
+
+        impl<T, G: GridWrite<T>> GridWrite<T> for $name<G> {
+            fn set(&mut self, x: isize, y: isize, item: T) {
+                let (x, y) = Self::resolve(self.0.bounds(), x, y);
+                self.0.set(x, y, item);
+            }
+
+            fn remove(&mut self, x: isize, y: isize) -> Option<T> {
+                let (x, y) = Self::resolve(self.0.bounds(), x, y);
+                self.0.remove(x, y)
+            }
+        }
+    };
+}
+
+// Opposite edges touch, as on a torus.
+edge_adapter!(Wrapping, wrap);
+// Coordinates past an edge read and write the edge cell.
+edge_adapter!(Clamped, |value, min, max| value.clamp(min, max));
+// The grid is reflected at each edge.
+edge_adapter!(Mirrored, mirror);
+
+#[cfg(test)]
+mod tests {
+    use super::{mirror, wrap, Clamped, Mirrored, Wrapping};
+    use crate::grid::{Grid, GridRead, GridWrite};
+
+    #[test]
+    fn maps_coordinates_by_policy() {
+        let mut grid = Grid::new();
+        for y in 0..3 {
+            grid.set(0, y, y);
+        }
+
+        fn row(adapter: impl GridRead<isize>) -> Vec<isize> {
+            (-4..=5).map(|y| *adapter.get(0, y).unwrap()).collect()
+        }
+        assert_eq!(row(Wrapping(&grid)), [2, 0, 1, 2, 0, 1, 2, 0, 1, 2]);
+        assert_eq!(row(Clamped(&grid)), [0, 0, 0, 0, 0, 1, 2, 2, 2, 2]);
+        assert_eq!(row(Mirrored(&grid)), [2, 2, 1, 0, 0, 1, 2, 2, 1, 0]);
+
+        assert_eq!(wrap(isize::MIN, isize::MIN, isize::MAX), isize::MIN);
+        assert_eq!(wrap(isize::MIN, 0, isize::MAX), 0);
+        assert_eq!(mirror(isize::MIN, 0, isize::MAX), isize::MAX);
+        assert_eq!(mirror(isize::MAX, isize::MIN, isize::MAX), isize::MAX);
+    }
+
+    #[test]
+    fn writes_land_inside() {
+        let mut grid = Grid::new();
+        grid.set(2, 2, 'a');
+
+        let mut wrapping = Wrapping::new(&mut grid);
+        wrapping.set(-1, 3, 'b');
+        assert_eq!(wrapping.remove(5, 5), Some('a'));
+        assert_eq!(grid.get(2, 0), Some(&'b'));
+        assert_eq!(grid.len(), 1);
+    }
+}
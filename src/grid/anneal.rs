@@ -0,0 +1,189 @@
+use super::Grid;
+use rand::{Rng, RngExt};
+
+// A change `optimize` tries: exchanging two cells, either of which may be
+// empty, or setting one cell, e.g. to flip it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Mutation<T> {
+    Swap((isize, isize), (isize, isize)),
+    Set(isize, isize, T),
+}
+
+// The cooling schedule of `optimize`. The temperature falls geometrically
+// from `start_temperature` to `end_temperature` over the iterations; a
+// worse layout is accepted with probability exp(loss / temperature), so at
+// temperature 0 the search is plain hill climbing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Annealing {
+    pub iterations: usize,
+    pub start_temperature: f64,
+    pub end_temperature: f64,
+}
+
+impl Annealing {
+    pub fn new(iterations: usize, start_temperature: f64, end_temperature: f64) -> Self {
+        Self {
+            iterations,
+            start_temperature,
+            end_temperature,
+        }
+    }
+
+    pub fn hill_climbing(iterations: usize) -> Self {
+        Self::new(iterations, 0.0, 0.0)
+    }
+
+    fn temperature(&self, iteration: usize) -> f64 {
+        if self.start_temperature <= 0.0 {
+            return 0.0;
+        }
+        let progress = iteration as f64 / self.iterations.max(1) as f64;
+        let end = self.end_temperature.max(f64::MIN_POSITIVE);
+        self.start_temperature * (end / self.start_temperature).powf(progress)
+    }
+}
+
+// What undoes a mutation.
+enum Undo<T> {
+    Swap((isize, isize), (isize, isize)),
+    Restore(isize, isize, Option<T>),
+}
+
+impl<T: Clone> Grid<T> {
+    fn mutate(&mut self, mutation: Mutation<T>) -> Undo<T> {
+        match mutation {
+            Mutation::Swap(a, b) => {
+                self.swap(a, b);
+                Undo::Swap(a, b)
+            }
+            Mutation::Set(x, y, item) => {
+                let previous = self.remove(x, y);
+                self.set(x, y, item);
+                Undo::Restore(x, y, previous)
+            }
+        }
+    }
+
+    fn undo(&mut self, undo: Undo<T>) {
+        match undo {
+            Undo::Swap(a, b) => self.swap(a, b),
+            Undo::Restore(x, y, Some(item)) => self.set(x, y, item),
+            Undo::Restore(x, y, None) => {
+                self.remove(x, y);
+            }
+        }
+    }
+
+    // Simulated annealing towards a higher `score`, for layout problems such
+    // as placing facilities or arranging keys. Each iteration applies the
+    // mutation `propose` picks and keeps it or undoes it under the schedule,
+    // rescoring the whole grid. The grid is left at the best layout seen,
+    // whose score is returned.
+    pub fn optimize<R, P, S>(
+        &mut self,
+        rng: &mut R,
+        schedule: Annealing,
+        mut propose: P,
+        mut score: S,
+    ) -> f64
+    where
+        R: Rng + ?Sized,
+        P: FnMut(&Grid<T>, &mut R) -> Mutation<T>,
+        S: FnMut(&Grid<T>) -> f64,
+    {
+        let mut current = score(self);
+        let mut best = (current, self.clone());
+
+        for iteration in 0..schedule.iterations {
+            let mutation = propose(self, rng);
+            let undo = self.mutate(mutation);
+
+            let candidate = score(self);
+            let temperature = schedule.temperature(iteration);
+            let accepted = candidate >= current
+                || temperature > 0.0
+                    && rng.random_bool(((candidate - current) / temperature).exp().min(1.0));
+            if accepted {
+                current = candidate;
+                if current > best.0 {
+                    best = (current, self.clone());
+                }
+                continue;
+            }
+
+            self.undo(undo);
+        }
+
+        *self = best.1;
+        best.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Annealing, Mutation};
+    use crate::grid::Grid;
+    use crate::rect::Rect;
+    use rand::rngs::StdRng;
+    use rand::{RngExt, SeedableRng};
+
+    fn random_cell(rng: &mut StdRng) -> (isize, isize) {
+        let (x, y): (i32, i32) = (rng.random_range(0..6), rng.random_range(0..6));
+        (x as isize, y as isize)
+    }
+
+    #[test]
+    fn sorts_by_swapping() {
+        // Heavy values should end up in the top rows.
+        let mut grid = Grid::new();
+        let mut rng = StdRng::seed_from_u64(4);
+        grid.fill_random(Rect::new(0, 0, 5, 5), &mut rng, |rng| {
+            rng.random_range(0..10)
+        });
+        let score = |grid: &Grid<i32>| -> f64 {
+            grid.iter()
+                .map(|((x, _), &value)| -(x as f64) * value as f64)
+                .sum()
+        };
+        let before = score(&grid);
+
+        let schedule = Annealing::new(4000, 20.0, 0.01);
+        let best = grid.optimize(
+            &mut rng,
+            schedule,
+            |_, rng| Mutation::Swap(random_cell(rng), random_cell(rng)),
+            score,
+        );
+        assert_eq!(best, score(&grid));
+        assert!(best > before);
+        let row_sum = |x| (0..6).map(|y| grid.get(x, y).unwrap()).sum::<i32>();
+        assert!(row_sum(0) > row_sum(5));
+    }
+
+    #[test]
+    fn hill_climbing_flips_towards_target() {
+        let mut grid = Grid::new();
+        grid.fill_rect(Rect::new(0, 0, 5, 5), false);
+        let target = |(x, y): (isize, isize)| (x + y) % 2 == 0;
+        let score = |grid: &Grid<bool>| {
+            grid.iter()
+                .filter(|&(position, &cell)| cell == target(position))
+                .count() as f64
+        };
+
+        let mut rng = StdRng::seed_from_u64(8);
+        let best = grid.optimize(
+            &mut rng,
+            Annealing::hill_climbing(600),
+            |grid, rng| {
+                let (x, y) = random_cell(rng);
+                Mutation::Set(x, y, !grid.get(x, y).unwrap())
+            },
+            score,
+        );
+        assert_eq!(best, 36.0);
+        assert!(grid
+            .iter()
+            .all(|(position, &cell)| cell == target(position)));
+    }
+}
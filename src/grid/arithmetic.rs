@@ -0,0 +1,154 @@
+use super::Grid;
+use crate::rect::Rect;
+use std::collections::BTreeMap;
+
+// Matrix-style arithmetic that visits stored cells only, so sparse fields
+// combine without being filled in. Empty cells read as their grid's default,
+// or zero without one. As matrices, x is the row and y the column.
+impl Grid<f64> {
+    // An empty result over this grid's bounds, keeping its maximum bounds and
+    // wrapping, with `default` as its default.
+    fn like(&self, default: Option<f64>) -> Grid<f64> {
+        let mut grid = Grid::with_boundaries_of(self);
+        grid.max_bounds = self.max_bounds;
+        grid.wrapping = self.wrapping;
+        if default.is_some() {
+            grid.default = default;
+            grid.is_default = |a, b| a == b;
+        }
+        grid
+    }
+
+    fn value(&self, x: isize, y: isize) -> f64 {
+        self.get(x, y).copied().unwrap_or(0.0)
+    }
+
+    // The cellwise sum; a cell is stored if either side stores it, and the
+    // default is the sum of the defaults.
+    pub fn add_sparse(&self, other: &Grid<f64>) -> Grid<f64> {
+        let default = match (self.default, other.default) {
+            (None, None) => None,
+            (a, b) => Some(a.unwrap_or(0.0) + b.unwrap_or(0.0)),
+        };
+        let mut sum = self.like(default);
+        for ((x, y), &value) in self.iter() {
+            sum.set(x, y, value + other.value(x, y));
+        }
+        for ((x, y), &value) in other.iter() {
+            if self.get_stored(x, y).is_none() {
+                sum.set(x, y, self.value(x, y) + value);
+            }
+        }
+        sum
+    }
+
+    // Every cell multiplied by `factor`, the default included.
+    pub fn scaled(&self, factor: f64) -> Grid<f64> {
+        let mut scaled = self.like(self.default.map(|default| default * factor));
+        for ((x, y), &value) in self.iter() {
+            scaled.set(x, y, value * factor);
+        }
+        scaled
+    }
+
+    // The matrix product with a dense right-hand side, costing one pass over
+    // the dense row for each stored cell here, so empty cells count as zero
+    // whatever the default. Only entries that received a product of two
+    // stored cells are stored; the bounds span this grid's rows and the
+    // dense side's columns.
+    pub fn mul_dense(&self, dense: &Grid<f64>) -> Grid<f64> {
+        let columns = dense.bounds();
+        let mut products = BTreeMap::new();
+        for ((x, k), &left) in self.iter() {
+            if !(columns.min_x..=columns.max_x).contains(&k) {
+                continue;
+            }
+            for y in columns.min_y..=columns.max_y {
+                if let Some(&right) = dense.get(k, y) {
+                    *products.entry((x, y)).or_insert(0.0) += left * right;
+                }
+            }
+        }
+
+        let bounds = self.bounds();
+        let mut product = Grid::with_bounds(Rect::new(
+            bounds.min_x,
+            columns.min_y,
+            bounds.max_x,
+            columns.max_y,
+        ));
+        for ((x, y), value) in products {
+            product.set(x, y, value);
+        }
+        product
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::{Grid, GridBuilder};
+    use crate::rect::Rect;
+
+    fn matrix(cells: &[((isize, isize), f64)]) -> Grid<f64> {
+        let mut grid = Grid::new();
+        for &((x, y), value) in cells {
+            grid.set(x, y, value);
+        }
+        grid
+    }
+
+    #[test]
+    fn combines_sparse_grids() {
+        let a = matrix(&[((0, 0), 2.0), ((1, 2), -1.0)]);
+        let b = matrix(&[((0, 0), 1.0), ((2, 1), 4.0)]);
+
+        let sum = a.add_sparse(&b);
+        assert_eq!(sum.len(), 3);
+        assert_eq!(sum.get(0, 0), Some(&3.0));
+        assert_eq!(sum.get(2, 1), Some(&4.0));
+        assert_eq!(sum.get(1, 1), None);
+        assert_eq!(a.scaled(0.5).get(1, 2), Some(&-0.5));
+        assert_eq!(a.scaled(0.5).len(), 2);
+
+        // [2 0 0; 0 0 -1] x [1 2; 3 4; 5 6]
+        let dense = matrix(&[
+            ((0, 0), 1.0),
+            ((0, 1), 2.0),
+            ((1, 0), 3.0),
+            ((1, 1), 4.0),
+            ((2, 0), 5.0),
+            ((2, 1), 6.0),
+        ]);
+        let product = a.mul_dense(&dense);
+        assert_eq!(product.len(), 4);
+        assert_eq!(product.get(0, 1), Some(&4.0));
+        assert_eq!(product.get(1, 0), Some(&-5.0));
+        assert_eq!(product.get(1, 1), Some(&-6.0));
+        assert_eq!(product.bounds(), Rect::new(0, 0, 1, 1));
+    }
+
+    #[test]
+    fn keeps_the_layout() {
+        let mut ones = Grid::new_with_default(1.0);
+        ones.set(0, 0, 3.0);
+        ones.set(4, 4, 0.0);
+        let scaled = ones.scaled(2.0);
+        assert_eq!(scaled.bounds(), ones.bounds());
+        assert_eq!(scaled.get(2, 2), Some(&2.0));
+        assert_eq!(scaled.get(0, 0), Some(&6.0));
+
+        let sum = ones.add_sparse(&matrix(&[((1, 1), 5.0)]));
+        assert_eq!(sum.get(1, 1), Some(&6.0));
+        assert_eq!(sum.get(0, 0), Some(&3.0));
+        assert_eq!(sum.get(3, 3), Some(&1.0));
+        assert_eq!(sum.len(), 3);
+
+        let torus = GridBuilder::new()
+            .wrapping(Rect::new(0, 0, 3, 3))
+            .build()
+            .unwrap();
+        let scaled = torus.scaled(2.0);
+        assert!(scaled.is_wrapping());
+        assert_eq!(scaled.max_bounds(), Some(Rect::new(0, 0, 3, 3)));
+    }
+}
@@ -0,0 +1,258 @@
+use super::Grid;
+use crate::error::GridError;
+use crate::rect::Rect;
+
+// The shift moving `from` to one past `to` along x (`step_x` 1) or y.
+fn offset(
+    to: (isize, isize),
+    from: (isize, isize),
+    step_x: isize,
+    step_y: isize,
+) -> Result<(isize, isize), GridError> {
+    let delta = |to: isize, step: isize, from: isize| to.checked_add(step)?.checked_sub(from);
+    delta(to.0, step_x, from.0)
+        .zip(delta(to.1, step_y, from.1))
+        .ok_or(GridError::CoordinateOverflow {
+            x: from.0,
+            y: from.1,
+        })
+}
+
+// The cell of `other` moved by (dx, dy), or the error naming it.
+fn shifted(x: isize, y: isize, dx: isize, dy: isize) -> Result<(isize, isize), GridError> {
+    x.checked_add(dx)
+        .zip(y.checked_add(dy))
+        .ok_or(GridError::CoordinateOverflow { x, y })
+}
+
+impl<T: Clone> Grid<T> {
+    // Copies the stored cells of `other` moved by (dx, dy) and grows the
+    // bounds to cover its moved bounds, including their empty parts. Nothing
+    // is copied if the moved bounds cannot be addressed.
+    fn paste(&mut self, other: &Grid<T>, dx: isize, dy: isize) -> Result<(), GridError> {
+        let bounds = other.bounds();
+        let (min_x, min_y) = shifted(bounds.min_x, bounds.min_y, dx, dy)?;
+        let (max_x, max_y) = shifted(bounds.max_x, bounds.max_y, dx, dy)?;
+        self.check_overflow(min_x, min_y)?;
+        self.check_overflow(max_x, max_y)?;
+
+        for ((x, y), item) in other.iter() {
+            self.try_set(x + dx, y + dy, item.clone())?;
+        }
+
+        self.update_boundaries(min_x, min_y);
+        self.update_boundaries(max_x, max_y);
+        Ok(())
+    }
+
+    fn placed(&self) -> Result<Grid<T>, GridError> {
+        let mut grid = self.derived(self.bounds());
+        grid.paste(self, 0, 0)?;
+        Ok(grid)
+    }
+
+    // Places the bounds of `other` directly after this grid's along x, with
+    // their smallest y aligned. Fails with `CoordinateOverflow` when the
+    // result would not fit in the coordinate space. The result keeps this
+    // grid's default, which `other`'s empty cells then read as too.
+    pub fn concat_x(&self, other: &Grid<T>) -> Result<Grid<T>, GridError> {
+        let mut grid = self.placed()?;
+        let (dx, dy) = offset((self.max_x, self.min_y), (other.min_x, other.min_y), 1, 0)?;
+        grid.paste(other, dx, dy)?;
+        Ok(grid)
+    }
+
+    pub fn concat_y(&self, other: &Grid<T>) -> Result<Grid<T>, GridError> {
+        let mut grid = self.placed()?;
+        let (dx, dy) = offset((self.min_x, self.max_y), (other.min_x, other.min_y), 0, 1)?;
+        grid.paste(other, dx, dy)?;
+        Ok(grid)
+    }
+
+    // Repeats the bounds `nx` times along x and `ny` times along y.
+    pub fn tile(&self, nx: usize, ny: usize) -> Result<Grid<T>, GridError> {
+        let bounds = self.bounds();
+        let (height, width) = (bounds.height() as isize, bounds.width() as isize);
+
+        if nx == 0 || ny == 0 {
+            return Ok(self.derived(Rect::new(0, 0, 0, 0)));
+        }
+
+        let mut grid = self.derived(bounds);
+        for i in 0..nx as isize {
+            for j in 0..ny as isize {
+                let overflow = GridError::CoordinateOverflow {
+                    x: bounds.max_x,
+                    y: bounds.max_y,
+                };
+                let dx = i.checked_mul(height).ok_or(overflow)?;
+                let dy = j.checked_mul(width).ok_or(overflow)?;
+                grid.paste(self, dx, dy)?;
+            }
+        }
+
+        Ok(grid)
+    }
+
+    // The stored cells inside `rect`, keeping the default. No rect gives an
+    // empty grid at the origin.
+    fn cropped(&self, rect: Option<Rect>) -> Grid<T> {
+        let Some(rect) = rect else {
+            return self.derived(Rect::new(0, 0, 0, 0));
+        };
+
+        let mut grid = self.derived(rect);
+        for ((x, y), item) in self.iter() {
+            if rect.contains(x, y) {
+                grid.set(x, y, item.clone());
+            }
+        }
+        grid
+    }
+
+    // Splits the bounds into the rows before `x` and the rows from `x` on.
+    // The halves keep their coordinates, so concatenating them again gives
+    // back this grid.
+    pub fn split_at_x(&self, x: isize) -> (Grid<T>, Grid<T>) {
+        let bounds = self.bounds();
+        let before = (x > bounds.min_x).then(|| Rect {
+            max_x: bounds.max_x.min(x - 1),
+            ..bounds
+        });
+        let after = (x <= bounds.max_x).then(|| Rect {
+            min_x: bounds.min_x.max(x),
+            ..bounds
+        });
+        (self.cropped(before), self.cropped(after))
+    }
+
+    pub fn split_at_y(&self, y: isize) -> (Grid<T>, Grid<T>) {
+        let bounds = self.bounds();
+        let before = (y > bounds.min_y).then(|| Rect {
+            max_y: bounds.max_y.min(y - 1),
+            ..bounds
+        });
+        let after = (y <= bounds.max_y).then(|| Rect {
+            min_y: bounds.min_y.max(y),
+            ..bounds
+        });
+        (self.cropped(before), self.cropped(after))
+    }
+
+    // Splits the bounds just after their center, in the order top left, top
+    // right, bottom left, bottom right. Bounds one cell thin leave the later
+    // halves empty.
+    pub fn split_quadrants(&self) -> [Grid<T>; 4] {
+        let (x, y) = self.bounds().center();
+        let (top, bottom) = self.split_at_x(x.saturating_add(1));
+        let (top_left, top_right) = top.split_at_y(y.saturating_add(1));
+        let (bottom_left, bottom_right) = bottom.split_at_y(y.saturating_add(1));
+        [top_left, top_right, bottom_left, bottom_right]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::error::GridError;
+    use crate::grid::GridBuilder;
+    use crate::rect::Rect;
+
+    fn chunk(rows: &[&str], x: isize, y: isize) -> Grid<char> {
+        let (height, width) = (rows.len() as isize, rows[0].len() as isize);
+        let bounds = Rect::new(x, y, x + height - 1, y + width - 1);
+        let mut grid = GridBuilder::new().bounds(bounds).build().unwrap();
+        for (dx, row) in rows.iter().enumerate() {
+            for (dy, cell) in row.chars().enumerate() {
+                if cell != '.' {
+                    grid.set(x + dx as isize, y + dy as isize, cell);
+                }
+            }
+        }
+        grid
+    }
+
+    fn rows(grid: &Grid<char>) -> Vec<String> {
+        let bounds = grid.bounds();
+        (bounds.min_x..=bounds.max_x)
+            .map(|x| {
+                (bounds.min_y..=bounds.max_y)
+                    .map(|y| grid.get(x, y).copied().unwrap_or('.'))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn concatenates_chunks() {
+        let a = chunk(&["ab", "c."], 0, 0);
+        let b = chunk(&["xy", "z."], -7, 3);
+
+        assert_eq!(rows(&a.concat_x(&b).unwrap()), ["ab", "c.", "xy", "z."]);
+        assert_eq!(rows(&a.concat_y(&b).unwrap()), ["abxy", "c.z."]);
+        assert_eq!(a.concat_y(&b).unwrap().bounds(), Rect::new(0, 0, 1, 3));
+    }
+
+    #[test]
+    fn tiles_with_empty_cells() {
+        let a = chunk(&["a.", ".."], -1, 4);
+        let tiled = a.tile(2, 3).unwrap();
+
+        assert_eq!(tiled.bounds(), Rect::new(-1, 4, 2, 9));
+        assert_eq!(rows(&tiled), ["a.a.a.", "......", "a.a.a.", "......"]);
+        assert_eq!(a.tile(0, 5).unwrap().get(-1, 4), None);
+    }
+
+    #[test]
+    fn pastes_stored_cells_only() {
+        let mut sparse = Grid::new();
+        sparse.set(0, 0, 'a');
+        sparse.set(100_000, 100_000, 'b');
+        let joined = sparse.concat_x(&sparse).unwrap();
+        assert_eq!(joined.len(), 4);
+        assert_eq!(joined.get(200_001, 100_000), Some(&'b'));
+
+        let mut defaults = Grid::new_with_default('.');
+        defaults.set(0, 0, 'a');
+        defaults.set(2, 2, 'b');
+        let tiled = defaults.tile(2, 2).unwrap();
+        assert_eq!(tiled.len(), 8);
+        assert_eq!(tiled.default_value(), Some(&'.'));
+        assert_eq!(tiled.get(4, 4), Some(&'.'));
+        let mut other = Grid::new_with_default('#');
+        other.set(0, 1, 'c');
+        let joined = defaults.concat_y(&other).unwrap();
+        assert_eq!(joined.default_value(), Some(&'.'));
+        assert_eq!(joined.get(0, 4), Some(&'c'));
+        assert_eq!(joined.get(0, 3), Some(&'.'));
+
+        // Bounds reaching halfway across the coordinate space cannot be
+        // placed after one another.
+        let far: Grid<char> = Grid::with_bounds(Rect::new(0, 0, isize::MAX / 2 + 1, 0));
+        assert_eq!(
+            far.concat_x(&far).err(),
+            Some(GridError::CoordinateOverflow {
+                x: isize::MAX / 2 + 1,
+                y: 0
+            })
+        );
+        assert!(far.tile(3, 1).is_err());
+    }
+
+    #[test]
+    fn splits_stored_cells_only() {
+        let mut grid = Grid::new_with_default('.');
+        grid.set(0, 0, 'a');
+        grid.set(5, 5, 'b');
+        let (top, bottom) = grid.split_at_x(3);
+        assert_eq!((top.len(), bottom.len()), (1, 1));
+        assert_eq!(bottom.bounds(), Rect::new(3, 0, 5, 5));
+        assert_eq!(bottom.get(3, 0), Some(&'.'));
+
+        let quadrants = grid.split_quadrants();
+        assert_eq!(
+            quadrants.each_ref().map(|quadrant| quadrant.len()),
+            [1, 0, 0, 1]
+        );
+    }
+}
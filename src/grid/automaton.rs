@@ -0,0 +1,121 @@
+use super::{Grid, GridRead};
+use crate::direction::Direction;
+
+// Computes the next generation of any readable grid, so views and the edge
+// adapters can be stepped too; the rule sees the whole previous generation,
+// so updates never observe each other. The result covers the source's
+// bounds.
+pub fn step<T, R, F>(grid: &R, mut rule: F) -> Grid<T>
+where
+    R: GridRead<T> + ?Sized,
+    F: FnMut(&R, isize, isize) -> Option<T>,
+{
+    let bounds = grid.bounds();
+    let mut next = Grid::with_bounds(bounds);
+
+    for (x, y) in bounds.iter() {
+        if let Some(item) = rule(grid, x, y) {
+            next.set(x, y, item);
+        }
+    }
+
+    next
+}
+
+// Counts the Moore neighbors of (x, y) matching the predicate, which also
+// sees empty cells so callers can decide how the outside counts.
+pub fn count_neighbors<T, R, F>(grid: &R, x: isize, y: isize, predicate: F) -> usize
+where
+    R: GridRead<T> + ?Sized,
+    F: Fn(Option<&T>) -> bool,
+{
+    Direction::ALL
+        .into_iter()
+        .filter(|direction| {
+            let neighbor = direction.checked_step((x, y));
+            predicate(neighbor.and_then(|(x, y)| grid.get(x, y)))
+        })
+        .count()
+}
+
+impl<T> Grid<T> {
+    pub fn step<F>(&self, rule: F) -> Grid<T>
+    where
+        F: FnMut(&Self, isize, isize) -> Option<T>,
+    {
+        step(self, rule)
+    }
+
+    pub fn count_neighbors<F>(&self, x: isize, y: isize, predicate: F) -> usize
+    where
+        F: Fn(Option<&T>) -> bool,
+    {
+        count_neighbors(self, x, y, predicate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count_neighbors, step, Grid};
+    use crate::grid::{GridRead, Wrapping};
+    use crate::rect::Rect;
+
+    #[test]
+    fn blinker_oscillates() {
+        let mut grid = Grid::new();
+        for x in -2..=2 {
+            for y in -2..=2 {
+                grid.set(x, y, y == 0 && x.abs() <= 1);
+            }
+        }
+
+        let life = |grid: &Grid<bool>, x, y| {
+            let alive = *grid.get(x, y)?;
+            let neighbors = grid.count_neighbors(x, y, |cell| cell == Some(&true));
+            Some(neighbors == 3 || (alive && neighbors == 2))
+        };
+
+        let next = grid.step(life);
+        for x in -2..=2 {
+            for y in -2..=2 {
+                assert_eq!(next.get(x, y), Some(&(x == 0 && y.abs() <= 1)));
+            }
+        }
+
+        let back = next.step(life);
+        for x in -2..=2 {
+            for y in -2..=2 {
+                assert_eq!(back.get(x, y), grid.get(x, y));
+            }
+        }
+    }
+
+    #[test]
+    fn steps_adapters() {
+        // A glider on a 5x5 torus is back where it started after 20 steps.
+        let mut grid = Grid::new();
+        for (x, y) in Rect::new(0, 0, 4, 4).iter() {
+            grid.set(
+                x,
+                y,
+                [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)].contains(&(x, y)),
+            );
+        }
+        fn life<R: GridRead<bool>>(grid: &R, x: isize, y: isize) -> Option<bool> {
+            let alive = *grid.get(x, y)?;
+            let neighbors = count_neighbors(grid, x, y, |cell| cell == Some(&true));
+            Some(neighbors == 3 || (alive && neighbors == 2))
+        }
+
+        let mut torus = grid.clone();
+        for _ in 0..20 {
+            torus = step(&Wrapping(&torus), life);
+        }
+        assert!(torus.iter().eq(grid.iter()));
+
+        let view = grid.view(Rect::new(0, 0, 1, 3));
+        let next = step(&view, life);
+        assert_eq!(next.bounds(), Rect::new(0, 0, 1, 3));
+        assert_eq!(next.get(1, 0), Some(&false));
+    }
+}
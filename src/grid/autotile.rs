@@ -0,0 +1,100 @@
+use super::Grid;
+use crate::direction::Direction;
+
+impl<T> Grid<T> {
+    fn matches<F: Fn(&T) -> bool>(
+        &self,
+        (x, y): (isize, isize),
+        direction: Direction,
+        predicate: &F,
+    ) -> bool {
+        let (dx, dy) = direction.offset();
+        self.get(x + dx, y + dy).is_some_and(predicate)
+    }
+
+    // A 4-bit neighbor mask for every cell matching the predicate, for
+    // picking edge tiles: bit i is set when the neighbor in
+    // `Direction::CARDINAL[i]` matches too, so North = 1, East = 2, South = 4
+    // and West = 8. Empty and missing neighbors never match.
+    pub fn autotile<F: Fn(&T) -> bool>(&self, predicate: F) -> Grid<u8> {
+        let mut masks = Grid::with_boundaries_of(self);
+        for (position, item) in self.iter() {
+            if !predicate(item) {
+                continue;
+            }
+            let mask = Direction::CARDINAL
+                .iter()
+                .enumerate()
+                .filter(|(_, &direction)| self.matches(position, direction, &predicate))
+                .fold(0, |mask, (bit, _)| mask | 1 << bit);
+            masks.set(position.0, position.1, mask);
+        }
+        masks
+    }
+
+    // The 8-bit mask of blob tilesets: bit i for `Direction::ALL[i]`, so
+    // clockwise from North = 1 to NorthWest = 128. A corner only counts when
+    // both edges next to it match as well, which leaves the 47 masks such
+    // tilesets draw.
+    pub fn autotile_blob<F: Fn(&T) -> bool>(&self, predicate: F) -> Grid<u8> {
+        let mut masks = Grid::with_boundaries_of(self);
+        for (position, item) in self.iter() {
+            if !predicate(item) {
+                continue;
+            }
+            let matching =
+                Direction::ALL.map(|direction| self.matches(position, direction, &predicate));
+            let mask = (0..8)
+                .filter(|&i| {
+                    matching[i] && (i % 2 == 0 || (matching[i - 1] && matching[(i + 1) % 8]))
+                })
+                .fold(0, |mask, bit| mask | 1 << bit);
+            masks.set(position.0, position.1, mask);
+        }
+        masks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::direction::Direction;
+    use std::collections::HashSet;
+
+    #[test]
+    fn masks_match_neighbors() {
+        // ##.
+        // ###
+        let mut grid = Grid::new();
+        for (x, y) in [(0, 0), (0, 1), (1, 0), (1, 1), (1, 2)] {
+            grid.set(x, y, true);
+        }
+        grid.set(0, 2, false);
+
+        let masks = grid.autotile(|&wall| wall);
+        assert_eq!(masks.get(0, 0), Some(&(2 | 4)));
+        assert_eq!(masks.get(1, 1), Some(&(1 | 2 | 8)));
+        assert_eq!(masks.get(1, 2), Some(&8));
+        assert_eq!(masks.get(0, 2), None);
+
+        let blob = grid.autotile_blob(|&wall| wall);
+        assert_eq!(blob.get(0, 0), Some(&(4 | 8 | 16)));
+        assert_eq!(blob.get(1, 1), Some(&(1 | 4 | 64 | 128)));
+        assert_eq!(blob.get(1, 2), Some(&64));
+    }
+
+    #[test]
+    fn blob_masks_are_the_47_tiles() {
+        let mut masks = HashSet::new();
+        for pattern in 0u32..256 {
+            let mut grid = Grid::new();
+            grid.set(0, 0, true);
+            for (i, direction) in Direction::ALL.iter().enumerate() {
+                let (dx, dy) = direction.offset();
+                grid.set(dx, dy, pattern & 1 << i != 0);
+            }
+            masks.insert(*grid.autotile_blob(|&wall| wall).get(0, 0).unwrap());
+        }
+        assert_eq!(masks.len(), 47);
+    }
+}
@@ -0,0 +1,265 @@
+use super::{Grid, GridWrite};
+use crate::error::GridError;
+use crate::rect::Rect;
+
+// The value a grid reads as where nothing is stored, with how to recognize
+// it, captured where `T: PartialEq` is known.
+type DefaultValue<T> = (T, fn(&T, &T) -> bool);
+
+// Options for a `Grid`, or for any other `GridWrite` storage through
+// `build_into`. Setters can be called in any order.
+pub struct GridBuilder<T> {
+    bounds: Option<Rect>,
+    fill: Option<T>,
+    default: Option<DefaultValue<T>>,
+    max_bounds: Option<Rect>,
+    wrapping: Option<Rect>,
+    memory_budget: Option<usize>,
+}
+
+impl<T: Clone> GridBuilder<T> {
+    pub fn new() -> Self {
+        Self {
+            bounds: None,
+            fill: None,
+            default: None,
+            max_bounds: None,
+            wrapping: None,
+            memory_budget: None,
+        }
+    }
+
+    // The bounds the grid starts with, before any cell is set.
+    pub fn bounds(mut self, bounds: Rect) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    // Sets every cell of the initial bounds, or of the maximum bounds when no
+    // initial bounds are given.
+    pub fn fill(mut self, value: T) -> Self {
+        self.fill = Some(value);
+        self
+    }
+
+    pub fn max_bounds(mut self, bounds: Rect) -> Self {
+        self.max_bounds = Some(bounds);
+        self
+    }
+
+    // Limits the grid to `bounds` and wraps every coordinate around them.
+    // Combined with `max_bounds`, both must name the same rect.
+    pub fn wrapping(mut self, bounds: Rect) -> Self {
+        self.wrapping = Some(bounds);
+        self
+    }
+
+    pub fn memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = Some(bytes);
+        self
+    }
+
+    // Fails if the options conflict, the initial bounds do not fit the
+    // limits or are too wide to address, there is nothing to fill or filling
+    // runs out of memory.
+    pub fn build(self) -> Result<Grid<T>, GridError> {
+        let max_bounds = match (self.max_bounds, self.wrapping) {
+            (Some(max), Some(wrapping)) if max != wrapping => {
+                return Err(GridError::InvalidConfiguration {
+                    reason: "max_bounds and wrapping give different limits",
+                });
+            }
+            (max, wrapping) => wrapping.or(max),
+        };
+        let mut grid = Grid {
+            max_bounds,
+            wrapping: self.wrapping.is_some(),
+            memory_budget: self.memory_budget,
+            ..Grid::new()
+        };
+        if let Some((default, is_default)) = self.default {
+            grid.default = Some(default);
+            grid.is_default = is_default;
+        }
+
+        if let Some(bounds) = self.bounds {
+            for (x, y) in [(bounds.min_x, bounds.min_y), (bounds.max_x, bounds.max_y)] {
+                if max_bounds.is_some_and(|max| !max.contains(x, y)) {
+                    return Err(GridError::OutOfBounds { x, y });
+                }
+            }
+
+            grid.min_x = bounds.min_x;
+            grid.max_x = bounds.max_x;
+            grid.min_y = bounds.min_y;
+            grid.max_y = bounds.max_y;
+            // Spans too wide to address would make the first `set` fail.
+            grid.check_overflow(bounds.max_x, bounds.max_y)?;
+        }
+
+        if let Some(value) = self.fill {
+            let Some(region) = self.bounds.or(max_bounds) else {
+                return Err(GridError::InvalidConfiguration {
+                    reason: "fill needs bounds, max_bounds or wrapping to fill",
+                });
+            };
+            for (x, y) in region.iter() {
+                grid.try_set(x, y, value.clone())?;
+            }
+        }
+
+        Ok(grid)
+    }
+
+    // Builds another storage backend, such as `RleGrid` or `ScratchGrid`,
+    // filled like `build` would fill a grid. Backends take their bounds from
+    // their cells, so the initial bounds only say where to fill; limits,
+    // wrapping, memory budgets and defaults belong to `Grid` and fail here.
+    pub fn build_into<B>(self) -> Result<B, GridError>
+    where
+        B: GridWrite<T> + Default,
+    {
+        if self.max_bounds.is_some()
+            || self.wrapping.is_some()
+            || self.memory_budget.is_some()
+            || self.default.is_some()
+        {
+            return Err(GridError::InvalidConfiguration {
+                reason: "only Grid supports limits, wrapping, memory budgets and defaults",
+            });
+        }
+
+        let mut backend = B::default();
+        if let (Some(value), Some(bounds)) = (self.fill, self.bounds) {
+            for (x, y) in bounds.iter() {
+                backend.set(x, y, value.clone());
+            }
+        }
+        Ok(backend)
+    }
+}
+
+impl<T: Clone + PartialEq> GridBuilder<T> {
+    // The value every cell not stored reads as, like `Grid::new_with_default`.
+    pub fn default_value(mut self, value: T) -> Self {
+        self.default = Some((value, T::eq));
+        self
+    }
+}
+
+impl<T: Clone> Default for GridBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GridBuilder;
+    use crate::error::GridError;
+    use crate::grid::GridRead;
+    use crate::rect::Rect;
+    use crate::rle::RleGrid;
+
+    #[test]
+    fn builds_filled_and_limited_grids() {
+        let grid = GridBuilder::new()
+            .bounds(Rect::new(-2, -2, 2, 2))
+            .fill('.')
+            .max_bounds(Rect::new(-5, -5, 5, 5))
+            .build()
+            .unwrap();
+
+        assert_eq!(grid.bounds(), Rect::new(-2, -2, 2, 2));
+        assert_eq!(grid.get(-2, 2), Some(&'.'));
+        assert_eq!(grid.max_bounds(), Some(Rect::new(-5, -5, 5, 5)));
+
+        let too_big = GridBuilder::<char>::new()
+            .bounds(Rect::new(0, 0, 9, 9))
+            .max_bounds(Rect::new(0, 0, 4, 4))
+            .build();
+        assert_eq!(too_big.err(), Some(GridError::OutOfBounds { x: 9, y: 9 }));
+
+        let over_budget = GridBuilder::new()
+            .bounds(Rect::new(0, 0, 99, 99))
+            .fill(0u8)
+            .memory_budget(100)
+            .build();
+        assert_eq!(over_budget.err(), Some(GridError::CapacityExceeded));
+
+        let too_wide = GridBuilder::<char>::new()
+            .bounds(Rect::new(isize::MIN, 0, isize::MAX, 0))
+            .build();
+        assert_eq!(
+            too_wide.err(),
+            Some(GridError::CoordinateOverflow {
+                x: isize::MAX,
+                y: 0
+            })
+        );
+
+        let nowhere = GridBuilder::new().fill('.').build();
+        assert!(matches!(
+            nowhere.err(),
+            Some(GridError::InvalidConfiguration { .. })
+        ));
+    }
+
+    #[test]
+    fn wrapping_grid() {
+        let mut grid = GridBuilder::new()
+            .wrapping(Rect::new(0, 0, 3, 4))
+            .fill(0)
+            .build()
+            .unwrap();
+
+        grid.set(-1, 5, 7);
+        assert_eq!(grid.get(3, 0), Some(&7));
+        assert_eq!(grid.get(isize::MAX, isize::MIN), grid.get(3, 2));
+        assert_eq!(grid.bounds(), Rect::new(0, 0, 3, 4));
+        assert_eq!(grid.count_neighbors(0, 0, |cell| cell == Some(&7)), 1);
+        assert!(grid.is_wrapping());
+
+        // The order of the limits does not matter; different limits conflict.
+        let limit = Rect::new(0, 0, 3, 4);
+        let grid = GridBuilder::<u8>::new()
+            .wrapping(limit)
+            .max_bounds(limit)
+            .build()
+            .unwrap();
+        assert!(grid.is_wrapping());
+        let conflicting = GridBuilder::<u8>::new()
+            .wrapping(limit)
+            .max_bounds(Rect::new(0, 0, 9, 9))
+            .build();
+        assert!(matches!(
+            conflicting.err(),
+            Some(GridError::InvalidConfiguration { .. })
+        ));
+    }
+
+    #[test]
+    fn defaults_and_backends() {
+        let mut grid = GridBuilder::new()
+            .bounds(Rect::new(0, 0, 2, 2))
+            .default_value('.')
+            .build()
+            .unwrap();
+        assert_eq!(grid.get(1, 1), Some(&'.'));
+        grid.set(1, 1, '#');
+        assert_eq!(grid.len(), 1);
+
+        let rle: RleGrid<char> = GridBuilder::new()
+            .bounds(Rect::new(0, 0, 1, 99))
+            .fill('~')
+            .build_into()
+            .unwrap();
+        assert_eq!(rle.get(1, 99), Some(&'~'));
+        assert_eq!(rle.bounds(), Rect::new(0, 0, 1, 99));
+
+        let limited = GridBuilder::<char>::new()
+            .max_bounds(Rect::new(0, 0, 1, 1))
+            .build_into::<RleGrid<char>>();
+        assert!(limited.is_err());
+    }
+}
@@ -0,0 +1,117 @@
+use super::Grid;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+impl<T> Grid<T> {
+    // Classifies the stored cells; cells left at the default stay empty.
+    pub fn classify<K, F>(&self, mut classifier: F) -> Grid<K>
+    where
+        F: FnMut(&T) -> K,
+    {
+        let mut grid = Grid::with_boundaries_of(self);
+
+        for ((x, y), item) in self.iter() {
+            grid.set(x, y, classifier(item));
+        }
+
+        grid
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    // Splits the cells into one grid per key. Every part keeps the cells'
+    // coordinates and this grid's bounds, so the parts line up as layers.
+    pub fn partition<K, F>(&self, mut key: F) -> HashMap<K, Grid<T>>
+    where
+        K: Eq + Hash,
+        F: FnMut(&T) -> K,
+    {
+        let mut parts = HashMap::new();
+
+        for ((x, y), item) in self.iter() {
+            parts
+                .entry(key(item))
+                .or_insert_with(|| Grid::with_boundaries_of(self))
+                .set(x, y, item.clone());
+        }
+
+        parts
+    }
+}
+
+impl<T: Eq + Hash + Clone> Grid<T> {
+    // How often each value is stored; the default is not counted.
+    pub fn histogram(&self) -> HashMap<T, usize> {
+        let mut histogram = HashMap::new();
+
+        for (_, item) in self.iter() {
+            *histogram.entry(item.clone()).or_insert(0) += 1;
+        }
+
+        histogram
+    }
+}
+
+impl Grid<f64> {
+    pub fn threshold(&self, threshold: f64) -> Grid<bool> {
+        self.classify(|value| *value >= threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+
+    #[test]
+    fn threshold_and_histogram() {
+        let mut grid = Grid::new();
+        for x in -2..=2 {
+            grid.set(x, 0, x as f64);
+        }
+        grid.set(5, 5, 0.5);
+
+        let mask = grid.threshold(0.5);
+        assert_eq!(mask.get(-1, 0), Some(&false));
+        assert_eq!(mask.get(1, 0), Some(&true));
+        assert_eq!(mask.get(5, 5), Some(&true));
+        assert_eq!(mask.get(4, 4), None);
+
+        let histogram = mask.histogram();
+        assert_eq!(histogram[&true], 3);
+        assert_eq!(histogram[&false], 3);
+    }
+
+    #[test]
+    fn classify_into_bands() {
+        let mut grid = Grid::new();
+        for x in 0..10 {
+            grid.set(x, 0, x);
+        }
+
+        let bands = grid.classify(|value| value / 4).histogram();
+        assert_eq!(bands[&0], 4);
+        assert_eq!(bands[&1], 4);
+        assert_eq!(bands[&2], 2);
+
+        let mut sparse = Grid::new_with_default(0);
+        sparse.set(-3, 0, 5);
+        sparse.set(3, 9, 5);
+        assert_eq!(sparse.histogram().into_iter().collect::<Vec<_>>(), [(5, 2)]);
+        assert_eq!(sparse.classify(|value| value * 2).len(), 2);
+    }
+
+    #[test]
+    fn partitions_into_layers() {
+        let mut grid = Grid::new();
+        for (y, tile) in "~~#.~#".chars().enumerate() {
+            grid.set(-1, y as isize, tile);
+        }
+
+        let layers = grid.partition(|&tile| tile == '~');
+        assert_eq!(layers.len(), 2);
+        assert_eq!(layers[&true].len(), 3);
+        assert_eq!(layers[&false].get(-1, 2), Some(&'#'));
+        assert_eq!(layers[&false].get(-1, 4), None);
+        assert_eq!(layers[&true].bounds(), grid.bounds());
+    }
+}
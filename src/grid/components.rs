@@ -0,0 +1,334 @@
+use super::Grid;
+use crate::connectivity::{step, Connectivity};
+use crate::direction::Direction;
+use crate::rect::Rect;
+use std::collections::VecDeque;
+
+// Which pair of opposite edges `percolates` connects: `X` joins the first
+// and last row, `Y` the first and last column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Axis {
+    X,
+    Y,
+}
+
+impl<T> Grid<T> {
+    // Labels the 4-connected components of cells matching the predicate,
+    // numbered from 0 in row-major order of their first cell, and returns
+    // the label grid with the number of components.
+    pub fn label_components<F>(&self, predicate: F) -> (Grid<usize>, usize)
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.label_components_with(&Connectivity::Four, predicate)
+    }
+
+    pub fn label_components_with<F>(
+        &self,
+        connectivity: &Connectivity,
+        predicate: F,
+    ) -> (Grid<usize>, usize)
+    where
+        F: Fn(&T) -> bool,
+    {
+        let offsets = connectivity.offsets();
+        let bounds = self.bounds();
+        let inside = |(x, y): (isize, isize)| {
+            bounds.contains(x, y) && self.get(x, y).is_some_and(&predicate)
+        };
+        let mut labels = Grid::with_boundaries_of(self);
+        let mut count = 0;
+
+        for (start, item) in self.iter() {
+            if !predicate(item) || labels.get(start.0, start.1).is_some() {
+                continue;
+            }
+
+            labels.set(start.0, start.1, count);
+            let mut queue = VecDeque::from([start]);
+            while let Some((x, y)) = queue.pop_front() {
+                for &(dx, dy) in &offsets {
+                    let Some(next) = step((x, y), (dx, dy)) else {
+                        continue;
+                    };
+                    if inside(next) && labels.get(next.0, next.1).is_none() {
+                        labels.set(next.0, next.1, count);
+                        queue.push_back(next);
+                    }
+                }
+            }
+            count += 1;
+        }
+
+        (labels, count)
+    }
+
+    // The cells of the biggest component, in row-major order; the first one
+    // found wins ties.
+    pub fn largest_component<F>(&self, predicate: F) -> Vec<(isize, isize)>
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.largest_component_with(&Connectivity::Four, predicate)
+    }
+
+    pub fn largest_component_with<F>(
+        &self,
+        connectivity: &Connectivity,
+        predicate: F,
+    ) -> Vec<(isize, isize)>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let (labels, count) = self.label_components_with(connectivity, predicate);
+        let mut sizes = vec![0; count];
+        for (_, &label) in labels.iter() {
+            sizes[label] += 1;
+        }
+
+        let Some(largest) = (0..count).max_by_key(|&label| (sizes[label], count - label)) else {
+            return vec![];
+        };
+        labels
+            .iter()
+            .filter(|(_, &label)| label == largest)
+            .map(|(position, _)| position)
+            .collect()
+    }
+
+    // Whether a component of matching cells touches both edges of the
+    // bounds across `axis`, e.g. whether water can flow from the top row to
+    // the bottom one.
+    pub fn percolates<F>(&self, axis: Axis, predicate: F) -> bool
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.percolates_with(axis, &Connectivity::Four, predicate)
+    }
+
+    pub fn percolates_with<F>(&self, axis: Axis, connectivity: &Connectivity, predicate: F) -> bool
+    where
+        F: Fn(&T) -> bool,
+    {
+        let (labels, count) = self.label_components_with(connectivity, predicate);
+        let bounds = self.bounds();
+        let edge = |first: bool| -> Vec<bool> {
+            let mut touched = vec![false; count];
+            let cells: Box<dyn Iterator<Item = (isize, isize)>> = match (axis, first) {
+                (Axis::X, true) => {
+                    Box::new((bounds.min_y..=bounds.max_y).map(|y| (bounds.min_x, y)))
+                }
+                (Axis::X, false) => {
+                    Box::new((bounds.min_y..=bounds.max_y).map(|y| (bounds.max_x, y)))
+                }
+                (Axis::Y, true) => {
+                    Box::new((bounds.min_x..=bounds.max_x).map(|x| (x, bounds.min_y)))
+                }
+                (Axis::Y, false) => {
+                    Box::new((bounds.min_x..=bounds.max_x).map(|x| (x, bounds.max_y)))
+                }
+            };
+            for (x, y) in cells {
+                if let Some(&label) = labels.get(x, y) {
+                    touched[label] = true;
+                }
+            }
+            touched
+        };
+
+        let (first, last) = (edge(true), edge(false));
+        (0..count).any(|label| first[label] && last[label])
+    }
+}
+
+// Measurements of one labeled region. The perimeter counts cell edges
+// facing another label or an empty cell, and holes are the enclosed
+// 8-connected pockets of other cells, as fits a 4-connected region.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegionStats {
+    pub area: usize,
+    pub perimeter: usize,
+    pub centroid: (f64, f64),
+    pub bounds: Rect,
+    pub holes: usize,
+}
+
+impl Grid<usize> {
+    // Statistics for every label of a label grid such as the one from
+    // `label_components`, indexed by label, in a single scan. Labels without
+    // cells get a zero area. Holes are derived from the Euler number of each
+    // region, counted with 2x2 bit quads, so a label split into several
+    // pieces can undercount them.
+    pub fn region_stats(&self) -> Vec<RegionStats> {
+        let count = self.iter().map(|(_, &label)| label + 1).max().unwrap_or(0);
+        let mut stats = vec![
+            RegionStats {
+                area: 0,
+                perimeter: 0,
+                centroid: (0.0, 0.0),
+                bounds: Rect::new(0, 0, 0, 0),
+                holes: 0,
+            };
+            count
+        ];
+        // Bit quad counts per label: windows with one, three and two
+        // diagonal cells of the label.
+        let mut quads = vec![(0isize, 0isize, 0isize); count];
+
+        // Cells past the edge of the coordinate space read as empty.
+        let at = |x: isize, y: isize, dx: isize, dy: isize| {
+            step((x, y), (dx, dy)).and_then(|(x, y)| self.get(x, y))
+        };
+
+        let mut windows = vec![];
+        for ((x, y), &label) in self.iter() {
+            let region = &mut stats[label];
+            region.bounds = if region.area == 0 {
+                Rect::new(x, y, x, y)
+            } else {
+                region.bounds.union(&Rect::new(x, y, x, y))
+            };
+            region.area += 1;
+            region.centroid.0 += x as f64;
+            region.centroid.1 += y as f64;
+            region.perimeter += Direction::CARDINAL
+                .iter()
+                .filter(|direction| {
+                    let (dx, dy) = direction.offset();
+                    at(x, y, dx, dy) != Some(&label)
+                })
+                .count();
+
+            // The four 2x2 windows holding the cell, by their top left corner.
+            windows.extend(
+                [(-1, -1), (-1, 0), (0, -1), (0, 0)]
+                    .into_iter()
+                    .filter_map(|offset| step((x, y), offset)),
+            );
+        }
+        windows.sort_unstable();
+        windows.dedup();
+
+        for (x, y) in windows {
+            let window = [
+                at(x, y, 0, 0),
+                at(x, y, 0, 1),
+                at(x, y, 1, 0),
+                at(x, y, 1, 1),
+            ];
+            for (i, &label) in window.iter().enumerate() {
+                let Some(&label) = label else {
+                    continue;
+                };
+                if window[..i].contains(&Some(&label)) {
+                    continue;
+                }
+                let same = window.map(|cell| cell == Some(&label));
+                match same.iter().filter(|&&same| same).count() {
+                    1 => quads[label].0 += 1,
+                    3 => quads[label].1 += 1,
+                    2 if same[0] == same[3] => quads[label].2 += 1,
+                    _ => {}
+                }
+            }
+        }
+
+        for (region, (q1, q3, qd)) in stats.iter_mut().zip(quads) {
+            if region.area > 0 {
+                region.centroid.0 /= region.area as f64;
+                region.centroid.1 /= region.area as f64;
+                let euler = (q1 - q3 + 2 * qd) / 4;
+                region.holes = (1 - euler).max(0) as usize;
+            }
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Axis, Grid};
+    use crate::connectivity::Connectivity;
+    use crate::rect::Rect;
+
+    fn parse(rows: &[&str]) -> Grid<bool> {
+        let mut grid = Grid::new();
+        for (x, row) in rows.iter().enumerate() {
+            for (y, cell) in row.chars().enumerate() {
+                grid.set(x as isize, y as isize, cell == '#');
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn labels_and_finds_largest() {
+        let grid = parse(&["##..#", "#..##", "..###"]);
+        let (labels, count) = grid.label_components(|&filled| filled);
+        assert_eq!(count, 2);
+        assert_eq!(labels.get(1, 0), Some(&0));
+        assert_eq!(labels.get(2, 4), Some(&1));
+        assert_eq!(labels.get(0, 2), None);
+
+        assert_eq!(grid.largest_component(|&filled| filled).len(), 6);
+        assert!(grid.largest_component(|_| false).is_empty());
+    }
+
+    #[test]
+    fn checks_edge_to_edge_connection() {
+        let grid = parse(&["##..#", "#..##", "..###"]);
+        assert!(grid.percolates(Axis::X, |&filled| filled));
+        assert!(!grid.percolates(Axis::Y, |&filled| filled));
+        assert!(grid.percolates(Axis::X, |&filled| !filled));
+        assert!(!grid.percolates(Axis::Y, |&filled| !filled));
+    }
+
+    #[test]
+    fn measures_each_region() {
+        let grid = parse(&["####.#", "#..#..", "####..", "......"]);
+        let (labels, count) = grid.label_components(|&filled| filled);
+        let stats = labels.region_stats();
+        assert_eq!(stats.len(), count);
+
+        let ring = &stats[0];
+        assert_eq!(ring.area, 10);
+        assert_eq!(ring.perimeter, 20);
+        assert_eq!(ring.bounds, Rect::new(0, 0, 2, 3));
+        assert_eq!(ring.centroid, (1.0, 1.5));
+        assert_eq!(ring.holes, 1);
+
+        let dot = &stats[1];
+        assert_eq!((dot.area, dot.perimeter, dot.holes), (1, 4, 0));
+        assert_eq!(dot.centroid, (0.0, 5.0));
+
+        // Wide bounds around a few cells cost nothing to scan.
+        let mut sparse =
+            Grid::<usize>::with_bounds(Rect::new(-1 << 40, -1 << 40, 1 << 40, 1 << 40));
+        sparse.set(0, 0, 0);
+        sparse.set(0, 1, 0);
+        sparse.set(5, 5, 1);
+        let stats = sparse.region_stats();
+        assert_eq!(
+            (stats[0].area, stats[0].perimeter, stats[0].holes),
+            (2, 6, 0)
+        );
+        assert_eq!(stats[0].bounds, Rect::new(0, 0, 0, 1));
+        assert_eq!(
+            (stats[1].area, stats[1].perimeter, stats[1].holes),
+            (1, 4, 0)
+        );
+    }
+
+    #[test]
+    fn diagonal_connectivity_joins_corners() {
+        let grid = parse(&["#..", ".#.", "..#"]);
+        assert_eq!(grid.label_components(|&filled| filled).1, 3);
+        let eight = Connectivity::Eight;
+        assert_eq!(grid.label_components_with(&eight, |&filled| filled).1, 1);
+        assert!(grid.percolates_with(Axis::Y, &eight, |&filled| filled));
+        assert_eq!(
+            grid.largest_component_with(&eight, |&filled| filled).len(),
+            3
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use super::Grid;
+use std::collections::BTreeMap;
+
+// A crossing point is identified by the cell edge it lies on: the edge from
+// (x, y) towards (x + 1, y) or towards (x, y + 1).
+type EdgeKey = (isize, isize, bool);
+
+impl Grid<f64> {
+    // Polylines in grid space, where cells are centered on integer coordinates.
+    // Closed contours repeat their first point at the end. Squares with an
+    // empty corner are treated as holes and produce no segments.
+    pub fn contours(&self, threshold: f64) -> Vec<Vec<(f64, f64)>> {
+        let mut points: BTreeMap<EdgeKey, (f64, f64)> = BTreeMap::new();
+        let mut links: BTreeMap<EdgeKey, Vec<EdgeKey>> = BTreeMap::new();
+
+        for x in self.min_x..self.max_x {
+            for y in self.min_y..self.max_y {
+                let corners = [(x, y), (x + 1, y), (x + 1, y + 1), (x, y + 1)];
+                let Some(values) = corners
+                    .iter()
+                    .map(|&(x, y)| self.get(x, y).copied())
+                    .collect::<Option<Vec<f64>>>()
+                else {
+                    continue;
+                };
+
+                let inside: Vec<bool> = values.iter().map(|value| *value >= threshold).collect();
+                let edges = [
+                    (x, y, true),
+                    (x + 1, y, false),
+                    (x, y + 1, true),
+                    (x, y, false),
+                ];
+
+                let mut crossed = vec![];
+                for (side, &edge) in edges.iter().enumerate() {
+                    let (from, to) = [(0, 1), (1, 2), (3, 2), (0, 3)][side];
+                    if inside[from] != inside[to] {
+                        let t = (threshold - values[from]) / (values[to] - values[from]);
+                        let (fx, fy) = corners[from];
+                        let (tx, ty) = corners[to];
+                        points.insert(
+                            edge,
+                            (
+                                fx as f64 + (tx - fx) as f64 * t,
+                                fy as f64 + (ty - fy) as f64 * t,
+                            ),
+                        );
+                        crossed.push(side);
+                    }
+                }
+
+                let pairs = match crossed.len() {
+                    2 => vec![(crossed[0], crossed[1])],
+                    4 => {
+                        let center = values.iter().sum::<f64>() / 4.0 >= threshold;
+                        if center == inside[0] {
+                            vec![(0, 1), (2, 3)]
+                        } else {
+                            vec![(3, 0), (1, 2)]
+                        }
+                    }
+                    _ => vec![],
+                };
+
+                for (a, b) in pairs {
+                    links.entry(edges[a]).or_default().push(edges[b]);
+                    links.entry(edges[b]).or_default().push(edges[a]);
+                }
+            }
+        }
+
+        let mut contours = vec![];
+
+        // Open contours start at keys with a single link, the rest are loops.
+        for open in [true, false] {
+            let starts: Vec<EdgeKey> = links
+                .iter()
+                .filter(|(_, next)| !open || next.len() == 1)
+                .map(|(key, _)| *key)
+                .collect();
+
+            for start in starts {
+                if links.get(&start).is_none_or(|next| next.is_empty()) {
+                    continue;
+                }
+
+                let mut line = vec![points[&start]];
+                let mut current = start;
+                while let Some(next) = links.get_mut(&current).and_then(|next| next.pop()) {
+                    if let Some(back) = links.get_mut(&next) {
+                        if let Some(index) = back.iter().position(|key| *key == current) {
+                            back.swap_remove(index);
+                        }
+                    }
+                    line.push(points[&next]);
+                    current = next;
+                }
+
+                contours.push(line);
+            }
+        }
+
+        contours
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+
+    #[test]
+    fn closed_contour_around_peak() {
+        let mut grid = Grid::new();
+        for x in -2..=2 {
+            for y in -2..=2 {
+                grid.set(x, y, if x == 0 && y == 0 { 1.0 } else { 0.0 });
+            }
+        }
+
+        let contours = grid.contours(0.5);
+        assert_eq!(contours.len(), 1);
+
+        let contour = &contours[0];
+        assert_eq!(contour.len(), 5);
+        assert_eq!(contour.first(), contour.last());
+        for &(x, y) in contour {
+            assert!((x.abs() + y.abs() - 0.5).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn open_contour_across_slope() {
+        let mut grid = Grid::new();
+        for x in 0..4 {
+            for y in 0..3 {
+                grid.set(x, y, x as f64);
+            }
+        }
+
+        let contours = grid.contours(1.5);
+        assert_eq!(contours.len(), 1);
+
+        let contour = &contours[0];
+        assert_eq!(contour.len(), 3);
+        assert!(contour.iter().all(|&(x, _)| x == 1.5));
+        assert_ne!(contour.first(), contour.last());
+    }
+}
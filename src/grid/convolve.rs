@@ -0,0 +1,168 @@
+use super::sample::resolve;
+use super::{Edge, Grid, GridRead};
+
+// The kernel's own coordinates are the offsets it is applied at, so a 3x3
+// kernel occupies -1..=1 in both directions. With `Edge::Empty`, missing
+// neighbors are left out and the remaining weights are rescaled to the
+// kernel's total, which keeps averaging kernels unbiased near edges. Takes
+// any readable grid, so views and the edge adapters convolve too; the
+// result covers the source's bounds.
+pub fn convolve<R, K>(grid: &R, kernel: &K, edge: Edge) -> Grid<f64>
+where
+    R: GridRead<f64> + ?Sized,
+    K: GridRead<f64> + ?Sized,
+{
+    let mut taps = vec![];
+    for (dx, dy) in kernel.bounds().iter() {
+        if let Some(weight) = kernel.get(dx, dy) {
+            taps.push((dx, dy, *weight));
+        }
+    }
+    let total: f64 = taps.iter().map(|(_, _, weight)| weight).sum();
+
+    let bounds = grid.bounds();
+    let mut result = Grid::with_bounds(bounds);
+    for (x, y) in bounds.iter() {
+        if grid.get(x, y).is_none() {
+            continue;
+        }
+
+        let mut sum = 0.0;
+        let mut covered = 0.0;
+        for &(dx, dy, weight) in &taps {
+            if let Some(value) = resolve(grid, x as i128 - dx as i128, y as i128 - dy as i128, edge)
+            {
+                sum += value * weight;
+                covered += weight;
+            }
+        }
+
+        if edge == Edge::Empty && covered != 0.0 {
+            sum *= total / covered;
+        }
+
+        result.set(x, y, sum);
+    }
+
+    result
+}
+
+impl Grid<f64> {
+    pub fn convolve<K>(&self, kernel: &K, edge: Edge) -> Grid<f64>
+    where
+        K: GridRead<f64> + ?Sized,
+    {
+        convolve(self, kernel, edge)
+    }
+
+    pub fn blur(&self, radius: usize) -> Grid<f64> {
+        if radius == 0 {
+            return self.clone();
+        }
+
+        let sigma = radius as f64 / 2.0;
+        let radius = radius as isize;
+
+        let weights: Vec<f64> = (-radius..=radius)
+            .map(|offset| (-((offset * offset) as f64) / (2.0 * sigma * sigma)).exp())
+            .collect();
+        let total: f64 = weights.iter().sum();
+
+        let mut rows = Grid::new();
+        let mut columns = Grid::new();
+        for (offset, weight) in (-radius..=radius).zip(weights) {
+            rows.set(offset, 0, weight / total);
+            columns.set(0, offset, weight / total);
+        }
+
+        self.convolve(&rows, Edge::Empty)
+            .convolve(&columns, Edge::Empty)
+    }
+
+    pub fn smooth(&self, iterations: usize) -> Grid<f64> {
+        let mut kernel = Grid::new();
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                kernel.set(dx, dy, 1.0 / 9.0);
+            }
+        }
+
+        let mut grid = self.clone();
+        for _ in 0..iterations {
+            grid = grid.convolve(&kernel, Edge::Empty);
+        }
+
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convolve, Edge, Grid};
+    use crate::grid::{GridRead, Wrapping};
+    use crate::rect::Rect;
+
+    fn filled(value: impl Fn(isize, isize) -> f64) -> Grid<f64> {
+        let mut grid = Grid::new();
+        for x in -5..=5 {
+            for y in -5..=5 {
+                grid.set(x, y, value(x, y));
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn blur_preserves_constant_fields() {
+        let blurred = filled(|_, _| 3.0).blur(2);
+
+        for x in -5..=5 {
+            for y in -5..=5 {
+                assert!((blurred.get(x, y).unwrap() - 3.0).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn blur_spreads_symmetrically() {
+        let blurred = filled(|x, y| if x == 0 && y == 0 { 1.0 } else { 0.0 }).blur(1);
+
+        let center = *blurred.get(0, 0).unwrap();
+        let side = *blurred.get(1, 0).unwrap();
+        assert!(center > side && side > 0.0);
+        assert_eq!(blurred.get(-1, 0), Some(&side));
+        assert_eq!(blurred.get(0, 1), Some(&side));
+        assert_eq!(blurred.get(3, 3), Some(&0.0));
+    }
+
+    #[test]
+    fn convolve_with_edges() {
+        let grid = filled(|x, _| x as f64);
+        let mut kernel = Grid::new();
+        kernel.set(1, 0, 1.0);
+
+        let shifted = grid.convolve(&kernel, Edge::Clamp);
+        assert_eq!(shifted.get(0, 0), Some(&-1.0));
+        assert_eq!(shifted.get(-5, 0), Some(&-5.0));
+
+        let wrapped = grid.convolve(&kernel, Edge::Wrap);
+        assert_eq!(wrapped.get(-5, 0), Some(&5.0));
+
+        // Adapters and views pick the edge behavior themselves.
+        let adapted = convolve(&Wrapping(&grid), &kernel, Edge::Empty);
+        assert!(adapted.iter().eq(wrapped.iter()));
+        let view = grid.view(Rect::new(0, 0, 2, 2));
+        let clipped = convolve(&view, &kernel, Edge::Clamp);
+        assert_eq!(clipped.bounds(), Rect::new(0, 0, 2, 2));
+        assert_eq!(clipped.get(0, 1), Some(&0.0));
+        assert_eq!(clipped.get(2, 1), Some(&1.0));
+    }
+
+    #[test]
+    fn smoothing_flattens_noise() {
+        let noisy = filled(|x, y| ((x + y) % 2) as f64);
+        let spread = |grid: &Grid<f64>| (grid.get(0, 0).unwrap() - grid.get(0, 1).unwrap()).abs();
+
+        assert!(spread(&noisy.smooth(3)) < spread(&noisy) / 4.0);
+    }
+}
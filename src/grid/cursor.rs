@@ -0,0 +1,107 @@
+use super::Grid;
+use crate::direction::Direction;
+
+// A position in a mutably borrowed grid. Reads and writes go through the
+// cursor, so a traversal can edit cells as it walks without holding on to
+// references across moves.
+pub struct Cursor<'a, T> {
+    grid: &'a mut Grid<T>,
+    position: (isize, isize),
+}
+
+impl<T> Grid<T> {
+    pub fn cursor(&mut self, x: isize, y: isize) -> Cursor<'_, T> {
+        Cursor {
+            grid: self,
+            position: (x, y),
+        }
+    }
+}
+
+impl<T> Cursor<'_, T> {
+    pub fn position(&self) -> (isize, isize) {
+        self.position
+    }
+
+    // Returns false, staying in place, at the edge of the coordinate space.
+    pub fn step(&mut self, direction: Direction) -> bool {
+        match direction.checked_step(self.position) {
+            Some(position) => {
+                self.position = position;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn jump_to(&mut self, x: isize, y: isize) {
+        self.position = (x, y);
+    }
+
+    pub fn get(&self) -> Option<&T> {
+        self.grid.get(self.position.0, self.position.1)
+    }
+
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        self.grid.get_mut(self.position.0, self.position.1)
+    }
+
+    pub fn set(&mut self, item: T) {
+        self.grid.set(self.position.0, self.position.1, item);
+    }
+
+    pub fn remove(&mut self) -> Option<T> {
+        self.grid.remove(self.position.0, self.position.1)
+    }
+
+    // The neighboring cell in `direction`, without moving.
+    pub fn peek(&self, direction: Direction) -> Option<&T> {
+        let (x, y) = direction.checked_step(self.position)?;
+        self.grid.get(x, y)
+    }
+
+    pub fn neighbors(&self) -> impl Iterator<Item = (Direction, &T)> {
+        Direction::ALL
+            .into_iter()
+            .filter_map(|direction| Some((direction, self.peek(direction)?)))
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        self.grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::direction::Direction;
+
+    #[test]
+    fn walks_and_writes() {
+        let mut grid = Grid::new();
+        grid.set(0, 2, 'x');
+
+        let mut cursor = grid.cursor(0, 0);
+        while cursor.peek(Direction::East).is_none() {
+            cursor.set('.');
+            cursor.step(Direction::East);
+        }
+        assert_eq!(cursor.position(), (0, 1));
+        assert_eq!(cursor.neighbors().count(), 2);
+        assert_eq!(cursor.get(), None);
+
+        cursor.set('o');
+        cursor.step(Direction::West);
+        *cursor.get_mut().unwrap() = '-';
+        cursor.jump_to(0, 2);
+        assert_eq!(cursor.remove(), Some('x'));
+
+        cursor.jump_to(isize::MIN, 0);
+        assert!(!cursor.step(Direction::North));
+        assert_eq!(cursor.position(), (isize::MIN, 0));
+
+        assert_eq!(grid.get(0, 0), Some(&'-'));
+        assert_eq!(grid.get(0, 1), Some(&'o'));
+        assert_eq!(grid.get(0, 2), None);
+    }
+}
@@ -0,0 +1,112 @@
+use super::Grid;
+use std::fmt::{self, Debug, Write};
+
+// How much of the occupancy map `Debug` shows; `debug_grid` prints it all.
+const MAX_ROWS: usize = 16;
+const MAX_COLUMNS: usize = 48;
+
+impl<T> Debug for Grid<T> {
+    // Bounds, cell count and a map of stored (#) and empty (.) cells,
+    // where cells reading as the default count as empty, truncated for large
+    // grids. Use `{:#?}` to get one map row per line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bounds = self.bounds();
+        let occupancy = format!("{:.1}%", 100.0 * self.len() as f64 / bounds.area() as f64);
+
+        let mut map: Vec<String> = (bounds.min_x..=bounds.max_x)
+            .take(MAX_ROWS)
+            .map(|x| {
+                let mut row: String = (bounds.min_y..=bounds.max_y)
+                    .take(MAX_COLUMNS)
+                    .map(|y| {
+                        if self.get_stored(x, y).is_some() {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect();
+                if bounds.width() > MAX_COLUMNS {
+                    write!(row, " … {} more columns", bounds.width() - MAX_COLUMNS).unwrap();
+                }
+                row
+            })
+            .collect();
+        if bounds.height() > MAX_ROWS {
+            map.push(format!("… {} more rows", bounds.height() - MAX_ROWS));
+        }
+
+        f.debug_struct("Grid")
+            .field("bounds", &bounds)
+            .field("len", &self.len())
+            .field("occupancy", &format_args!("{occupancy}"))
+            .field("map", &map)
+            .finish()
+    }
+}
+
+impl<T: Debug> Grid<T> {
+    // Every cell of the bounds as its `Debug` output, right aligned in
+    // columns of equal width, with `.` for empty cells and one line per row.
+    pub fn debug_grid(&self) -> String {
+        let bounds = self.bounds();
+        let cells: Vec<String> = bounds
+            .iter()
+            .map(|(x, y)| match self.get(x, y) {
+                Some(item) => format!("{item:?}"),
+                None => ".".to_string(),
+            })
+            .collect();
+        let width = cells
+            .iter()
+            .map(|cell| cell.chars().count())
+            .max()
+            .unwrap_or(1);
+
+        let mut out = String::new();
+        for row in cells.chunks(bounds.width()) {
+            let line: Vec<String> = row.iter().map(|cell| format!("{cell:>width$}")).collect();
+            out.push_str(&line.join(" "));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+
+    #[test]
+    fn summarizes_large_grids() {
+        let mut grid = Grid::new();
+        grid.set(0, 0, 1);
+        grid.set(1, 2, 2);
+        assert_eq!(
+            format!("{grid:?}"),
+            "Grid { bounds: Rect { min_x: 0, min_y: 0, max_x: 1, max_y: 2 }, len: 2, \
+             occupancy: 33.3%, map: [\"#..\", \"..#\"] }"
+        );
+
+        grid.set(99, 99, 3);
+        let debug = format!("{grid:#?}");
+        assert!(debug.contains("… 52 more columns"));
+        assert!(debug.contains("… 84 more rows"));
+        assert!(debug.lines().count() < 30);
+
+        let mut defaults = Grid::new_with_default(0);
+        defaults.set(0, 1, 7);
+        defaults.set(1, 0, 7);
+        let debug = format!("{defaults:?}");
+        assert!(debug.contains("occupancy: 50.0%, map: [\".#\", \"#.\"]"));
+    }
+
+    #[test]
+    fn lays_out_every_cell() {
+        let mut grid = Grid::new();
+        grid.set(0, 0, 5);
+        grid.set(1, 1, -12);
+        grid.set(0, 2, 300);
+        assert_eq!(grid.debug_grid(), "  5   . 300\n  . -12   .\n");
+    }
+}
@@ -0,0 +1,156 @@
+use super::Grid;
+use crate::rect::Rect;
+
+// A run of changed cells along y; None clears a cell.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Span<T> {
+    x: isize,
+    y: isize,
+    cells: Vec<Option<T>>,
+}
+
+// The changes turning one grid state into a later one. Changed cells are
+// grouped into runs along rows, so a patch only carries the coordinates of
+// each run rather than of every cell.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Delta<T> {
+    bounds: Rect,
+    spans: Vec<Span<T>>,
+}
+
+impl<T> Delta<T> {
+    // The number of changed cells.
+    pub fn len(&self) -> usize {
+        self.spans.iter().map(|span| span.cells.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+}
+
+impl<T: PartialEq + Clone> Grid<T> {
+    // The changes from `older` to this grid, including its bounds. Walks the
+    // stored cells of both side by side, so the cost follows the cells
+    // rather than the area they span.
+    pub fn delta_from(&self, older: &Grid<T>) -> Delta<T> {
+        let mut spans: Vec<Span<T>> = vec![];
+        let mut push = |(x, y): (isize, isize), cell: Option<&T>| match spans.last_mut() {
+            Some(span) if span.x == x && span.y + span.cells.len() as isize == y => {
+                span.cells.push(cell.cloned());
+            }
+            _ => spans.push(Span {
+                x,
+                y,
+                cells: vec![cell.cloned()],
+            }),
+        };
+
+        let (mut newer, mut older) = (self.iter().peekable(), older.iter().peekable());
+        loop {
+            match (newer.peek(), older.peek()) {
+                (Some(&(at, cell)), Some(&(before, _))) if at < before => {
+                    push(at, Some(cell));
+                    newer.next();
+                }
+                (Some(&(at, cell)), Some(&(before, old))) if at == before => {
+                    if cell != old {
+                        push(at, Some(cell));
+                    }
+                    newer.next();
+                    older.next();
+                }
+                (_, Some(&(before, _))) => {
+                    push(before, None);
+                    older.next();
+                }
+                (Some(&(at, cell)), None) => {
+                    push(at, Some(cell));
+                    newer.next();
+                }
+                (None, None) => break,
+            }
+        }
+
+        Delta {
+            bounds: self.bounds(),
+            spans,
+        }
+    }
+
+    // Applied to the grid the delta was taken from, gives the newer grid.
+    pub fn apply_delta(&mut self, delta: &Delta<T>) {
+        for span in &delta.spans {
+            for (y, cell) in (span.y..).zip(&span.cells) {
+                match cell {
+                    Some(item) => self.set(span.x, y, item.clone()),
+                    None => {
+                        self.remove(span.x, y);
+                    }
+                }
+            }
+        }
+
+        self.update_boundaries(delta.bounds.min_x, delta.bounds.min_y);
+        self.update_boundaries(delta.bounds.max_x, delta.bounds.max_y);
+        self.debug_validate();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+
+    #[test]
+    fn patches_older_states() {
+        let mut older = Grid::new();
+        for y in 0..10 {
+            older.set(0, y, y);
+            older.set(1, y, y);
+        }
+
+        let mut newer = older.clone();
+        for y in 2..6 {
+            newer.set(0, y, 0);
+        }
+        newer.remove(1, 9);
+        newer.set(-3, 12, 7);
+
+        let delta = newer.delta_from(&older);
+        assert_eq!(delta.len(), 6);
+        assert_eq!(delta.spans.len(), 3);
+
+        older.apply_delta(&delta);
+        assert_eq!(older.bounds(), newer.bounds());
+        assert!(older.iter().eq(newer.iter()));
+        assert!(newer.delta_from(&older).is_empty());
+    }
+
+    #[test]
+    fn follows_stored_cells() {
+        let mut older = Grid::new();
+        older.set(0, 0, 1);
+        older.set(100_000, 100_000, 2);
+        let mut newer = older.clone();
+        newer.set(100_000, 100_000, 3);
+        newer.remove(0, 0);
+
+        let delta = newer.delta_from(&older);
+        assert_eq!(delta.len(), 2);
+        older.apply_delta(&delta);
+        assert!(older.iter().eq(newer.iter()));
+
+        // Defaults are not stored, so they never show up as changes.
+        let mut older = Grid::new_with_default(0);
+        older.set(5, 5, 1);
+        let mut newer = older.clone();
+        newer.set(-5, -5, 2);
+        let delta = newer.delta_from(&older);
+        assert_eq!(delta.len(), 1);
+        older.apply_delta(&delta);
+        assert_eq!(older.get(-5, -5), Some(&2));
+        assert_eq!(older.get(0, 0), Some(&0));
+    }
+}
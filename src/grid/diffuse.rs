@@ -0,0 +1,172 @@
+use super::sample::resolve;
+use super::{Edge, Grid};
+use crate::direction::Direction;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Boundary {
+    // Everything outside the grid (and in its holes) is held at this value.
+    Fixed(f64),
+    // No flow across the edge of the grid.
+    Insulated,
+    // Opposite edges of the bounding rect touch.
+    Wrapping,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Relaxation {
+    Jacobi,
+    GaussSeidel,
+}
+
+impl Grid<f64> {
+    fn neighbor_sum(&self, x: isize, y: isize, boundary: Boundary) -> f64 {
+        let own = self.get(x, y).copied().unwrap_or(0.0);
+        let edge = match boundary {
+            Boundary::Fixed(value) => Edge::Constant(value),
+            Boundary::Insulated => Edge::Clamp,
+            Boundary::Wrapping => Edge::Wrap,
+        };
+
+        Direction::CARDINAL
+            .into_iter()
+            .map(|direction| {
+                let (dx, dy) = direction.offset();
+                resolve(self, x as i128 + dx as i128, y as i128 + dy as i128, edge).unwrap_or(own)
+            })
+            .sum()
+    }
+
+    pub fn diffuse(&self, alpha: f64, steps: usize) -> Grid<f64> {
+        self.diffuse_with(alpha, steps, Boundary::Insulated)
+    }
+
+    // Explicit finite differences; the scheme is stable for alpha up to 0.25.
+    pub fn diffuse_with(&self, alpha: f64, steps: usize, boundary: Boundary) -> Grid<f64> {
+        let mut grid = self.clone();
+
+        for _ in 0..steps {
+            grid = grid.step(|grid, x, y| {
+                let value = *grid.get(x, y)?;
+                Some(value + alpha * (grid.neighbor_sum(x, y, boundary) - 4.0 * value))
+            });
+        }
+
+        grid
+    }
+
+    // Iterates towards the steady state where every free cell is the average
+    // of its neighbors, leaving cells for which `fixed` holds untouched. Stops
+    // once no cell changes by more than `tolerance` and returns the number of
+    // iterations performed.
+    pub fn relax<F>(
+        &mut self,
+        method: Relaxation,
+        boundary: Boundary,
+        tolerance: f64,
+        max_iterations: usize,
+        fixed: F,
+    ) -> usize
+    where
+        F: Fn(isize, isize) -> bool,
+    {
+        let bounds = self.bounds();
+
+        for iteration in 1..=max_iterations {
+            let previous = match method {
+                Relaxation::Jacobi => Some(self.clone()),
+                Relaxation::GaussSeidel => None,
+            };
+            let mut change: f64 = 0.0;
+
+            for (x, y) in bounds.iter() {
+                if self.get(x, y).is_none() || fixed(x, y) {
+                    continue;
+                }
+
+                let source = previous.as_ref().unwrap_or(self);
+                let value = source.neighbor_sum(x, y, boundary) / 4.0;
+                let cell = self.get_mut(x, y).unwrap();
+                change = change.max((value - *cell).abs());
+                *cell = value;
+            }
+
+            if change <= tolerance {
+                return iteration;
+            }
+        }
+
+        max_iterations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Boundary, Grid, Relaxation};
+
+    fn hot_spot() -> Grid<f64> {
+        let mut grid = Grid::new();
+        for x in -4..=4 {
+            for y in -4..=4 {
+                grid.set(x, y, if (x, y) == (0, 0) { 100.0 } else { 0.0 });
+            }
+        }
+        grid
+    }
+
+    fn total(grid: &Grid<f64>) -> f64 {
+        grid.bounds()
+            .iter()
+            .filter_map(|(x, y)| grid.get(x, y))
+            .sum()
+    }
+
+    #[test]
+    fn insulated_diffusion_conserves_heat() {
+        let grid = hot_spot().diffuse(0.2, 50);
+
+        assert!((total(&grid) - 100.0).abs() < 1e-9);
+        assert!(grid.get(0, 0).unwrap() < &10.0);
+        assert_eq!(grid.get(4, 4), grid.get(-4, -4));
+    }
+
+    #[test]
+    fn boundaries_change_the_outcome() {
+        let cooled = hot_spot().diffuse_with(0.2, 200, Boundary::Fixed(0.0));
+        assert!(total(&cooled) < 50.0);
+
+        let wrapped = hot_spot().diffuse_with(0.2, 50, Boundary::Wrapping);
+        assert!((total(&wrapped) - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn relaxation_finds_linear_profile() {
+        let bar = || {
+            let mut grid = Grid::new();
+            for y in 0..=10 {
+                grid.set(0, y, if y == 10 { 10.0 } else { 0.0 });
+            }
+            grid
+        };
+        let ends = |_, y| y == 0 || y == 10;
+
+        let mut jacobi = bar();
+        let jacobi_iterations =
+            jacobi.relax(Relaxation::Jacobi, Boundary::Insulated, 1e-9, 10_000, ends);
+
+        let mut gauss_seidel = bar();
+        let gauss_seidel_iterations = gauss_seidel.relax(
+            Relaxation::GaussSeidel,
+            Boundary::Insulated,
+            1e-9,
+            10_000,
+            ends,
+        );
+
+        assert!(gauss_seidel_iterations < jacobi_iterations);
+        for grid in [jacobi, gauss_seidel] {
+            for y in 0..=10 {
+                assert!((grid.get(0, y).unwrap() - y as f64).abs() < 1e-5);
+            }
+        }
+    }
+}
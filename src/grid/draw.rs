@@ -0,0 +1,272 @@
+use super::Grid;
+use std::collections::BTreeMap;
+use std::f64::consts::TAU;
+
+// The outline of the axis-aligned ellipse around the origin with radius
+// `rx` along rows and `ry` along columns, by the midpoint algorithm. Only
+// the quarter with non-negative offsets is returned; the rest mirrors it.
+// An ellipse with a zero radius is the line along its other axis.
+fn ellipse_quarter(rx: usize, ry: usize) -> Vec<(isize, isize)> {
+    if rx == 0 {
+        return (0..=ry as isize).map(|u| (0, u)).collect();
+    }
+    if ry == 0 {
+        return (0..=rx as isize).map(|v| (v, 0)).collect();
+    }
+
+    let (a2, b2) = ((ry * ry) as f64, (rx * rx) as f64);
+    let (mut u, mut v) = (0.0, rx as f64);
+    let (mut du, mut dv) = (0.0, 2.0 * a2 * v);
+    let mut points = vec![];
+
+    let mut decision = b2 - a2 * rx as f64 + a2 / 4.0;
+    while du < dv {
+        points.push((v as isize, u as isize));
+        u += 1.0;
+        du += 2.0 * b2;
+        if decision < 0.0 {
+            decision += du + b2;
+        } else {
+            v -= 1.0;
+            dv -= 2.0 * a2;
+            decision += du - dv + b2;
+        }
+    }
+
+    decision = b2 * (u + 0.5) * (u + 0.5) + a2 * (v - 1.0) * (v - 1.0) - a2 * b2;
+    while v >= 0.0 {
+        points.push((v as isize, u as isize));
+        v -= 1.0;
+        dv -= 2.0 * a2;
+        if decision > 0.0 {
+            decision += a2 - dv;
+        } else {
+            u += 1.0;
+            du += 2.0 * b2;
+            decision += du - dv + a2;
+        }
+    }
+    points
+}
+
+fn mirrored(quarter: &[(isize, isize)]) -> impl Iterator<Item = (isize, isize)> + '_ {
+    quarter
+        .iter()
+        .flat_map(|&(v, u)| [(v, u), (v, -u), (-v, u), (-v, -u)])
+}
+
+// Drawing onto the grid for importing vector shapes. Shapes are given by the
+// cells their vertices sit on and cover every cell they pass through, like
+// pixels of a raster image.
+impl<T: Clone> Grid<T> {
+    // Bresenham's line from `a` to `b`, both included.
+    pub fn draw_line(&mut self, a: (isize, isize), b: (isize, isize), value: T) {
+        let (dx, dy) = (a.0.abs_diff(b.0) as isize, -(a.1.abs_diff(b.1) as isize));
+        let (sx, sy) = ((b.0 - a.0).signum(), (b.1 - a.1).signum());
+        let (mut x, mut y) = a;
+        let mut error = dx + dy;
+
+        loop {
+            self.set(x, y, value.clone());
+            if (x, y) == b {
+                break;
+            }
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += sx;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += sy;
+            }
+        }
+    }
+
+    // The closed outline through `vertices`, with the last vertex joined back
+    // to the first.
+    pub fn draw_polygon_outline(&mut self, vertices: &[(isize, isize)], value: T) {
+        for (i, &vertex) in vertices.iter().enumerate() {
+            let next = vertices[(i + 1) % vertices.len()];
+            self.draw_line(vertex, next, value.clone());
+        }
+    }
+
+    // The polygon with its outline. The interior is found by scanline with
+    // the even-odd rule, so self-intersecting polygons leave holes where
+    // they overlap themselves.
+    pub fn fill_polygon(&mut self, vertices: &[(isize, isize)], value: T) {
+        let Some(min_x) = vertices.iter().map(|&(x, _)| x).min() else {
+            return;
+        };
+        let max_x = vertices.iter().map(|&(x, _)| x).max().unwrap();
+
+        for x in min_x..=max_x {
+            // Edges count on their upper end only, so a vertex between two
+            // edges is crossed once.
+            let mut crossings: Vec<f64> = vertices
+                .iter()
+                .zip(vertices.iter().cycle().skip(1))
+                .filter(|(a, b)| (a.0 <= x) != (b.0 <= x))
+                .map(|(a, b)| {
+                    a.1 as f64 + (x - a.0) as f64 * (b.1 - a.1) as f64 / (b.0 - a.0) as f64
+                })
+                .collect();
+            crossings.sort_by(f64::total_cmp);
+
+            for span in crossings.chunks_exact(2) {
+                for y in span[0].ceil() as isize..=span[1].floor() as isize {
+                    self.set(x, y, value.clone());
+                }
+            }
+        }
+        self.draw_polygon_outline(vertices, value);
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn draw_ellipse(&mut self, center: (isize, isize), rx: usize, ry: usize, value: T) {
+        for (dx, dy) in mirrored(&ellipse_quarter(rx, ry)) {
+            self.set(center.0 + dx, center.1 + dy, value.clone());
+        }
+    }
+
+    // The ellipse with its inside, whole rows at a time.
+    pub fn fill_ellipse(&mut self, center: (isize, isize), rx: usize, ry: usize, value: T) {
+        let mut widths = BTreeMap::new();
+        for (v, u) in ellipse_quarter(rx, ry) {
+            let width = widths.entry(v).or_insert(u);
+            *width = u.max(*width);
+        }
+        for (v, u) in widths {
+            for dx in [-v, v] {
+                for dy in -u..=u {
+                    self.set(center.0 + dx, center.1 + dy, value.clone());
+                }
+            }
+        }
+    }
+
+    pub fn draw_circle(&mut self, center: (isize, isize), radius: usize, value: T) {
+        self.draw_ellipse(center, radius, radius, value);
+    }
+
+    pub fn fill_circle(&mut self, center: (isize, isize), radius: usize, value: T) {
+        self.fill_ellipse(center, radius, radius, value);
+    }
+
+    // The part of the circle from angle `start` counterclockwise to `end`,
+    // in radians, with 0 pointing East (increasing y) and a quarter turn
+    // pointing North (decreasing x). Angles a whole turn or more apart draw
+    // the full circle.
+    pub fn draw_arc(
+        &mut self,
+        center: (isize, isize),
+        radius: usize,
+        start: f64,
+        end: f64,
+        value: T,
+    ) {
+        let sweep = if (end - start).abs() >= TAU {
+            TAU
+        } else {
+            (end - start).rem_euclid(TAU)
+        };
+        for (dx, dy) in mirrored(&ellipse_quarter(radius, radius)) {
+            let angle = (-dx as f64).atan2(dy as f64);
+            if (angle - start).rem_euclid(TAU) <= sweep {
+                self.set(center.0 + dx, center.1 + dy, value.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use std::f64::consts::TAU;
+
+    #[test]
+    fn draws_lines_and_outlines() {
+        let mut grid = Grid::new();
+        grid.draw_line((-2, -3), (1, 3), ());
+        assert_eq!(grid.len(), 7);
+        assert!(grid.get(-2, -3).is_some() && grid.get(1, 3).is_some());
+
+        let mut outline = Grid::new();
+        outline.draw_polygon_outline(&[(-2, -2), (-2, 2), (2, 2), (2, -2)], ());
+        assert_eq!(outline.len(), 16);
+        assert!(outline.get(0, 0).is_none());
+    }
+
+    #[test]
+    fn fills_polygons_with_negative_coordinates() {
+        let mut grid = Grid::new();
+        grid.fill_polygon(&[(-4, -4), (-4, 0), (0, 0), (0, -4)], ());
+        assert_eq!(grid.len(), 25);
+
+        // A triangle: row x covers columns -3..=-3 + x.
+        let mut triangle = Grid::new();
+        triangle.fill_polygon(&[(0, -3), (3, -3), (3, 0)], ());
+        assert_eq!(triangle.len(), 10);
+        assert!(triangle.get(2, -1).is_some() && triangle.get(1, -1).is_none());
+
+        // A concave L shape.
+        let mut concave = Grid::new();
+        concave.fill_polygon(&[(0, 0), (0, 4), (2, 4), (2, 2), (4, 2), (4, 0)], ());
+        assert_eq!(concave.len(), 21);
+        assert!(concave.get(3, 3).is_none());
+    }
+
+    #[test]
+    fn draws_ellipses_and_arcs() {
+        let mut circle = Grid::new();
+        circle.draw_circle((0, 0), 5, ());
+        for ((x, y), _) in circle.iter() {
+            let distance = ((x * x + y * y) as f64).sqrt();
+            assert!((distance - 5.0).abs() < 0.75, "({x}, {y})");
+        }
+        assert!(circle.get(5, 0).is_some() && circle.get(0, -5).is_some());
+
+        let mut ellipse = Grid::new();
+        ellipse.fill_ellipse((10, -10), 2, 6, ());
+        assert!(ellipse.get(10, -16).is_some() && ellipse.get(8, -10).is_some());
+        assert!(ellipse.get(10, -10).is_some() && ellipse.get(11, -17).is_none());
+        assert!(ellipse.iter().all(|((x, y), _)| {
+            let (dx, dy) = ((x - 10) as f64 / 2.5, (y + 10) as f64 / 6.5);
+            dx * dx + dy * dy <= 1.0
+        }));
+
+        // The upper right quarter, from East to North.
+        let mut arc = Grid::new();
+        arc.draw_arc((0, 0), 4, 0.0, std::f64::consts::FRAC_PI_2, ());
+        assert!(arc.get(0, 4).is_some() && arc.get(-4, 0).is_some());
+        assert!(arc.iter().all(|((x, y), _)| x <= 0 && y >= 0));
+
+        let mut circle = Grid::new();
+        circle.draw_circle((0, 0), 4, ());
+        for (start, end) in [(0.0, TAU), (1.0, 1.0 - 3.0 * TAU)] {
+            let mut full = Grid::new();
+            full.draw_arc((0, 0), 4, start, end, ());
+            assert!(full.iter().eq(circle.iter()));
+        }
+    }
+
+    #[test]
+    fn degenerate_ellipses_are_lines() {
+        let mut row = Grid::new();
+        row.draw_ellipse((1, 1), 0, 3, ());
+        assert_eq!(row.len(), 7);
+        assert!(row.iter().all(|((x, _), _)| x == 1));
+        assert!(row.get(1, -2).is_some() && row.get(1, 4).is_some());
+
+        let mut column = Grid::new();
+        column.fill_ellipse((0, 0), 2, 0, ());
+        assert_eq!(column.len(), 5);
+        assert!(column.iter().all(|((_, y), _)| y == 0));
+
+        let mut point = Grid::new();
+        point.fill_circle((3, 3), 0, ());
+        assert_eq!(point.len(), 1);
+    }
+}
@@ -0,0 +1,525 @@
+use super::{Existence, Grid, NegativeIndexVec, NegativeIndexed};
+use crate::error::GridError;
+use crate::rect::Rect;
+
+pub enum Edit<T> {
+    Set(isize, isize, T),
+    Remove(isize, isize),
+}
+
+impl<T> Edit<T> {
+    fn position(&self) -> (isize, isize) {
+        match self {
+            Edit::Set(x, y, _) | Edit::Remove(x, y) => (*x, *y),
+        }
+    }
+}
+
+// The slots `storage` grows by to hold both `min` and `max`.
+fn growth_between<U: Default>(storage: &impl NegativeIndexed<U>, min: isize, max: isize) -> usize {
+    if min < 0 && max >= 0 {
+        storage.growth(min) + storage.growth(max)
+    } else {
+        storage.growth(min).max(storage.growth(max))
+    }
+}
+
+impl<T> Grid<T> {
+    // Exchanges two cells without cloning; either may be empty.
+    pub fn swap(&mut self, a: (isize, isize), b: (isize, isize)) {
+        if a == b {
+            return;
+        }
+
+        let first = self.remove(a.0, a.1);
+        let second = self.remove(b.0, b.1);
+
+        if let Some(item) = first {
+            self.set(b.0, b.1, item);
+        }
+        if let Some(item) = second {
+            self.set(a.0, a.1, item);
+        }
+    }
+
+    // Moves the value at `from` to `to` and returns what it replaced there.
+    // Moving an empty cell changes nothing and returns None.
+    pub fn move_cell(&mut self, from: (isize, isize), to: (isize, isize)) -> Option<T> {
+        if from == to {
+            return None;
+        }
+
+        let item = self.remove(from.0, from.1)?;
+        let replaced = self.remove(to.0, to.1);
+        self.set(to.0, to.1, item);
+        replaced
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn set_many<I>(&mut self, cells: I)
+    where
+        I: IntoIterator<Item = ((isize, isize), T)>,
+    {
+        self.apply_batch(
+            cells
+                .into_iter()
+                .map(|((x, y), item)| Edit::Set(x, y, item)),
+        );
+    }
+
+    // Applies the edits as if one after another. They are sorted by position
+    // first, so storage grows once per row instead of once per edit.
+    pub fn apply_batch<I>(&mut self, edits: I)
+    where
+        I: IntoIterator<Item = Edit<T>>,
+    {
+        if let Err(error) = self.try_apply_batch(edits) {
+            panic!("cannot apply batch: {error}");
+        }
+    }
+
+    // Stops at the first edit that fails; the edits sorted before it have
+    // been applied.
+    pub fn try_apply_batch<I>(&mut self, edits: I) -> Result<(), GridError>
+    where
+        I: IntoIterator<Item = Edit<T>>,
+    {
+        let mut edits: Vec<Edit<T>> = edits.into_iter().collect();
+        edits.sort_by_key(Edit::position);
+
+        let sets = edits.iter().filter(|edit| matches!(edit, Edit::Set(..)));
+        let (mut min_x, mut max_x) = (self.min_x, self.max_x);
+        let (mut min_y, mut max_y) = (self.min_y, self.max_y);
+        for (x, y) in sets.map(Edit::position) {
+            (min_x, max_x) = (min_x.min(x), max_x.max(x));
+            (min_y, max_y) = (min_y.min(y), max_y.max(y));
+        }
+
+        let fits =
+            |min: isize, max: isize| max.checked_sub(min).and_then(|span| span.checked_add(1));
+        let limited =
+            self.max_bounds.is_some() || self.memory_budget.is_some() || self.default.is_some();
+
+        if limited || fits(min_x, max_x).is_none() || fits(min_y, max_y).is_none() {
+            for edit in edits {
+                match edit {
+                    Edit::Set(x, y, item) => self.try_set(x, y, item)?,
+                    Edit::Remove(x, y) => {
+                        self.try_remove(x, y)?;
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        let capacity = |_| GridError::CapacityExceeded;
+        let rows = growth_between(self, min_x, max_x);
+        self.try_grow(min_x).map_err(capacity)?;
+        self.try_grow(max_x).map_err(capacity)?;
+        self.memory += rows * size_of::<Option<NegativeIndexVec<T>>>();
+
+        let mut edits = edits.into_iter().peekable();
+        while let Some(edit) = edits.next() {
+            let x = edit.position().0;
+            let mut row_edits = vec![edit];
+            while let Some(edit) = edits.next_if(|edit| edit.position().0 == x) {
+                row_edits.push(edit);
+            }
+
+            let columns = row_edits.iter().filter_map(|edit| match edit {
+                Edit::Set(_, y, _) => Some(*y),
+                Edit::Remove(..) => None,
+            });
+            let Some((min_y, max_y)) = columns.fold(None, |range, y| match range {
+                None => Some((y, y)),
+                Some((min, max)) => Some((y.min(min), y.max(max))),
+            }) else {
+                for edit in row_edits {
+                    let (x, y) = edit.position();
+                    self.remove(x, y);
+                }
+                continue;
+            };
+
+            let mut len = self.len;
+            let row = self.row_mut(x);
+            let cells = growth_between(row, min_y, max_y);
+            row.try_grow(min_y).map_err(capacity)?;
+            row.try_grow(max_y).map_err(capacity)?;
+
+            for edit in row_edits {
+                match edit {
+                    Edit::Set(_, y, item) => match row.try_set(y, item) {
+                        Ok(None) => len += 1,
+                        Ok(Some(_)) => {}
+                        Err(_) => {
+                            self.len = len;
+                            return Err(GridError::CapacityExceeded);
+                        }
+                    },
+                    Edit::Remove(_, y) => {
+                        if row.take(y).is_some() {
+                            len -= 1;
+                        }
+                    }
+                }
+            }
+
+            self.len = len;
+
+            self.memory += cells * size_of::<Option<T>>();
+            self.update_boundaries(x, min_y);
+            self.update_boundaries(x, max_y);
+        }
+
+        self.debug_validate();
+        Ok(())
+    }
+}
+
+// Rows are x and columns y. The bounds only ever grow, so removing a row or
+// column leaves them as they were; cells shifted past the maximum bounds are
+// dropped.
+impl<T> Grid<T> {
+    // Takes out the stored cells picked by `moved` and puts each one back at
+    // its target, so the cost follows the stored cells rather than the area
+    // of the bounds. Cells with no target, or one outside the maximum bounds,
+    // are dropped.
+    fn relocate<P, F>(&mut self, moved: P, target: F)
+    where
+        P: Fn(isize, isize) -> bool,
+        F: Fn(isize, isize) -> Option<(isize, isize)>,
+    {
+        let cells: Vec<(isize, isize)> = self
+            .iter()
+            .map(|(position, _)| position)
+            .filter(|&(x, y)| moved(x, y))
+            .collect();
+        let items: Vec<((isize, isize), T)> = cells
+            .into_iter()
+            .filter_map(|(x, y)| Some(((x, y), self.remove(x, y)?)))
+            .collect();
+
+        for ((x, y), item) in items {
+            let target = target(x, y)
+                .filter(|&(x, y)| self.max_bounds.is_none_or(|bounds| bounds.contains(x, y)));
+            if let Some((x, y)) = target {
+                self.set(x, y, item);
+            }
+        }
+    }
+
+    // Shifts row `x` and every row after it one row further.
+    pub fn insert_row(&mut self, x: isize) {
+        self.relocate(|from, _| from >= x, |x, y| Some((x.checked_add(1)?, y)));
+    }
+
+    // Deletes row `x` and shifts the rows after it back by one.
+    pub fn remove_row(&mut self, x: isize) {
+        self.relocate(
+            |from, _| from >= x,
+            |from, y| (from != x).then(|| (from - 1, y)),
+        );
+    }
+
+    pub fn insert_column(&mut self, y: isize) {
+        self.relocate(|_, from| from >= y, |x, y| Some((x, y.checked_add(1)?)));
+    }
+
+    pub fn remove_column(&mut self, y: isize) {
+        self.relocate(
+            |_, from| from >= y,
+            |x, from| (from != y).then(|| (x, from - 1)),
+        );
+    }
+}
+
+impl<T> NegativeIndexVec<T> {
+    // The existing slots for indices `min..=max`, negative ones first.
+    fn slots_mut(&mut self, min: isize, max: isize) -> [&mut [Option<T>]; 2] {
+        let negative: &mut [Option<T>] = if min < 0 {
+            let end = min.unsigned_abs().min(self.negative.len());
+            let start = if max < 0 { max.unsigned_abs() - 1 } else { 0 };
+            &mut self.negative[start.min(end)..end]
+        } else {
+            &mut []
+        };
+        let positive: &mut [Option<T>] = if max >= 0 {
+            let end = (max as usize + 1).min(self.positive.len());
+            &mut self.positive[(min.max(0) as usize).min(end)..end]
+        } else {
+            &mut []
+        };
+        [negative, positive]
+    }
+}
+
+// Bulk edits working on whole row slices instead of cell by cell. Grids with
+// limits, defaults or wrapping fall back to single edits.
+impl<T: Clone> Grid<T> {
+    pub fn fill_rect(&mut self, rect: Rect, value: T) {
+        if let Err(error) = self.try_fill_rect(rect, value) {
+            panic!("cannot fill {rect:?}: {error}");
+        }
+    }
+
+    // Stops at the first cell that fails; the rows before it have been
+    // filled.
+    pub fn try_fill_rect(&mut self, rect: Rect, value: T) -> Result<(), GridError> {
+        if let Some(default) = &self.default {
+            if (self.is_default)(&value, default) && self.max_bounds.is_none() {
+                self.clear_rect(rect);
+                return Ok(());
+            }
+        }
+        let limited =
+            self.max_bounds.is_some() || self.memory_budget.is_some() || self.default.is_some();
+        if limited {
+            for (x, y) in rect.iter() {
+                self.try_set(x, y, value.clone())?;
+            }
+            return Ok(());
+        }
+
+        self.check_overflow(rect.min_x, rect.min_y)?;
+        self.check_overflow(rect.max_x, rect.max_y)?;
+
+        let capacity = |_| GridError::CapacityExceeded;
+        let rows = growth_between(self, rect.min_x, rect.max_x);
+        self.try_grow(rect.min_x).map_err(capacity)?;
+        self.try_grow(rect.max_x).map_err(capacity)?;
+        self.memory += rows * size_of::<Option<NegativeIndexVec<T>>>();
+
+        for x in rect.min_x..=rect.max_x {
+            let row = self.row_mut(x);
+            let cells = growth_between(row, rect.min_y, rect.max_y);
+            row.try_grow(rect.min_y).map_err(capacity)?;
+            row.try_grow(rect.max_y).map_err(capacity)?;
+
+            let mut added = 0;
+            for slots in row.slots_mut(rect.min_y, rect.max_y) {
+                added += slots.iter().filter(|slot| slot.is_none()).count();
+                slots.fill(Some(value.clone()));
+            }
+            self.len += added;
+            self.memory += cells * size_of::<Option<T>>();
+        }
+
+        self.update_boundaries(rect.min_x, rect.min_y);
+        self.update_boundaries(rect.max_x, rect.max_y);
+        self.debug_validate();
+        Ok(())
+    }
+}
+
+impl<T> Grid<T> {
+    // Removes every cell of `rect`, returning how many were stored. The
+    // bounds stay as they were. Only the part of `rect` inside the bounds is
+    // visited, so clearing a huge rect costs no more than clearing the grid.
+    pub fn clear_rect(&mut self, rect: Rect) -> usize {
+        if let Some(period) = self.max_bounds.filter(|_| self.wrapping) {
+            // Any stored cell that `rect` reaches after wrapping.
+            let covers = |value: isize, min: isize, max: isize, size: usize| {
+                let span = max as i128 - min as i128 + 1;
+                span >= size as i128
+                    || (value as i128 - min as i128).rem_euclid(size as i128) < span
+            };
+            let covered: Vec<(isize, isize)> = self
+                .iter()
+                .map(|(position, _)| position)
+                .filter(|&(x, y)| {
+                    covers(x, rect.min_x, rect.max_x, period.height())
+                        && covers(y, rect.min_y, rect.max_y, period.width())
+                })
+                .collect();
+            return covered
+                .into_iter()
+                .filter(|&(x, y)| self.remove(x, y).is_some())
+                .count();
+        }
+
+        let Some(rect) = rect.intersection(&self.bounds()) else {
+            return 0;
+        };
+        let mut removed = 0;
+        for x in rect.min_x..=rect.max_x {
+            let row = match self.existence(x) {
+                Existence::Positive => self.positive[x as usize].as_mut(),
+                Existence::Negative => self.negative[x.unsigned_abs() - 1].as_mut(),
+                Existence::Nonexistent => None,
+            };
+            let Some(row) = row else {
+                continue;
+            };
+
+            for slots in row.slots_mut(rect.min_y, rect.max_y) {
+                removed += slots.iter().filter(|slot| slot.is_some()).count();
+                slots.fill_with(|| None);
+            }
+        }
+
+        self.len -= removed;
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Edit, Grid};
+    use crate::grid::GridBuilder;
+    use crate::rect::Rect;
+
+    #[test]
+    fn swaps_and_moves() {
+        let mut grid = Grid::new();
+        grid.set(0, 0, String::from("knight"));
+        grid.set(1, 1, String::from("rook"));
+
+        grid.swap((0, 0), (1, 1));
+        assert_eq!(grid.get(0, 0).map(String::as_str), Some("rook"));
+
+        grid.swap((0, 0), (-3, 2));
+        assert_eq!(grid.get(0, 0), None);
+        assert_eq!(grid.get(-3, 2).map(String::as_str), Some("rook"));
+
+        assert_eq!(grid.move_cell((5, 5), (1, 1)), None);
+        assert_eq!(grid.get(1, 1).map(String::as_str), Some("knight"));
+
+        let taken = grid.move_cell((-3, 2), (1, 1));
+        assert_eq!(taken.as_deref(), Some("knight"));
+        assert_eq!(grid.get(-3, 2), None);
+        assert_eq!(grid.get(1, 1).map(String::as_str), Some("rook"));
+        assert_eq!(grid.move_cell((1, 1), (1, 1)), None);
+    }
+
+    #[test]
+    fn batches_match_single_edits() {
+        let edits = || {
+            vec![
+                Edit::Set(3, -4, 'a'),
+                Edit::Set(-2, 7, 'b'),
+                Edit::Remove(3, -4),
+                Edit::Set(3, -4, 'c'),
+                Edit::Set(0, 0, 'd'),
+                Edit::Remove(-2, 7),
+                Edit::Remove(9, 9),
+            ]
+        };
+
+        let mut single = Grid::new();
+        for edit in edits() {
+            match edit {
+                Edit::Set(x, y, item) => single.set(x, y, item),
+                Edit::Remove(x, y) => {
+                    single.remove(x, y);
+                }
+            }
+        }
+
+        let mut batched = Grid::new();
+        batched.apply_batch(edits());
+
+        assert_eq!(batched.bounds(), single.bounds());
+        assert_eq!(batched.memory_usage(), single.memory_usage());
+        assert_eq!(batched.len(), single.len());
+        for (x, y) in Rect::new(-3, -5, 10, 10).iter() {
+            assert_eq!(batched.get(x, y), single.get(x, y));
+        }
+
+        let mut limited = Grid::with_max_bounds(Rect::new(0, 0, 4, 4));
+        limited.set_many([((1, 1), 1), ((4, 4), 2)]);
+        assert_eq!(limited.get(4, 4), Some(&2));
+        assert!(limited.try_apply_batch([Edit::Set(5, 5, 3)]).is_err());
+    }
+
+    fn rows(grid: &Grid<char>) -> Vec<String> {
+        let bounds = grid.bounds();
+        (bounds.min_x..=bounds.max_x)
+            .map(|x| {
+                (bounds.min_y..=bounds.max_y)
+                    .map(|y| grid.get(x, y).copied().unwrap_or('.'))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn inserts_and_removes_rows_and_columns() {
+        let mut grid = Grid::new();
+        for (x, row) in ["ab", "cd"].iter().enumerate() {
+            for (y, cell) in row.chars().enumerate() {
+                grid.set(x as isize, y as isize, cell);
+            }
+        }
+
+        grid.insert_row(1);
+        assert_eq!(rows(&grid), ["ab", "..", "cd"]);
+        grid.insert_column(0);
+        assert_eq!(rows(&grid), [".ab", "...", ".cd"]);
+
+        grid.remove_row(0);
+        assert_eq!(rows(&grid), ["...", ".cd", "..."]);
+        grid.remove_column(1);
+        assert_eq!(rows(&grid), ["...", ".d.", "..."]);
+
+        let mut limited = Grid::with_max_bounds(Rect::new(0, 0, 1, 1));
+        limited.set(1, 1, 'x');
+        limited.insert_row(0);
+        assert_eq!(limited.get(1, 1), None);
+
+        let mut sparse = Grid::new_with_default('.');
+        sparse.set(-5, -5, 'a');
+        sparse.set(5, 5, 'b');
+        sparse.insert_row(0);
+        sparse.remove_column(-5);
+        assert_eq!(sparse.len(), 1);
+        assert_eq!(sparse.get(6, 4), Some(&'b'));
+        assert_eq!(sparse.get(5, 5), Some(&'.'));
+    }
+
+    #[test]
+    fn fills_and_clears_whole_rows() {
+        let region = Rect::new(-3, -5, 2, 4);
+        let mut filled = Grid::new();
+        filled.set(0, 0, 'x');
+        filled.fill_rect(region, 'a');
+
+        let mut single = Grid::new();
+        single.set(0, 0, 'x');
+        for (x, y) in region.iter() {
+            single.set(x, y, 'a');
+        }
+        assert_eq!(filled.bounds(), single.bounds());
+        assert_eq!(filled.len(), single.len());
+        assert_eq!(filled.memory_usage(), single.memory_usage());
+        assert!(filled.iter().eq(single.iter()));
+
+        assert_eq!(filled.clear_rect(Rect::new(-1, -10, 0, 0)), 12);
+        assert_eq!(filled.clear_rect(Rect::new(50, 50, 60, 60)), 0);
+        assert_eq!(filled.len(), 60 - 12);
+        assert_eq!(filled.get(0, 0), None);
+        assert_eq!(filled.get(0, 1), Some(&'a'));
+        assert_eq!(filled.bounds(), region);
+        let everything = Rect::new(isize::MIN, isize::MIN, isize::MAX, isize::MAX);
+        assert_eq!(filled.clear_rect(everything), 48);
+        assert!(filled.is_empty());
+
+        let mut wrapping = GridBuilder::new()
+            .wrapping(Rect::new(0, 0, 3, 3))
+            .build()
+            .unwrap();
+        wrapping.fill_rect(Rect::new(0, 0, 3, 3), 'w');
+        assert_eq!(wrapping.clear_rect(Rect::new(3, -1, 4, 0)), 4);
+        assert_eq!(wrapping.get(0, 3), None);
+        assert_eq!(wrapping.clear_rect(everything), 12);
+
+        let mut defaults = Grid::new_with_default('.');
+        defaults.set(1, 1, 'a');
+        defaults.fill_rect(everything, '.');
+        assert!(defaults.is_empty());
+
+        let mut limited = Grid::with_max_bounds(Rect::new(0, 0, 3, 3));
+        assert!(limited.try_fill_rect(Rect::new(2, 2, 4, 4), 1).is_err());
+    }
+}
@@ -0,0 +1,201 @@
+use super::Grid;
+use crate::connectivity::{step, Connectivity};
+use crate::visited::VisitedGrid;
+use std::collections::VecDeque;
+
+impl<T> Grid<T> {
+    // The 4-connected region of cells matching the predicate that contains
+    // `start`, in breadth-first order.
+    pub fn flood_fill<F>(&self, start: (isize, isize), predicate: F) -> Vec<(isize, isize)>
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.flood_fill_with(start, &Connectivity::Four, predicate)
+    }
+
+    pub fn flood_fill_with<F>(
+        &self,
+        start: (isize, isize),
+        connectivity: &Connectivity,
+        predicate: F,
+    ) -> Vec<(isize, isize)>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let bounds = self.bounds();
+        let inside = |(x, y): (isize, isize)| {
+            bounds.contains(x, y) && self.get(x, y).is_some_and(&predicate)
+        };
+
+        if !inside(start) {
+            return vec![];
+        }
+
+        let mut seen = VisitedGrid::new();
+        seen.insert(start.0, start.1);
+        let mut queue = VecDeque::from([start]);
+        let mut region = vec![];
+        let offsets = connectivity.offsets();
+
+        while let Some((x, y)) = queue.pop_front() {
+            region.push((x, y));
+
+            for &(dx, dy) in &offsets {
+                let Some(next) = step((x, y), (dx, dy)) else {
+                    continue;
+                };
+
+                if inside(next) && seen.insert(next.0, next.1) {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        region
+    }
+
+    // Flood fill generalized to biome growth and territory expansion:
+    // spreads breadth-first from all `seeds` at once over 4-connected stored
+    // cells, entering a cell when `admit(from, to)` accepts the step, and
+    // stops once `max_cells` cells are taken. Seeds that are empty cells are
+    // skipped; the result is in the order cells were taken.
+    pub fn grow_region<F>(
+        &self,
+        seeds: &[(isize, isize)],
+        admit: F,
+        max_cells: usize,
+    ) -> Vec<(isize, isize)>
+    where
+        F: FnMut(((isize, isize), &T), ((isize, isize), &T)) -> bool,
+    {
+        self.grow_region_with(seeds, &Connectivity::Four, admit, max_cells)
+    }
+
+    pub fn grow_region_with<F>(
+        &self,
+        seeds: &[(isize, isize)],
+        connectivity: &Connectivity,
+        mut admit: F,
+        max_cells: usize,
+    ) -> Vec<(isize, isize)>
+    where
+        F: FnMut(((isize, isize), &T), ((isize, isize), &T)) -> bool,
+    {
+        let offsets = connectivity.offsets();
+        let bounds = self.bounds();
+        let cell = |x, y| bounds.contains(x, y).then(|| self.get(x, y)).flatten();
+        let mut seen = VisitedGrid::new();
+        let mut queue = VecDeque::new();
+        for &(x, y) in seeds {
+            if cell(x, y).is_some() && seen.insert(x, y) {
+                queue.push_back((x, y));
+            }
+        }
+
+        let mut region = vec![];
+        while let Some((x, y)) = queue.pop_front() {
+            if region.len() == max_cells {
+                break;
+            }
+            region.push((x, y));
+            let from = ((x, y), cell(x, y).unwrap());
+
+            for &(dx, dy) in &offsets {
+                let Some(next) = step((x, y), (dx, dy)) else {
+                    continue;
+                };
+                let Some(item) = cell(next.0, next.1) else {
+                    continue;
+                };
+                if !seen.contains(next.0, next.1) && admit(from, (next, item)) {
+                    seen.insert(next.0, next.1);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        region
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::connectivity::Connectivity;
+
+    #[test]
+    fn fill_stops_at_walls() {
+        let mut grid = Grid::new();
+        for x in -3..=3 {
+            for y in -3..=3 {
+                grid.set(x, y, x != 0);
+            }
+        }
+
+        let mut region = grid.flood_fill((-2, 1), |open| *open);
+        region.sort();
+
+        assert_eq!(region.len(), 21);
+        assert!(region.iter().all(|&(x, _)| x < 0));
+        assert_eq!(region[0], (-3, -3));
+        assert!(grid.flood_fill((0, 0), |open| *open).is_empty());
+    }
+
+    #[test]
+    fn fills_sparse_grids_without_sizing_to_the_bounds() {
+        let mut grid = Grid::new();
+        for (x, y) in [(0, 0), (0, 1), (3_000_000, 3_000_000)] {
+            grid.set(x, y, true);
+        }
+        assert_eq!(grid.flood_fill((0, 0), |&open| open), [(0, 0), (0, 1)]);
+        let far = grid.grow_region(&[(3_000_000, 3_000_000)], |_, _| true, 10);
+        assert_eq!(far, [(3_000_000, 3_000_000)]);
+    }
+
+    #[test]
+    fn stays_within_the_bounds_of_default_grids() {
+        let mut grid = Grid::new_with_default('.');
+        grid.set(0, 0, '#');
+        grid.set(2, 3, '#');
+        assert_eq!(grid.flood_fill((1, 1), |&cell| cell == '.').len(), 10);
+        assert!(grid.flood_fill((5, 5), |&cell| cell == '.').is_empty());
+        let grown = grid.grow_region(&[(1, 1)], |_, _| true, usize::MAX);
+        assert_eq!(grown.len(), 12);
+    }
+
+    #[test]
+    fn growth_respects_admission_and_cap() {
+        let mut grid = Grid::new();
+        for y in 0..10 {
+            grid.set(0, y, y);
+        }
+
+        // Only climbs, by at most 1 per step.
+        let region = grid.grow_region(
+            &[(0, 4)],
+            |(_, from), (_, to)| to.abs_diff(*from) <= 1 && to >= from,
+            100,
+        );
+        assert_eq!(region, [(0, 4), (0, 5), (0, 6), (0, 7), (0, 8), (0, 9)]);
+
+        let capped = grid.grow_region(&[(0, 0), (0, 9)], |_, _| true, 4);
+        assert_eq!(capped, [(0, 0), (0, 9), (0, 1), (0, 8)]);
+        assert!(grid.grow_region(&[(5, 5)], |_, _| true, 4).is_empty());
+    }
+
+    #[test]
+    fn fills_with_other_connectivity() {
+        let mut grid = Grid::new();
+        for (x, y) in [(0, 0), (1, 1), (2, 2), (0, 2)] {
+            grid.set(x, y, true);
+        }
+        assert_eq!(grid.flood_fill((0, 0), |&open| open).len(), 1);
+        let eight = grid.flood_fill_with((0, 0), &Connectivity::Eight, |&open| open);
+        assert_eq!(eight.len(), 4);
+        let knight = grid.flood_fill_with((0, 0), &Connectivity::Knight, |&open| open);
+        assert_eq!(knight.len(), 1);
+        let jumps = Connectivity::Custom(vec![(0, 2)]);
+        let reached = grid.grow_region_with(&[(0, 0)], &jumps, |_, _| true, 10);
+        assert_eq!(reached, [(0, 0), (0, 2)]);
+    }
+}
@@ -0,0 +1,244 @@
+use super::Grid;
+use crate::connectivity::{step, Connectivity};
+use crate::coordset::CoordSet;
+use std::collections::{HashMap, VecDeque};
+
+type Cell = (isize, isize);
+
+// A minimum cut between two sets of cells.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MinCut {
+    // The total capacity of the cut, equal to the maximum flow.
+    pub value: f64,
+    // The cells a source still reaches once the cut edges are removed.
+    pub source_side: CoordSet,
+    // The cut edges, each leading out of the source side.
+    pub edges: Vec<(Cell, Cell)>,
+}
+
+// A residual network in which edge `e ^ 1` is the reverse of edge `e`.
+struct Network {
+    adjacent: Vec<Vec<usize>>,
+    targets: Vec<usize>,
+    capacities: Vec<f64>,
+}
+
+impl Network {
+    fn add_edge(&mut self, from: usize, to: usize, capacity: f64) {
+        self.adjacent[from].push(self.targets.len());
+        self.targets.push(to);
+        self.capacities.push(capacity);
+        self.adjacent[to].push(self.targets.len());
+        self.targets.push(from);
+        self.capacities.push(0.0);
+    }
+
+    // Distances from `source` over edges with capacity left.
+    fn levels(&self, source: usize) -> Vec<usize> {
+        let mut levels = vec![usize::MAX; self.adjacent.len()];
+        levels[source] = 0;
+        let mut queue = VecDeque::from([source]);
+        while let Some(node) = queue.pop_front() {
+            for &edge in &self.adjacent[node] {
+                let next = self.targets[edge];
+                if self.capacities[edge] > 0.0 && levels[next] == usize::MAX {
+                    levels[next] = levels[node] + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+        levels
+    }
+
+    // Dinic's algorithm, with an explicit stack so long paths cannot
+    // overflow the call stack.
+    fn max_flow(&mut self, source: usize, sink: usize) -> f64 {
+        let mut flow = 0.0;
+        loop {
+            let mut levels = self.levels(source);
+            if levels[sink] == usize::MAX {
+                return flow;
+            }
+            let mut current = vec![0; self.adjacent.len()];
+
+            loop {
+                let mut stack = vec![source];
+                let mut path = vec![];
+                while let Some(&node) = stack.last() {
+                    if node == sink {
+                        break;
+                    }
+                    let edges = &self.adjacent[node];
+                    while current[node] < edges.len() {
+                        let edge = edges[current[node]];
+                        if self.capacities[edge] > 0.0
+                            && levels[self.targets[edge]] == levels[node] + 1
+                        {
+                            break;
+                        }
+                        current[node] += 1;
+                    }
+                    if current[node] == edges.len() {
+                        levels[node] = usize::MAX;
+                        stack.pop();
+                        path.pop();
+                    } else {
+                        let edge = edges[current[node]];
+                        path.push(edge);
+                        stack.push(self.targets[edge]);
+                    }
+                }
+                if stack.is_empty() {
+                    break;
+                }
+
+                let bottleneck = path
+                    .iter()
+                    .map(|&edge| self.capacities[edge])
+                    .fold(f64::INFINITY, f64::min);
+                for &edge in &path {
+                    self.capacities[edge] -= bottleneck;
+                    self.capacities[edge ^ 1] += bottleneck;
+                }
+                flow += bottleneck;
+            }
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    // Treats the stored cells as a flow network where each 4-connected step
+    // from one cell to another can carry `capacity(from, to)`, and cuts the
+    // cheapest set of steps separating every source from every sink. Narrow
+    // doorways show up as small cuts, and with capacities that are high
+    // between similar cells it splits a map into two coherent parts.
+    // Capacities must be finite, and zero or negative ones mean no edge. A
+    // cell that is both a source and a sink cannot be cut off, giving an
+    // infinite value and no edges.
+    pub fn min_cut<F>(&self, sources: &[Cell], sinks: &[Cell], capacity: F) -> MinCut
+    where
+        F: FnMut((Cell, &T), (Cell, &T)) -> f64,
+    {
+        self.min_cut_with(sources, sinks, &Connectivity::Four, capacity)
+    }
+
+    pub fn min_cut_with<F>(
+        &self,
+        sources: &[Cell],
+        sinks: &[Cell],
+        connectivity: &Connectivity,
+        mut capacity: F,
+    ) -> MinCut
+    where
+        F: FnMut((Cell, &T), (Cell, &T)) -> f64,
+    {
+        if sources.iter().any(|source| sinks.contains(source)) {
+            return MinCut {
+                value: f64::INFINITY,
+                source_side: sources.iter().copied().collect(),
+                edges: vec![],
+            };
+        }
+
+        let cells: Vec<(Cell, &T)> = self.iter().collect();
+        let index: HashMap<Cell, usize> = cells
+            .iter()
+            .enumerate()
+            .map(|(node, &(position, _))| (position, node))
+            .collect();
+        let (source, sink) = (cells.len(), cells.len() + 1);
+        let mut network = Network {
+            adjacent: vec![vec![]; cells.len() + 2],
+            targets: vec![],
+            capacities: vec![],
+        };
+
+        let offsets = connectivity.offsets();
+        for (node, &((x, y), item)) in cells.iter().enumerate() {
+            for &(dx, dy) in &offsets {
+                let Some(next) = step((x, y), (dx, dy)) else {
+                    continue;
+                };
+                let Some(&neighbor) = index.get(&next) else {
+                    continue;
+                };
+                let amount = capacity(((x, y), item), cells[neighbor]);
+                if amount > 0.0 {
+                    network.add_edge(node, neighbor, amount);
+                }
+            }
+        }
+        for &cell in sources {
+            if let Some(&node) = index.get(&cell) {
+                network.add_edge(source, node, f64::INFINITY);
+            }
+        }
+        for &cell in sinks {
+            if let Some(&node) = index.get(&cell) {
+                network.add_edge(node, sink, f64::INFINITY);
+            }
+        }
+
+        let value = network.max_flow(source, sink);
+        let reached = network.levels(source);
+        let on_source_side = |node: usize| reached[node] != usize::MAX;
+
+        let mut source_side = CoordSet::new();
+        let mut edges = vec![];
+        for (node, &((x, y), _)) in cells.iter().enumerate() {
+            if !on_source_side(node) {
+                continue;
+            }
+            source_side.insert(x, y);
+            for &edge in &network.adjacent[node] {
+                let next = network.targets[edge];
+                if edge % 2 == 0 && next < cells.len() && !on_source_side(next) {
+                    edges.push(((x, y), cells[next].0));
+                }
+            }
+        }
+
+        MinCut {
+            value,
+            source_side,
+            edges,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+
+    fn parse(rows: &[&str]) -> Grid<char> {
+        let mut grid = Grid::new();
+        for (x, row) in rows.iter().enumerate() {
+            for (y, cell) in row.chars().enumerate() {
+                grid.set(x as isize, y as isize, cell);
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn finds_the_doorway() {
+        let grid = parse(&["...#...", ".......", "...#...", "...#..."]);
+        let open = |(_, a): (_, &char), (_, b): (_, &char)| {
+            if *a == '.' && *b == '.' {
+                1.0
+            } else {
+                0.0
+            }
+        };
+
+        let cut = grid.min_cut(&[(0, 0), (3, 0)], &[(3, 6)], open);
+        assert_eq!(cut.value, 1.0);
+        assert_eq!(cut.edges.len(), 1);
+        assert!(cut.source_side.contains(3, 2));
+        assert!(!cut.source_side.contains(0, 6));
+
+        let wide = grid.min_cut(&[(0, 0)], &[(0, 2)], open);
+        assert_eq!(wide.value, 2.0);
+        assert!(grid.min_cut(&[(0, 0)], &[(0, 0)], open).value.is_infinite());
+    }
+}
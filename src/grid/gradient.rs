@@ -0,0 +1,115 @@
+use super::Grid;
+
+impl Grid<f64> {
+    fn derivative(&self, x: isize, y: isize, dx: isize, dy: isize) -> f64 {
+        let center = self.get(x, y).copied();
+        let before = self.get(x - dx, y - dy).copied();
+        let after = self.get(x + dx, y + dy).copied();
+
+        match (before, center, after) {
+            (Some(before), _, Some(after)) => (after - before) / 2.0,
+            (None, Some(center), Some(after)) => after - center,
+            (Some(before), Some(center), None) => center - before,
+            _ => 0.0,
+        }
+    }
+
+    // Central differences where both neighbors exist, one-sided differences
+    // at edges and next to empty cells. Only stored cells get a gradient;
+    // unstored neighbors still read as the default.
+    pub fn gradient(&self) -> Grid<(f64, f64)> {
+        let mut grid = Grid::with_boundaries_of(self);
+
+        for ((x, y), _) in self.iter() {
+            grid.set(
+                x,
+                y,
+                (self.derivative(x, y, 1, 0), self.derivative(x, y, 0, 1)),
+            );
+        }
+
+        grid
+    }
+
+    // Steepness in radians, 0 for flat cells.
+    pub fn slope(&self) -> Grid<f64> {
+        let gradient = self.gradient();
+        let mut grid = Grid::with_boundaries_of(self);
+
+        for ((x, y), (gx, gy)) in gradient.iter() {
+            grid.set(x, y, gx.hypot(*gy).atan());
+        }
+
+        grid
+    }
+
+    // Direction of steepest descent as an angle in radians, measured from the
+    // positive x axis towards the positive y axis. Flat cells have no aspect
+    // and are left empty.
+    pub fn aspect(&self) -> Grid<f64> {
+        let gradient = self.gradient();
+        let mut grid = Grid::with_boundaries_of(self);
+
+        for ((x, y), &(gx, gy)) in gradient.iter() {
+            if gx != 0.0 || gy != 0.0 {
+                grid.set(x, y, (-gy).atan2(-gx));
+            }
+        }
+
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    fn plane(a: f64, b: f64) -> Grid<f64> {
+        let mut grid = Grid::new();
+        for x in -3..=3 {
+            for y in -3..=3 {
+                grid.set(x, y, a * x as f64 + b * y as f64);
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn gradient_of_plane() {
+        let gradient = plane(2.0, -1.0).gradient();
+
+        for x in -3..=3 {
+            for y in -3..=3 {
+                assert_eq!(gradient.get(x, y), Some(&(2.0, -1.0)));
+            }
+        }
+    }
+
+    #[test]
+    fn slope_and_aspect() {
+        let grid = plane(1.0, 0.0);
+
+        assert!((grid.slope().get(0, 0).unwrap() - FRAC_PI_4).abs() < 1e-12);
+        assert!((grid.aspect().get(0, 0).unwrap().abs() - PI).abs() < 1e-12);
+        assert!((plane(0.0, -3.0).aspect().get(2, 2).unwrap() - FRAC_PI_2).abs() < 1e-12);
+
+        let flat = plane(0.0, 0.0);
+        assert_eq!(flat.slope().get(1, 1), Some(&0.0));
+        assert_eq!(flat.aspect().get(1, 1), None);
+    }
+
+    #[test]
+    fn only_stored_cells_get_a_gradient() {
+        let mut grid = Grid::new_with_default(0.0);
+        grid.set(0, 0, 1.0);
+        grid.set(0, 1, 3.0);
+        grid.set(9, 9, 5.0);
+
+        let gradient = grid.gradient();
+        assert_eq!(gradient.len(), 3);
+        assert_eq!(gradient.get(0, 0), Some(&(0.0, 1.5)));
+        assert_eq!(gradient.get(5, 5), None);
+        assert_eq!(grid.slope().len(), 3);
+    }
+}
@@ -0,0 +1,117 @@
+use super::Grid;
+use crate::rect::Rect;
+
+impl<T> Grid<T> {
+    // The stored cells matching the predicate; cells left at the default are
+    // never matched.
+    fn matching<'a, F>(&'a self, predicate: F) -> impl Iterator<Item = (isize, isize)> + 'a
+    where
+        F: Fn(&T) -> bool + 'a,
+    {
+        self.iter()
+            .filter(move |(_, cell)| predicate(cell))
+            .map(|(position, _)| position)
+    }
+
+    // The smallest rect containing every matching cell, as opposed to the
+    // grid bounds which only ever grow.
+    pub fn tight_bounding_rect<F>(&self, predicate: F) -> Option<Rect>
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.matching(predicate).fold(None, |rect, (x, y)| {
+            Some(match rect {
+                None => Rect::new(x, y, x, y),
+                Some(rect) => Rect::new(
+                    rect.min_x.min(x),
+                    rect.min_y.min(y),
+                    rect.max_x.max(x),
+                    rect.max_y.max(y),
+                ),
+            })
+        })
+    }
+
+    // Hull vertices of the matching cell coordinates without collinear points,
+    // counter-clockwise in (x, y) starting at the smallest coordinate.
+    pub fn convex_hull<F>(&self, predicate: F) -> Vec<(isize, isize)>
+    where
+        F: Fn(&T) -> bool,
+    {
+        // Cell iteration is row-major, so the points are already sorted.
+        let points: Vec<(isize, isize)> = self.matching(predicate).collect();
+        if points.len() < 3 {
+            return points;
+        }
+
+        let cross = |o: (isize, isize), a: (isize, isize), b: (isize, isize)| {
+            (a.0 - o.0) as i128 * (b.1 - o.1) as i128 - (a.1 - o.1) as i128 * (b.0 - o.0) as i128
+        };
+
+        let mut hull: Vec<(isize, isize)> = vec![];
+        for pass in [points.clone(), points.into_iter().rev().collect()] {
+            let start = hull.len();
+            for point in pass {
+                while hull.len() >= start + 2
+                    && cross(hull[hull.len() - 2], hull[hull.len() - 1], point) <= 0
+                {
+                    hull.pop();
+                }
+                hull.push(point);
+            }
+            hull.pop();
+        }
+
+        hull.dedup();
+        if hull.len() > 1 && hull.first() == hull.last() {
+            hull.pop();
+        }
+        hull
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::rect::Rect;
+
+    #[test]
+    fn hull_of_blob() {
+        let mut grid = Grid::new();
+        for (x, y) in Rect::new(-2, -2, 2, 2).iter() {
+            grid.set(x, y, x.abs() + y.abs() <= 2);
+        }
+        grid.set(10, 10, false);
+
+        assert_eq!(
+            grid.convex_hull(|cell| *cell),
+            vec![(-2, 0), (0, -2), (2, 0), (0, 2)]
+        );
+        assert_eq!(
+            grid.tight_bounding_rect(|cell| *cell),
+            Some(Rect::new(-2, -2, 2, 2))
+        );
+        assert_eq!(grid.bounds(), Rect::new(-2, -2, 10, 10));
+    }
+
+    #[test]
+    fn degenerate_hulls() {
+        let mut grid = Grid::new();
+        for y in 0..5 {
+            grid.set(1, y, ());
+        }
+
+        assert_eq!(grid.convex_hull(|_| true), vec![(1, 0), (1, 4)]);
+        assert_eq!(grid.tight_bounding_rect(|_| false), None);
+        assert!(grid.convex_hull(|_| false).is_empty());
+
+        let mut sparse = Grid::new_with_default(true);
+        sparse.set(0, 0, false);
+        sparse.set(8, 8, false);
+        assert_eq!(sparse.convex_hull(|cell| *cell), vec![]);
+        assert_eq!(
+            sparse.tight_bounding_rect(|cell| !*cell),
+            Some(Rect::new(0, 0, 8, 8))
+        );
+    }
+}
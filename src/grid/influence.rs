@@ -0,0 +1,90 @@
+use super::Grid;
+use crate::connectivity::{step, Connectivity};
+use crate::visited::VisitedGrid;
+use std::collections::VecDeque;
+
+impl<T> Grid<T> {
+    // An influence field for game AI: each source spreads its strength over
+    // the 4-connected passable cells, multiplied by `decay` per step and cut
+    // off after `max_range` steps. Fields of several sources add up, so
+    // negative strengths can mark threats next to positive desires. Cells no
+    // source reaches are left empty.
+    pub fn propagate_influence<F>(
+        &self,
+        sources: &[((isize, isize), f64)],
+        decay: f64,
+        max_range: usize,
+        passable: F,
+    ) -> Grid<f64>
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.propagate_influence_with(sources, decay, max_range, &Connectivity::Four, passable)
+    }
+
+    pub fn propagate_influence_with<F>(
+        &self,
+        sources: &[((isize, isize), f64)],
+        decay: f64,
+        max_range: usize,
+        connectivity: &Connectivity,
+        passable: F,
+    ) -> Grid<f64>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let offsets = connectivity.offsets();
+        let open = |(x, y): (isize, isize)| self.get(x, y).is_some_and(&passable);
+        let mut field = Grid::with_boundaries_of(self);
+        let mut seen = VisitedGrid::new();
+
+        for &(source, strength) in sources.iter().filter(|(source, _)| open(*source)) {
+            seen.clear();
+            seen.insert(source.0, source.1);
+            let mut queue = VecDeque::from([(source, 0, strength)]);
+
+            while let Some(((x, y), steps, influence)) = queue.pop_front() {
+                let total = field.get(x, y).copied().unwrap_or(0.0) + influence;
+                field.set(x, y, total);
+                if steps == max_range {
+                    continue;
+                }
+
+                for &(dx, dy) in &offsets {
+                    let Some(next) = step((x, y), (dx, dy)) else {
+                        continue;
+                    };
+                    if open(next) && seen.insert(next.0, next.1) {
+                        queue.push_back((next, steps + 1, influence * decay));
+                    }
+                }
+            }
+        }
+
+        field
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::rect::Rect;
+
+    #[test]
+    fn sources_decay_and_stack() {
+        let mut grid = Grid::new();
+        grid.fill_rect(Rect::new(0, 0, 0, 6), true);
+        grid.set(0, 3, false);
+
+        let field =
+            grid.propagate_influence(&[((0, 0), 8.0), ((0, 6), -4.0)], 0.5, 2, |&open| open);
+        assert_eq!(field.get(0, 0), Some(&8.0));
+        assert_eq!(field.get(0, 2), Some(&2.0));
+        assert_eq!(field.get(0, 3), None);
+        assert_eq!(field.get(0, 4), Some(&-1.0));
+
+        let stacked = grid.propagate_influence(&[((0, 0), 1.0), ((0, 1), 1.0)], 0.5, 6, |_| true);
+        assert_eq!(stacked.get(0, 1), Some(&1.5));
+        assert_eq!(stacked.get(0, 6), Some(&(0.5f64.powi(6) + 0.5f64.powi(5))));
+    }
+}
@@ -0,0 +1,194 @@
+use super::{Grid, NegativeIndexVec};
+use std::iter::FusedIterator;
+
+// Every iterator over a grid visits its stored cells in row-major order: x
+// ascending, then y ascending within a row, straight across the boundary
+// between negative and non-negative coordinates. Grids with a default value
+// only visit the cells not holding it.
+
+// Pairs the slots of a negative/non-negative pair of vectors with their
+// coordinate, in ascending order.
+fn ascending<N, P>(negative: N, positive: P) -> impl DoubleEndedIterator<Item = (isize, N::Item)>
+where
+    N: DoubleEndedIterator + ExactSizeIterator,
+    P: DoubleEndedIterator<Item = N::Item> + ExactSizeIterator,
+{
+    let negative = negative
+        .enumerate()
+        .rev()
+        .map(|(i, item)| (-(i as isize) - 1, item));
+    let positive = positive.enumerate().map(|(i, item)| (i as isize, item));
+    negative.chain(positive)
+}
+
+pub struct Cells<'a, Item> {
+    cells: Box<dyn DoubleEndedIterator<Item = Item> + 'a>,
+    remaining: usize,
+}
+
+pub type Iter<'a, T> = Cells<'a, ((isize, isize), &'a T)>;
+pub type IterMut<'a, T> = Cells<'a, ((isize, isize), &'a mut T)>;
+pub type IntoIter<T> = std::vec::IntoIter<((isize, isize), T)>;
+
+impl<Item> Iterator for Cells<'_, Item> {
+    type Item = Item;
+
+    fn next(&mut self) -> Option<Item> {
+        let item = self.cells.next()?;
+        self.remaining -= 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<Item> DoubleEndedIterator for Cells<'_, Item> {
+    fn next_back(&mut self) -> Option<Item> {
+        let item = self.cells.next_back()?;
+        self.remaining -= 1;
+        Some(item)
+    }
+}
+
+impl<Item> ExactSizeIterator for Cells<'_, Item> {}
+
+impl<Item> FusedIterator for Cells<'_, Item> {}
+
+impl<T> Grid<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
+        let rows = ascending(self.negative.iter(), self.positive.iter())
+            .filter_map(|(x, row)| Some((x, row.as_ref()?)));
+        let cells = rows.flat_map(|(x, row)| {
+            ascending(row.negative.iter(), row.positive.iter())
+                .filter_map(move |(y, cell)| Some(((x, y), cell.as_ref()?)))
+        });
+
+        Cells {
+            cells: Box::new(cells),
+            remaining: self.len,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let rows = ascending(self.negative.iter_mut(), self.positive.iter_mut())
+            .filter_map(|(x, row)| Some((x, row.as_mut()?)));
+        let cells = rows.flat_map(|(x, row)| {
+            ascending(row.negative.iter_mut(), row.positive.iter_mut())
+                .filter_map(move |(y, cell)| Some(((x, y), cell.as_mut()?)))
+        });
+
+        Cells {
+            cells: Box::new(cells),
+            remaining: self.len,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    // The positions inside the bounds holding no value, in row-major order.
+    // A grid with a default value has none.
+    pub fn iter_empty(&self) -> impl DoubleEndedIterator<Item = (isize, isize)> + '_ {
+        let bounds = self.bounds();
+        (bounds.min_x..=bounds.max_x)
+            .flat_map(move |x| (bounds.min_y..=bounds.max_y).map(move |y| (x, y)))
+            .filter(|&(x, y)| self.get(x, y).is_none())
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.iter_empty().next().is_none()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Grid<T> {
+    type Item = ((isize, isize), &'a T);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Grid<T> {
+    type Item = ((isize, isize), &'a mut T);
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> IntoIterator for Grid<T> {
+    type Item = ((isize, isize), T);
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        let mut cells = Vec::with_capacity(self.len);
+        let rows = ascending(self.negative.into_iter(), self.positive.into_iter())
+            .filter_map(|(x, row)| Some((x, row?)));
+        for (x, row) in rows {
+            let NegativeIndexVec { negative, positive } = row;
+            let row = ascending(negative.into_iter(), positive.into_iter());
+            cells.extend(row.filter_map(|(y, cell)| Some(((x, y), cell?))));
+        }
+        cells.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+
+    #[test]
+    fn finds_empty_cells() {
+        let mut grid = Grid::new();
+        grid.set(-1, 0, 'a');
+        grid.set(0, 1, 'b');
+
+        let empty: Vec<_> = grid.iter_empty().collect();
+        assert_eq!(empty, [(-1, 1), (0, 0)]);
+        assert_eq!(grid.iter_empty().next_back(), Some((0, 0)));
+        assert!(!grid.is_full());
+
+        grid.set(-1, 1, 'c');
+        grid.set(0, 0, 'd');
+        assert!(grid.is_full());
+        assert!(Grid::new_with_default('.').is_full());
+    }
+
+    #[test]
+    fn iterates_in_row_major_order() {
+        let mut grid = Grid::new();
+        for (x, y) in [(2, -1), (-3, 4), (0, 0), (-3, -2), (2, -5), (0, 7)] {
+            grid.set(x, y, x * 10 + y);
+        }
+        grid.set(0, 0, 1);
+        grid.remove(0, 7);
+
+        let order = [(-3, -2), (-3, 4), (0, 0), (2, -5), (2, -1)];
+        let positions: Vec<_> = grid.iter().map(|(position, _)| position).collect();
+        assert_eq!(positions, order);
+        assert_eq!(grid.iter().len(), 5);
+
+        {
+            let mut iter = grid.iter();
+            assert_eq!(iter.next_back(), Some(((2, -1), &19)));
+            assert_eq!(iter.next(), Some(((-3, -2), &-32)));
+            assert_eq!(iter.len(), 3);
+        }
+
+        for (_, cell) in &mut grid {
+            *cell += 1;
+        }
+        let owned: Vec<_> = grid.into_iter().collect();
+        assert_eq!(owned[2], ((0, 0), 2));
+        assert_eq!(
+            owned
+                .iter()
+                .map(|&(position, _)| position)
+                .collect::<Vec<_>>(),
+            order
+        );
+    }
+}
@@ -1,3 +1,97 @@
+mod adapter;
+mod anneal;
+mod arithmetic;
+mod assemble;
+mod automaton;
+mod autotile;
+mod builder;
+mod classify;
+mod components;
+mod contour;
+mod convolve;
+mod cursor;
+mod debug;
+mod delta;
+mod diffuse;
+mod draw;
+mod edit;
+mod flood;
+mod flow;
+mod gradient;
+mod hull;
+mod influence;
+mod iter;
+mod noise;
+mod outline;
+mod push;
+mod random;
+mod rewrite;
+mod sample;
+mod scale;
+mod sdf;
+mod sequence;
+mod symmetry;
+mod turtle;
+mod validate;
+mod view;
+mod visibility;
+mod voronoi;
+mod watershed;
+mod window;
+
+pub use adapter::{Clamped, Mirrored, Wrapping};
+pub use anneal::{Annealing, Mutation};
+pub use automaton::{count_neighbors, step};
+pub use builder::GridBuilder;
+pub use components::{Axis, RegionStats};
+pub use convolve::convolve;
+pub use cursor::Cursor;
+pub use delta::Delta;
+pub use diffuse::{Boundary, Relaxation};
+pub use edit::Edit;
+pub use flow::MinCut;
+pub use iter::{Cells, IntoIter, Iter, IterMut};
+pub use noise::NoiseParams;
+pub use push::Pushability;
+pub use rewrite::{Pattern, Replacement, RewriteStrategy};
+pub use sample::Edge;
+pub use symmetry::Symmetry;
+pub use turtle::{InstructionError, Turtle};
+pub use validate::Violation;
+pub use view::{GridRead, GridView, GridWrite};
+pub use visibility::VisibilityCache;
+pub use window::Window;
+
+use crate::error::GridError;
+use crate::rect::Rect;
+use std::collections::TryReserveError;
+
+// Storage for rows and for the cells of a row. With the smallvec feature the
+// first eight slots on either side of the origin are stored inline, so grids
+// up to 8x8 around the origin never touch the heap.
+#[cfg(not(feature = "smallvec"))]
+type Slots<U> = Vec<Option<U>>;
+#[cfg(feature = "smallvec")]
+type Slots<U> = smallvec::SmallVec<[Option<U>; 8]>;
+
+// Storage could not grow, either because the allocator refused or the size
+// overflowed.
+#[derive(Debug)]
+struct ReserveError;
+
+impl From<TryReserveError> for ReserveError {
+    fn from(_: TryReserveError) -> Self {
+        ReserveError
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl From<smallvec::CollectionAllocErr> for ReserveError {
+    fn from(_: smallvec::CollectionAllocErr) -> Self {
+        ReserveError
+    }
+}
+
 enum Existence {
     Positive,
     Negative,
@@ -8,36 +102,60 @@ trait NegativeIndexed<U: Default> {
     fn existence(&self, index: isize) -> Existence {
         if index >= 0 && (index as usize) < Self::positive_len(self) {
             Existence::Positive
-        } else if index < 0 && (index.abs() as usize) <= Self::negative_len(self) {
+        } else if index < 0 && index.unsigned_abs() <= Self::negative_len(self) {
             Existence::Negative
         } else {
             Existence::Nonexistent
         }
     }
 
-    fn assert_size(&mut self, size: isize) {
-        if size >= 0 {
-            for _ in Self::positive_len(self)..=size as usize {
+    // The number of slots `try_grow` adds to make room for `index`.
+    fn growth(&self, index: isize) -> usize {
+        if index >= 0 {
+            (index as usize + 1).saturating_sub(Self::positive_len(self))
+        } else {
+            index
+                .unsigned_abs()
+                .saturating_sub(Self::negative_len(self))
+        }
+    }
+
+    // Makes room for `index`, reserving the memory up front so a failed
+    // allocation is reported instead of aborting.
+    fn try_grow(&mut self, index: isize) -> Result<(), ReserveError> {
+        let additional = Self::growth(self, index);
+
+        if index >= 0 {
+            let len = index as usize + 1;
+            Self::reserve_positive(self, additional)?;
+            for _ in Self::positive_len(self)..len {
                 Self::push_positive(self, U::default())
             }
         } else {
-            for _ in Self::negative_len(self)..size.abs() as usize {
+            let len = index.unsigned_abs();
+            Self::reserve_negative(self, additional)?;
+            for _ in Self::negative_len(self)..len {
                 Self::push_negative(self, U::default())
             }
         }
+
+        Ok(())
     }
 
     fn positive_len(&self) -> usize;
     fn negative_len(&self) -> usize;
 
+    fn reserve_positive(&mut self, additional: usize) -> Result<(), ReserveError>;
+    fn reserve_negative(&mut self, additional: usize) -> Result<(), ReserveError>;
+
     fn push_positive(&mut self, item: U);
     fn push_negative(&mut self, item: U);
 }
 
 #[derive(Clone)]
 struct NegativeIndexVec<T> {
-    positive: Vec<Option<T>>,
-    negative: Vec<Option<T>>,
+    positive: Slots<T>,
+    negative: Slots<T>,
 }
 
 impl<T> NegativeIndexed<Option<T>> for NegativeIndexVec<T> {
@@ -49,6 +167,14 @@ impl<T> NegativeIndexed<Option<T>> for NegativeIndexVec<T> {
         self.negative.len()
     }
 
+    fn reserve_positive(&mut self, additional: usize) -> Result<(), ReserveError> {
+        Ok(self.positive.try_reserve(additional)?)
+    }
+
+    fn reserve_negative(&mut self, additional: usize) -> Result<(), ReserveError> {
+        Ok(self.negative.try_reserve(additional)?)
+    }
+
     fn push_positive(&mut self, item: Option<T>) {
         self.positive.push(item);
     }
@@ -61,25 +187,33 @@ impl<T> NegativeIndexed<Option<T>> for NegativeIndexVec<T> {
 impl<T> NegativeIndexVec<T> {
     pub fn new() -> Self {
         Self {
-            positive: vec![],
-            negative: vec![],
+            positive: Slots::new(),
+            negative: Slots::new(),
         }
     }
 
+    #[cfg(test)]
     pub fn set(&mut self, index: isize, item: T) {
-        self.assert_size(index);
+        self.try_set(index, item)
+            .expect("failed to grow negative index vec");
+    }
 
-        if index >= 0 {
-            self.positive[index as usize] = Some(item);
+    // Returns the value that was replaced.
+    pub fn try_set(&mut self, index: isize, item: T) -> Result<Option<T>, ReserveError> {
+        self.try_grow(index)?;
+
+        let slot = if index >= 0 {
+            &mut self.positive[index as usize]
         } else {
-            self.negative[index.abs() as usize - 1] = Some(item);
-        }
+            &mut self.negative[index.unsigned_abs() - 1]
+        };
+        Ok(slot.replace(item))
     }
 
     pub fn get(&self, index: isize) -> Option<&T> {
         match self.existence(index) {
             Existence::Positive => self.positive[index as usize].as_ref(),
-            Existence::Negative => self.negative[(index.abs() as usize) - 1].as_ref(),
+            Existence::Negative => self.negative[index.unsigned_abs() - 1].as_ref(),
             Existence::Nonexistent => None,
         }
     }
@@ -87,20 +221,40 @@ impl<T> NegativeIndexVec<T> {
     pub fn get_mut(&mut self, index: isize) -> Option<&mut T> {
         match self.existence(index) {
             Existence::Positive => self.positive[index as usize].as_mut(),
-            Existence::Negative => self.negative[index.abs() as usize - 1].as_mut(),
+            Existence::Negative => self.negative[index.unsigned_abs() - 1].as_mut(),
+            Existence::Nonexistent => None,
+        }
+    }
+
+    pub fn take(&mut self, index: isize) -> Option<T> {
+        match self.existence(index) {
+            Existence::Positive => self.positive[index as usize].take(),
+            Existence::Negative => self.negative[index.unsigned_abs() - 1].take(),
             Existence::Nonexistent => None,
         }
     }
 }
 
 #[derive(Clone)]
+#[cfg_attr(
+    feature = "bevy",
+    derive(bevy_ecs::resource::Resource, bevy_reflect::Reflect),
+    reflect(opaque, where T: Clone)
+)]
 pub struct Grid<T> {
-    positive: Vec<Option<NegativeIndexVec<T>>>,
-    negative: Vec<Option<NegativeIndexVec<T>>>,
+    positive: Slots<NegativeIndexVec<T>>,
+    negative: Slots<NegativeIndexVec<T>>,
     min_x: isize,
     max_x: isize,
     min_y: isize,
     max_y: isize,
+    max_bounds: Option<Rect>,
+    wrapping: bool,
+    memory_budget: Option<usize>,
+    memory: usize,
+    len: usize,
+    default: Option<T>,
+    is_default: fn(&T, &T) -> bool,
 }
 
 impl<T> NegativeIndexed<Option<NegativeIndexVec<T>>> for Grid<T> {
@@ -112,6 +266,14 @@ impl<T> NegativeIndexed<Option<NegativeIndexVec<T>>> for Grid<T> {
         self.negative.len()
     }
 
+    fn reserve_positive(&mut self, additional: usize) -> Result<(), ReserveError> {
+        Ok(self.positive.try_reserve(additional)?)
+    }
+
+    fn reserve_negative(&mut self, additional: usize) -> Result<(), ReserveError> {
+        Ok(self.negative.try_reserve(additional)?)
+    }
+
     fn push_positive(&mut self, item: Option<NegativeIndexVec<T>>) {
         self.positive.push(item);
     }
@@ -124,15 +286,98 @@ impl<T> NegativeIndexed<Option<NegativeIndexVec<T>>> for Grid<T> {
 impl<T> Grid<T> {
     pub fn new() -> Self {
         Self {
-            positive: vec![],
-            negative: vec![],
+            positive: Slots::new(),
+            negative: Slots::new(),
             min_x: 0,
             max_x: 0,
             min_y: 0,
             max_y: 0,
+            max_bounds: None,
+            wrapping: false,
+            memory_budget: None,
+            memory: 0,
+            len: 0,
+            default: None,
+            is_default: |_, _| false,
         }
     }
 
+    // A grid refusing cells outside `bounds`.
+    pub fn with_max_bounds(bounds: Rect) -> Self {
+        Self {
+            max_bounds: Some(bounds),
+            ..Self::new()
+        }
+    }
+
+    // A grid refusing cells whose storage would take it beyond `bytes`. Rows
+    // and columns are stored from the origin outwards, so a far away cell
+    // costs memory for everything between it and the origin.
+    pub fn with_memory_budget(bytes: usize) -> Self {
+        Self {
+            memory_budget: Some(bytes),
+            ..Self::new()
+        }
+    }
+
+    pub fn default_value(&self) -> Option<&T> {
+        self.default.as_ref()
+    }
+
+    pub fn max_bounds(&self) -> Option<Rect> {
+        self.max_bounds
+    }
+
+    // Whether coordinates wrap around the maximum bounds, making the grid a
+    // torus.
+    pub fn is_wrapping(&self) -> bool {
+        self.wrapping
+    }
+
+    pub fn memory_budget(&self) -> Option<usize> {
+        self.memory_budget
+    }
+
+    // Bytes of cell storage allocated so far.
+    pub fn memory_usage(&self) -> usize {
+        self.memory
+    }
+
+    pub(crate) fn with_boundaries_of<U>(other: &Grid<U>) -> Self {
+        Self::with_bounds(other.bounds())
+    }
+
+    pub(crate) fn with_bounds(bounds: Rect) -> Self {
+        Self {
+            min_x: bounds.min_x,
+            max_x: bounds.max_x,
+            min_y: bounds.min_y,
+            max_y: bounds.max_y,
+            ..Self::new()
+        }
+    }
+
+    // An empty grid over `bounds` with this grid's default, for results made
+    // of its cells.
+    pub(crate) fn derived(&self, bounds: Rect) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            default: self.default.clone(),
+            is_default: self.is_default,
+            ..Self::with_bounds(bounds)
+        }
+    }
+
+    // Like `with_bounds` for bounds read from untrusted input, failing
+    // unless every cell inside them can be set without overflowing.
+    pub(crate) fn try_with_bounds(bounds: Rect) -> Result<Self, GridError> {
+        let grid = Self::with_bounds(bounds);
+        grid.check_overflow(bounds.max_x, bounds.max_y)?;
+        Ok(grid)
+    }
+
     pub fn min_x(&self) -> isize {
         self.min_x
     }
@@ -149,11 +394,25 @@ impl<T> Grid<T> {
         self.max_y
     }
 
+    pub fn bounds(&self) -> Rect {
+        Rect::new(self.min_x, self.min_y, self.max_x, self.max_y)
+    }
+
+    // The number of stored cells; a grid with a default value does not store
+    // cells holding it.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
     fn assert_existence(&mut self, x: isize) {
         if x >= 0 && self.positive[x as usize].is_none() {
             self.positive[x as usize] = Some(NegativeIndexVec::new());
-        } else if x < 0 && self.negative[x.abs() as usize - 1].is_none() {
-            self.negative[x.abs() as usize - 1] = Some(NegativeIndexVec::new());
+        } else if x < 0 && self.negative[x.unsigned_abs() - 1].is_none() {
+            self.negative[x.unsigned_abs() - 1] = Some(NegativeIndexVec::new());
         }
     }
 
@@ -171,40 +430,193 @@ impl<T> Grid<T> {
         }
     }
 
-    pub fn set(&mut self, x: isize, y: isize, item: T) {
-        self.update_boundaries(x, y);
-        self.assert_size(x);
-        self.assert_existence(x);
+    pub(crate) fn wrap(&self, x: isize, y: isize) -> (isize, isize) {
+        let Some(bounds) = self.max_bounds.filter(|_| self.wrapping) else {
+            return (x, y);
+        };
+
+        let wrap = |value: isize, min: isize, size: usize| {
+            ((value as i128 - min as i128).rem_euclid(size as i128) + min as i128) as isize
+        };
+        (
+            wrap(x, bounds.min_x, bounds.height()),
+            wrap(y, bounds.min_y, bounds.width()),
+        )
+    }
+
+    fn check_bounds(&self, x: isize, y: isize) -> Result<(), GridError> {
+        let (x, y) = self.wrap(x, y);
+        match self.max_bounds {
+            Some(bounds) if !bounds.contains(x, y) => Err(GridError::OutOfBounds { x, y }),
+            _ => Ok(()),
+        }
+    }
 
-        if x >= 0 {
-            self.positive[x as usize].as_mut().unwrap().set(y, item);
+    fn row(&self, x: isize) -> Option<&NegativeIndexVec<T>> {
+        match self.existence(x) {
+            Existence::Positive => self.positive[x as usize].as_ref(),
+            Existence::Negative => self.negative[x.unsigned_abs() - 1].as_ref(),
+            Existence::Nonexistent => None,
+        }
+    }
+
+    // Keeps the bounds spanning at most isize::MAX cells per axis once (x, y)
+    // is included.
+    fn check_overflow(&self, x: isize, y: isize) -> Result<(), GridError> {
+        let span = |min: isize, max: isize, value: isize| {
+            max.max(value).checked_sub(min.min(value))?.checked_add(1)
+        };
+        if span(self.min_x, self.max_x, x).is_none() || span(self.min_y, self.max_y, y).is_none() {
+            return Err(GridError::CoordinateOverflow { x, y });
+        }
+        Ok(())
+    }
+
+    // The row at `x`, created if needed; its storage must already exist.
+    fn row_mut(&mut self, x: isize) -> &mut NegativeIndexVec<T> {
+        self.assert_existence(x);
+        let row = if x >= 0 {
+            self.positive[x as usize].as_mut()
         } else {
-            self.negative[x.abs() as usize - 1]
-                .as_mut()
-                .unwrap()
-                .set(y, item);
+            self.negative[x.unsigned_abs() - 1].as_mut()
+        };
+        row.unwrap()
+    }
+
+    // Bytes the storage grows by when (x, y) is set.
+    fn growth_bytes(&self, x: isize, y: isize) -> usize {
+        let rows = self.growth(x);
+        let cells = match self.row(x) {
+            Some(row) => row.growth(y),
+            None => NegativeIndexVec::<T>::new().growth(y),
+        };
+
+        rows.saturating_mul(size_of::<Option<NegativeIndexVec<T>>>())
+            .saturating_add(cells.saturating_mul(size_of::<Option<T>>()))
+    }
+
+    pub fn set(&mut self, x: isize, y: isize, item: T) {
+        if let Err(error) = self.try_set(x, y, item) {
+            panic!("cannot set ({x}, {y}): {error}");
+        }
+    }
+
+    // Like `set`, but reports errors instead of panicking or aborting. The
+    // grid's bounds only change once the cell has been stored, and never span
+    // more than isize::MAX cells along an axis, so `max - min + 1` cannot
+    // overflow; cells that would break this are a coordinate overflow.
+    pub fn try_set(&mut self, x: isize, y: isize, item: T) -> Result<(), GridError> {
+        let (x, y) = self.wrap(x, y);
+        if let Some(default) = &self.default {
+            if (self.is_default)(&item, default) {
+                self.check_bounds(x, y)?;
+                self.remove(x, y);
+                return Ok(());
+            }
         }
+
+        self.check_overflow(x, y)?;
+        self.check_bounds(x, y)?;
+
+        let growth = self.growth_bytes(x, y);
+        let memory = self.memory.saturating_add(growth);
+        if self.memory_budget.is_some_and(|budget| memory > budget) {
+            return Err(GridError::CapacityExceeded);
+        }
+
+        let capacity = |_| GridError::CapacityExceeded;
+        self.try_grow(x).map_err(capacity)?;
+        if self
+            .row_mut(x)
+            .try_set(y, item)
+            .map_err(capacity)?
+            .is_none()
+        {
+            self.len += 1;
+        }
+
+        self.memory = memory;
+        self.update_boundaries(x, y);
+        Ok(())
     }
 
     pub fn get(&self, x: isize, y: isize) -> Option<&T> {
+        self.get_stored(x, y).or(self.default_value())
+    }
+
+    fn get_stored(&self, x: isize, y: isize) -> Option<&T> {
+        let (x, y) = self.wrap(x, y);
         match self.existence(x) {
             Existence::Positive => self.positive.get(x as usize)?.as_ref()?.get(y),
-            Existence::Negative => self.negative.get(x.abs() as usize - 1)?.as_ref()?.get(y),
+            Existence::Negative => self.negative.get(x.unsigned_abs() - 1)?.as_ref()?.get(y),
             Existence::Nonexistent => None,
         }
     }
 
     pub fn get_mut(&mut self, x: isize, y: isize) -> Option<&mut T> {
+        let (x, y) = self.wrap(x, y);
         match self.existence(x) {
             Existence::Positive => self.positive.get_mut(x as usize)?.as_mut()?.get_mut(y),
             Existence::Negative => self
                 .negative
-                .get_mut(x.abs() as usize - 1)?
+                .get_mut(x.unsigned_abs() - 1)?
                 .as_mut()?
                 .get_mut(y),
             Existence::Nonexistent => None,
         }
     }
+
+    pub fn remove(&mut self, x: isize, y: isize) -> Option<T> {
+        let (x, y) = self.wrap(x, y);
+        let removed = match self.existence(x) {
+            Existence::Positive => self.positive.get_mut(x as usize)?.as_mut()?.take(y),
+            Existence::Negative => self
+                .negative
+                .get_mut(x.unsigned_abs() - 1)?
+                .as_mut()?
+                .take(y),
+            Existence::Nonexistent => None,
+        };
+
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+}
+
+// The checked counterparts of `get`, `get_mut` and `remove`, which fail for
+// coordinates outside the grid's maximum bounds.
+impl<T> Grid<T> {
+    pub fn try_get(&self, x: isize, y: isize) -> Result<Option<&T>, GridError> {
+        self.check_bounds(x, y)?;
+        Ok(self.get(x, y))
+    }
+
+    pub fn try_get_mut(&mut self, x: isize, y: isize) -> Result<Option<&mut T>, GridError> {
+        self.check_bounds(x, y)?;
+        Ok(self.get_mut(x, y))
+    }
+
+    pub fn try_remove(&mut self, x: isize, y: isize) -> Result<Option<T>, GridError> {
+        self.check_bounds(x, y)?;
+        Ok(self.remove(x, y))
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    // A grid that is `default` everywhere. Only other values are stored:
+    // setting a cell to the default clears it, `get_mut` and `remove` only see
+    // stored cells and the bounds only cover those. Searches that follow
+    // neighbors stay within the bounds, seeing the default in their empty
+    // cells.
+    pub fn new_with_default(default: T) -> Self {
+        Self {
+            default: Some(default),
+            is_default: T::eq,
+            ..Self::new()
+        }
+    }
 }
 
 impl<T> Default for Grid<T> {
@@ -215,7 +627,7 @@ impl<T> Default for Grid<T> {
 
 #[cfg(test)]
 mod tests {
-    use super::{Grid, NegativeIndexVec};
+    use super::{Grid, GridError, NegativeIndexVec, Rect};
 
     #[test]
     fn negative_vec() {
@@ -230,6 +642,105 @@ mod tests {
         }
     }
 
+    #[test]
+    fn failed_growth_keeps_bounds() {
+        let mut grid = Grid::new();
+        grid.set(1, 1, 1u8);
+
+        assert_eq!(
+            grid.try_set(isize::MAX / 2, 0, 2),
+            Err(GridError::CapacityExceeded)
+        );
+        assert_eq!(
+            grid.try_set(0, isize::MIN / 2, 2),
+            Err(GridError::CapacityExceeded)
+        );
+        assert_eq!((grid.min_x(), grid.max_x()), (0, 1));
+        assert_eq!((grid.min_y(), grid.max_y()), (0, 1));
+
+        assert_eq!(grid.try_set(-1, -1, 3), Ok(()));
+        assert_eq!(grid.try_get(-1, -1), Ok(Some(&3)));
+        assert_eq!(grid.try_remove(1, 1), Ok(Some(1)));
+    }
+
+    #[test]
+    fn extreme_coordinates() {
+        let mut grid = Grid::new();
+        let overflow = |x, y| Err(GridError::CoordinateOverflow { x, y });
+
+        assert_eq!(grid.try_set(isize::MIN, 0, 'a'), overflow(isize::MIN, 0));
+        assert_eq!(grid.try_set(0, isize::MAX, 'a'), overflow(0, isize::MAX));
+        assert_eq!(grid.get(isize::MIN, isize::MAX), None);
+        assert_eq!(grid.remove(isize::MAX, isize::MIN), None);
+
+        grid.set(-3, 0, 'b');
+        assert_eq!(
+            grid.try_set(isize::MAX - 3, 0, 'c'),
+            overflow(isize::MAX - 3, 0)
+        );
+        assert_eq!(grid.bounds().height(), 4);
+        assert_eq!(
+            grid.count_neighbors(isize::MAX, isize::MIN, |cell| cell.is_none()),
+            8
+        );
+    }
+
+    #[test]
+    fn limits() {
+        let mut bounded = Grid::with_max_bounds(Rect::new(-5, -5, 5, 5));
+        bounded.set(5, -5, 1);
+
+        let outside = Err(GridError::OutOfBounds { x: 6, y: 0 });
+        assert_eq!(bounded.try_set(6, 0, 2), outside);
+        assert_eq!(bounded.try_get(6, 0), outside.map(|_| None));
+        assert!(matches!(
+            bounded.try_set(isize::MAX / 2, 0, 2),
+            Err(GridError::OutOfBounds { .. })
+        ));
+        assert_eq!(bounded.bounds(), Rect::new(0, -5, 5, 0));
+
+        let mut budgeted = Grid::with_memory_budget(1024);
+        budgeted.set(3, 3, 1u8);
+        assert!(budgeted.memory_usage() <= 1024);
+        assert_eq!(
+            budgeted.try_set(0, 2000, 2),
+            Err(GridError::CapacityExceeded)
+        );
+        assert_eq!(
+            budgeted.try_set(-2000, 0, 2),
+            Err(GridError::CapacityExceeded)
+        );
+        assert_eq!(budgeted.get(3, 3), Some(&1));
+    }
+
+    #[test]
+    fn default_cells() {
+        let mut grid = Grid::new_with_default('.');
+        assert_eq!(grid.get(-1000, 1000), Some(&'.'));
+
+        grid.set(2, 2, '#');
+        let memory = grid.memory_usage();
+        grid.set(500, -500, '.');
+        assert_eq!(grid.memory_usage(), memory);
+
+        assert_eq!(grid.get_mut(0, 1), None);
+        grid.set(2, 2, '.');
+        assert_eq!(grid.remove(2, 2), None);
+        assert_eq!(grid.get(2, 2), Some(&'.'));
+    }
+
+    #[test]
+    fn remove_cells() {
+        let mut grid = Grid::new();
+        grid.set(-2, 3, 'a');
+        grid.set(2, -3, 'b');
+
+        assert_eq!(grid.remove(-2, 3), Some('a'));
+        assert_eq!(grid.remove(-2, 3), None);
+        assert_eq!(grid.remove(7, 7), None);
+        assert_eq!(grid.get(2, -3), Some(&'b'));
+    }
+
     #[test]
     fn negative_grid() {
         let mut grid = Grid::new();
@@ -246,4 +757,21 @@ mod tests {
             }
         }
     }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn small_grids_stay_inline() {
+        let mut grid = Grid::new();
+        for (x, y) in Rect::new(-4, -4, 3, 3).iter() {
+            grid.set(x, y, x + y);
+        }
+
+        let mut rows = grid.positive.iter().chain(&grid.negative).flatten();
+        assert!(!grid.positive.spilled() && !grid.negative.spilled());
+        assert_eq!(rows.clone().count(), 8);
+        assert!(rows.all(|row| !row.positive.spilled() && !row.negative.spilled()));
+
+        grid.set(0, 8, 0);
+        assert!(grid.positive[0].as_ref().unwrap().positive.spilled());
+    }
 }
@@ -0,0 +1,153 @@
+use super::Grid;
+use crate::rect::Rect;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoiseParams {
+    pub seed: u64,
+    pub frequency: f64,
+    pub octaves: usize,
+    pub persistence: f64,
+    pub lacunarity: f64,
+}
+
+impl Default for NoiseParams {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            frequency: 0.1,
+            octaves: 4,
+            persistence: 0.5,
+            lacunarity: 2.0,
+        }
+    }
+}
+
+struct Perlin {
+    permutation: Vec<usize>,
+}
+
+impl Perlin {
+    fn new(seed: u64) -> Self {
+        let mut permutation: Vec<usize> = (0..256).collect();
+        permutation.shuffle(&mut StdRng::seed_from_u64(seed));
+        permutation.extend_from_within(..);
+
+        Self { permutation }
+    }
+
+    fn gradient(&self, x: isize, y: isize, dx: f64, dy: f64) -> f64 {
+        let hash = self.permutation[self.permutation[(x & 255) as usize] + (y & 255) as usize];
+
+        match hash & 7 {
+            0 => dx + dy,
+            1 => dx - dy,
+            2 => -dx + dy,
+            3 => -dx - dy,
+            4 => dx,
+            5 => -dx,
+            6 => dy,
+            _ => -dy,
+        }
+    }
+
+    fn sample(&self, x: f64, y: f64) -> f64 {
+        let fade = |t: f64| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+        let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+
+        let (x0, y0) = (x.floor(), y.floor());
+        let (dx, dy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as isize, y0 as isize);
+        let (u, v) = (fade(dx), fade(dy));
+
+        let top = lerp(
+            self.gradient(x0, y0, dx, dy),
+            self.gradient(x0 + 1, y0, dx - 1.0, dy),
+            u,
+        );
+        let bottom = lerp(
+            self.gradient(x0, y0 + 1, dx, dy - 1.0),
+            self.gradient(x0 + 1, y0 + 1, dx - 1.0, dy - 1.0),
+            u,
+        );
+
+        lerp(top, bottom, v)
+    }
+
+    // Octaves are summed and normalized, keeping the result within -1..=1.
+    fn fractal(&self, x: f64, y: f64, params: &NoiseParams) -> f64 {
+        let (mut value, mut total) = (0.0, 0.0);
+        let (mut frequency, mut amplitude) = (params.frequency, 1.0);
+
+        for _ in 0..params.octaves.max(1) {
+            value += self.sample(x * frequency, y * frequency) * amplitude;
+            total += amplitude;
+            frequency *= params.lacunarity;
+            amplitude *= params.persistence;
+        }
+
+        (value / total).clamp(-1.0, 1.0)
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn fill_noise<F>(&mut self, rect: Rect, params: &NoiseParams, mut f: F)
+    where
+        F: FnMut(f64) -> T,
+    {
+        let perlin = Perlin::new(params.seed);
+
+        for (x, y) in rect.iter() {
+            self.set(x, y, f(perlin.fractal(x as f64, y as f64, params)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Grid, NoiseParams};
+    use crate::rect::Rect;
+
+    fn heightmap(seed: u64) -> Grid<f64> {
+        let params = NoiseParams {
+            seed,
+            ..NoiseParams::default()
+        };
+
+        let mut grid = Grid::new();
+        grid.fill_noise(Rect::new(-20, -20, 20, 20), &params, |value| value);
+        grid
+    }
+
+    #[test]
+    fn noise_is_coherent_and_bounded() {
+        let grid = heightmap(1);
+
+        for (x, y) in grid.bounds().iter() {
+            let value = *grid.get(x, y).unwrap();
+            assert!((-1.0..=1.0).contains(&value));
+
+            if let Some(next) = grid.get(x, y + 1) {
+                assert!((value - next).abs() < 0.5);
+            }
+        }
+
+        let values: Vec<f64> = grid
+            .bounds()
+            .iter()
+            .map(|(x, y)| *grid.get(x, y).unwrap())
+            .collect();
+        assert!(values.iter().any(|value| *value > 0.1));
+        assert!(values.iter().any(|value| *value < -0.1));
+    }
+
+    #[test]
+    fn noise_depends_on_seed() {
+        let (a, b, c) = (heightmap(1), heightmap(1), heightmap(2));
+
+        assert!(a.bounds().iter().all(|(x, y)| a.get(x, y) == b.get(x, y)));
+        assert!(a.bounds().iter().any(|(x, y)| a.get(x, y) != c.get(x, y)));
+    }
+}
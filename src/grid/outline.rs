@@ -0,0 +1,110 @@
+use super::Grid;
+use crate::direction::Direction;
+
+impl<T> Grid<T> {
+    // Moore neighbor tracing of the 8-connected region containing `start`. The
+    // boundary is returned clockwise; cells on one cell wide parts of the region
+    // appear once for every time the trace passes them.
+    pub fn trace_outline<F>(&self, predicate: F, start: (isize, isize)) -> Vec<(isize, isize)>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let inside = |(x, y): (isize, isize)| self.get(x, y).is_some_and(&predicate);
+
+        if !inside(start) {
+            return vec![];
+        }
+
+        // Walking north until the region ends yields a boundary cell whose
+        // northern neighbor is known to be outside.
+        let mut first = start;
+        while inside((first.0 - 1, first.1)) {
+            first.0 -= 1;
+        }
+
+        let initial = (first, Direction::North);
+        let (mut current, mut backtrack) = initial;
+        let mut outline = vec![current];
+
+        loop {
+            let mut direction = backtrack;
+            let mut next = None;
+
+            for _ in 0..8 {
+                let previous = direction;
+                direction = direction.clockwise();
+                let (dx, dy) = direction.offset();
+                let candidate = (current.0 + dx, current.1 + dy);
+
+                if inside(candidate) {
+                    let (bx, by) = previous.offset();
+                    let outside = (current.0 + bx, current.1 + by);
+                    next = Some((candidate, outside));
+                    break;
+                }
+            }
+
+            let Some((candidate, outside)) = next else {
+                return outline;
+            };
+
+            current = candidate;
+            backtrack = Direction::from_offset(outside.0 - current.0, outside.1 - current.1)
+                .expect("consecutive Moore neighbors are orthogonally adjacent");
+
+            if (current, backtrack) == initial {
+                return outline;
+            }
+
+            outline.push(current);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+
+    #[test]
+    fn square_outline() {
+        let mut grid = Grid::new();
+        for x in 0..3 {
+            for y in 0..3 {
+                grid.set(x, y, true);
+            }
+        }
+        grid.set(5, 5, false);
+
+        let outline = grid.trace_outline(|cell| *cell, (1, 1));
+        assert_eq!(
+            outline,
+            vec![
+                (0, 1),
+                (0, 2),
+                (1, 2),
+                (2, 2),
+                (2, 1),
+                (2, 0),
+                (1, 0),
+                (0, 0)
+            ]
+        );
+
+        assert!(grid.trace_outline(|cell| *cell, (5, 5)).is_empty());
+    }
+
+    #[test]
+    fn thin_and_single_regions() {
+        let mut grid = Grid::new();
+        for y in -1..=1 {
+            grid.set(0, y, ());
+        }
+        grid.set(4, 4, ());
+
+        assert_eq!(
+            grid.trace_outline(|_| true, (0, 0)),
+            vec![(0, 0), (0, 1), (0, 0), (0, -1)]
+        );
+        assert_eq!(grid.trace_outline(|_| true, (4, 4)), vec![(4, 4)]);
+    }
+}
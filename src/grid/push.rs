@@ -0,0 +1,156 @@
+use super::Grid;
+use crate::connectivity::step;
+use crate::direction::Direction;
+
+// How a stored cell reacts to being pushed; empty cells are open floor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Pushability {
+    // Moves along when pushed, such as a crate.
+    Pushable,
+    // Stops the push, such as a wall.
+    Blocked,
+}
+
+impl<T> Grid<T> {
+    // Moves the cell at `from` one step towards `direction`, pushing the
+    // chain of pushable cells in front of it into the first empty cell
+    // beyond. Returns false and changes nothing if `from` is empty or the
+    // chain runs into a blocked cell, the maximum bounds or, on wrapping
+    // grids, back into `from`; the mover itself is never classified. Cells
+    // that are not stored are open floor, also on grids with a default.
+    pub fn try_push<F>(&mut self, from: (isize, isize), direction: Direction, classify: F) -> bool
+    where
+        F: FnMut(&T) -> Pushability,
+    {
+        self.try_push_with(from, direction, usize::MAX, classify)
+    }
+
+    // Like `try_push`, failing when more than `max_pushed` cells would have
+    // to move along, e.g. 1 for Sokoban.
+    pub fn try_push_with<F>(
+        &mut self,
+        from: (isize, isize),
+        direction: Direction,
+        max_pushed: usize,
+        mut classify: F,
+    ) -> bool
+    where
+        F: FnMut(&T) -> Pushability,
+    {
+        let from = self.wrap(from.0, from.1);
+        if self.get_stored(from.0, from.1).is_none() {
+            return false;
+        }
+
+        // Find the whole chain and the empty cell it moves into before moving
+        // anything, so a failed push needs no rollback.
+        let offset = direction.offset();
+        let mut chain = vec![from];
+        let target = loop {
+            let Some(next) = step(chain[chain.len() - 1], offset) else {
+                return false;
+            };
+            let next = self.wrap(next.0, next.1);
+            if next == from
+                || self
+                    .max_bounds
+                    .is_some_and(|bounds| !bounds.contains(next.0, next.1))
+            {
+                return false;
+            }
+            match self.get_stored(next.0, next.1) {
+                None => break next,
+                Some(item) => {
+                    if classify(item) == Pushability::Blocked || chain.len() > max_pushed {
+                        return false;
+                    }
+                    chain.push(next);
+                }
+            }
+        };
+
+        let mut to = target;
+        for &cell in chain.iter().rev() {
+            self.move_cell(cell, to);
+            to = cell;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pushability;
+    use crate::direction::Direction;
+    use crate::grid::{Grid, GridBuilder};
+    use crate::rect::Rect;
+
+    fn row(cells: &str) -> Grid<char> {
+        let mut grid = Grid::new();
+        for (y, cell) in cells.chars().enumerate() {
+            if cell != '.' {
+                grid.set(0, y as isize, cell);
+            }
+        }
+        grid
+    }
+
+    fn text(grid: &Grid<char>) -> String {
+        (0..7).map(|y| *grid.get(0, y).unwrap_or(&'.')).collect()
+    }
+
+    fn classify(cell: &char) -> Pushability {
+        match cell {
+            '$' => Pushability::Pushable,
+            _ => Pushability::Blocked,
+        }
+    }
+
+    #[test]
+    fn pushes_chains_until_blocked() {
+        let mut grid = row("@$$..#.");
+        assert!(grid.try_push((0, 0), Direction::East, classify));
+        assert_eq!(text(&grid), ".@$$.#.");
+        assert!(!grid.try_push_with((0, 1), Direction::East, 1, classify));
+        assert!(grid.try_push((0, 1), Direction::East, classify));
+        assert_eq!(text(&grid), "..@$$#.");
+
+        // Blocked by the wall: nothing moves.
+        assert!(!grid.try_push((0, 2), Direction::East, classify));
+        assert_eq!(text(&grid), "..@$$#.");
+        assert!(!grid.try_push((0, 0), Direction::East, classify));
+        assert!(grid.try_push((0, 2), Direction::North, classify));
+        assert_eq!(grid.get(-1, 2), Some(&'@'));
+    }
+
+    #[test]
+    fn stops_at_the_limits() {
+        // Unstored cells are floor even though the default is pushable.
+        let mut floor = Grid::new_with_default('$');
+        floor.set(0, 0, '@');
+        assert!(floor.try_push((0, 0), Direction::East, classify));
+        assert_eq!(floor.len(), 1);
+        assert_eq!(floor.get(0, 1), Some(&'@'));
+
+        let mut bounded = Grid::with_max_bounds(Rect::new(0, 0, 0, 2));
+        bounded.set(0, 0, '@');
+        bounded.set(0, 1, '$');
+        assert!(bounded.try_push((0, 0), Direction::East, classify));
+        assert!(!bounded.try_push((0, 1), Direction::East, classify));
+        assert_eq!(bounded.get(0, 2), Some(&'$'));
+
+        // A full ring has no empty cell to move into.
+        let mut ring = GridBuilder::new()
+            .wrapping(Rect::new(0, 0, 0, 2))
+            .build()
+            .unwrap();
+        for y in 0..3 {
+            ring.set(0, y, if y == 0 { '@' } else { '$' });
+        }
+        assert!(!ring.try_push((0, 0), Direction::East, classify));
+        ring.remove(0, 2);
+        assert!(ring.try_push((0, 0), Direction::West, classify));
+        assert_eq!(ring.get(0, 2), Some(&'@'));
+        assert_eq!(ring.get(0, 0), None);
+    }
+}
@@ -0,0 +1,219 @@
+use super::Grid;
+use crate::rect::Rect;
+use rand::seq::SliceRandom;
+use rand::{Rng, RngExt};
+
+// Cells are visited in row-major order, so a seeded generator always
+// produces the same grid.
+impl<T> Grid<T> {
+    pub fn fill_random<R, F>(&mut self, rect: Rect, rng: &mut R, mut f: F)
+    where
+        R: Rng + ?Sized,
+        F: FnMut(&mut R) -> T,
+    {
+        for (x, y) in rect.iter() {
+            let item = f(rng);
+            self.set(x, y, item);
+        }
+    }
+
+    // Randomly permutes the values among the stored cells; cells holding the
+    // default stay as they are.
+    pub fn shuffle_cells<R>(&mut self, rng: &mut R)
+    where
+        R: Rng + ?Sized,
+    {
+        let positions: Vec<(isize, isize)> = self.iter().map(|(position, _)| position).collect();
+
+        let mut items: Vec<T> = positions
+            .iter()
+            .filter_map(|&(x, y)| self.remove(x, y))
+            .collect();
+        items.shuffle(rng);
+
+        for ((x, y), item) in positions.into_iter().zip(items) {
+            self.set(x, y, item);
+        }
+    }
+
+    // Replaces each stored cell, with the given probability between 0 and
+    // 1, by `mutate(cell, rng)`, and returns how many were replaced. Handy
+    // for noise in simulations and for randomized variants of a test map.
+    // Probabilities outside 0..=1 are clamped and NaN replaces nothing.
+    pub fn perturb<R, F>(&mut self, rng: &mut R, probability: f64, mut mutate: F) -> usize
+    where
+        R: Rng + ?Sized,
+        F: FnMut(&T, &mut R) -> T,
+    {
+        if probability.is_nan() {
+            return 0;
+        }
+        let probability = probability.clamp(0.0, 1.0);
+        let mut mutated = 0;
+        for (_, item) in self.iter_mut() {
+            if rng.random_bool(probability) {
+                *item = mutate(item, rng);
+                mutated += 1;
+            }
+        }
+        mutated
+    }
+}
+
+// Sampling picks among the stored cells in a single pass over them, without
+// collecting them first.
+impl<T> Grid<T> {
+    pub fn random_cell<R>(&self, rng: &mut R) -> Option<((isize, isize), &T)>
+    where
+        R: Rng + ?Sized,
+    {
+        if self.is_empty() {
+            return None;
+        }
+        self.iter().nth(rng.random_range(0..self.len()))
+    }
+
+    // Reservoir sampling: the n-th match replaces the pick with probability
+    // 1/n, which leaves every match equally likely.
+    pub fn random_matching<R, F>(
+        &self,
+        rng: &mut R,
+        mut predicate: F,
+    ) -> Option<((isize, isize), &T)>
+    where
+        R: Rng + ?Sized,
+        F: FnMut(&T) -> bool,
+    {
+        let matches = self.iter().filter(|(_, item)| predicate(item));
+        let mut pick = None;
+
+        for (seen, cell) in matches.enumerate() {
+            if rng.random_range(0..=seen) == 0 {
+                pick = Some(cell);
+            }
+        }
+
+        pick
+    }
+
+    // Picks a cell with probability proportional to its weight. Cells weighing
+    // zero, less or NaN are never picked.
+    pub fn random_weighted<R, F>(&self, rng: &mut R, mut weight: F) -> Option<((isize, isize), &T)>
+    where
+        R: Rng + ?Sized,
+        F: FnMut(&T) -> f64,
+    {
+        let mut pick = None;
+        let mut total = 0.0;
+
+        for (position, item) in self.iter() {
+            let weight = weight(item);
+            if weight > 0.0 {
+                total += weight;
+                if rng.random_bool((weight / total).min(1.0)) {
+                    pick = Some((position, item));
+                }
+            }
+        }
+
+        pick
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::rect::Rect;
+    use rand::rngs::StdRng;
+    use rand::{RngExt, SeedableRng};
+
+    fn random_grid(seed: u64) -> Grid<u8> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut grid = Grid::new();
+        grid.fill_random(Rect::new(-3, -3, 3, 3), &mut rng, |rng| {
+            rng.random_range(0..10)
+        });
+        grid
+    }
+
+    #[test]
+    fn seeded_fill_is_reproducible() {
+        let (a, b) = (random_grid(9), random_grid(9));
+
+        assert_eq!(a.bounds(), Rect::new(-3, -3, 3, 3));
+        assert!(a.bounds().iter().all(|(x, y)| a.get(x, y) == b.get(x, y)));
+        assert!(a.bounds().iter().all(|(x, y)| *a.get(x, y).unwrap() < 10));
+    }
+
+    #[test]
+    fn samples_cells() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut grid = Grid::new();
+        assert!(grid.random_cell(&mut rng).is_none());
+
+        for y in -5..5 {
+            grid.set(1, y, y);
+        }
+
+        let ((x, y), value) = grid.random_cell(&mut rng).unwrap();
+        assert_eq!((x, *value), (1, y));
+
+        let mut counts = [0; 10];
+        for _ in 0..2000 {
+            let (_, value) = grid.random_matching(&mut rng, |v| v % 2 == 0).unwrap();
+            counts[(value + 5) as usize] += 1;
+        }
+        assert!(counts.iter().skip(1).step_by(2).all(|&count| count > 300));
+        assert!(counts.iter().step_by(2).all(|&count| count == 0));
+
+        let mut heavy = 0;
+        for _ in 0..2000 {
+            let weighted = grid.random_weighted(&mut rng, |&v| if v == 4 { 9.0 } else { 0.1 });
+            if weighted.unwrap().1 == &4 {
+                heavy += 1;
+            }
+        }
+        assert!((1700..1950).contains(&heavy));
+        assert!(grid.random_weighted(&mut rng, |_| 0.0).is_none());
+    }
+
+    #[test]
+    fn shuffle_keeps_values_and_positions() {
+        let mut grid = Grid::new();
+        for y in 0..20 {
+            grid.set(0, y * 2, y);
+        }
+
+        grid.shuffle_cells(&mut StdRng::seed_from_u64(3));
+
+        let mut values: Vec<isize> = (0..20).map(|y| *grid.get(0, y * 2).unwrap()).collect();
+        assert!(values.windows(2).any(|pair| pair[0] > pair[1]));
+        assert!((0..20).all(|y| grid.get(0, y * 2 + 1).is_none()));
+
+        values.sort();
+        assert_eq!(values, (0..20).collect::<Vec<_>>());
+
+        let mut defaults = Grid::new_with_default('.');
+        defaults.set(0, 0, 'a');
+        defaults.set(0, 5, 'b');
+        defaults.shuffle_cells(&mut StdRng::seed_from_u64(3));
+        assert_eq!(defaults.len(), 2);
+        assert!((1..5).all(|y| defaults.get(0, y) == Some(&'.')));
+    }
+
+    #[test]
+    fn perturbs_some_cells() {
+        let mut grid = Grid::new();
+        grid.fill_rect(Rect::new(0, 0, 19, 19), 0u8);
+        let mut rng = StdRng::seed_from_u64(11);
+
+        let mutated = grid.perturb(&mut rng, 0.25, |&cell, rng| cell + rng.random_range(1..4));
+        let changed = grid.iter().filter(|(_, &cell)| cell != 0).count();
+        assert_eq!(changed, mutated);
+        assert!((60..140).contains(&mutated));
+        assert_eq!(grid.perturb(&mut rng, 0.0, |_, _| 9), 0);
+        assert_eq!(grid.perturb(&mut rng, f64::NAN, |_, _| 9), 0);
+        assert_eq!(grid.perturb(&mut rng, 7.0, |_, _| 9), 400);
+        assert_eq!(grid.len(), 400);
+    }
+}
@@ -0,0 +1,187 @@
+use super::{Grid, Symmetry};
+use crate::visited::VisitedGrid;
+
+// The left side of a rewrite rule, placed with its origin on each cell in
+// turn. `Some(value)` must match that value and `None` matches any stored
+// cell; cells the pattern does not store are not looked at.
+pub type Pattern<T> = Grid<Option<T>>;
+
+// The right side, in the same coordinates as its pattern: `Some(value)` is
+// written, `None` removes the cell and unstored cells are left alone.
+pub type Replacement<T> = Grid<Option<T>>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RewriteStrategy {
+    // Passes over the grid at most; rewriting stops early after a pass
+    // without matches.
+    pub max_passes: usize,
+    // Also try every rule rotated by 90, 180 and 270 degrees.
+    pub rotations: bool,
+}
+
+impl RewriteStrategy {
+    pub const ONE_PASS: RewriteStrategy = RewriteStrategy {
+        max_passes: 1,
+        rotations: false,
+    };
+
+    pub fn until_stable(max_passes: usize) -> Self {
+        Self {
+            max_passes,
+            rotations: false,
+        }
+    }
+
+    pub fn with_rotations(self) -> Self {
+        Self {
+            rotations: true,
+            ..self
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> Grid<T> {
+    fn matches_pattern(
+        &self,
+        pattern: &Pattern<T>,
+        (px, py): (isize, isize),
+        written: &VisitedGrid,
+    ) -> bool {
+        pattern.iter().all(|((dx, dy), expected)| {
+            let (x, y) = (px + dx, py + dy);
+            !written.contains(x, y)
+                && self
+                    .get(x, y)
+                    .is_some_and(|item| expected.as_ref().is_none_or(|expected| expected == item))
+        })
+    }
+
+    fn changes(&self, replacement: &Replacement<T>, (px, py): (isize, isize)) -> bool {
+        replacement
+            .iter()
+            .any(|((dx, dy), item)| self.get(px + dx, py + dy) != item.as_ref())
+    }
+
+    // Applies pattern -> replacement rules for grammar-style map refinement.
+    // Each pass scans positions in row-major order and tries the rules in
+    // order; within a pass a rewrite never overlaps the cells of an earlier
+    // one, so every rule sees cells as they were at the start of the pass.
+    // Matches whose replacement would change nothing are skipped. Returns
+    // how many rewrites were applied in total.
+    pub fn rewrite(
+        &mut self,
+        rules: &[(Pattern<T>, Replacement<T>)],
+        strategy: RewriteStrategy,
+    ) -> usize {
+        let mut rules = rules.to_vec();
+        if strategy.rotations {
+            let symmetries = [Symmetry::Rotate90, Symmetry::Rotate180, Symmetry::Rotate270];
+            for (pattern, replacement) in rules.clone() {
+                for symmetry in symmetries {
+                    rules.push((
+                        pattern.transformed(symmetry),
+                        replacement.transformed(symmetry),
+                    ));
+                }
+            }
+        }
+
+        let mut total = 0;
+        for _ in 0..strategy.max_passes {
+            let bounds = self.bounds();
+            let mut written = VisitedGrid::new();
+            let mut rewrites = 0;
+
+            for (px, py) in bounds.iter() {
+                let Some((pattern, replacement)) = rules.iter().find(|(pattern, replacement)| {
+                    self.matches_pattern(pattern, (px, py), &written)
+                        && self.changes(replacement, (px, py))
+                }) else {
+                    continue;
+                };
+
+                for ((dx, dy), _) in pattern.iter() {
+                    written.insert(px + dx, py + dy);
+                }
+                for ((dx, dy), item) in replacement.iter() {
+                    let (x, y) = (px + dx, py + dy);
+                    written.insert(x, y);
+                    match item {
+                        Some(item) => self.set(x, y, item.clone()),
+                        None => {
+                            self.remove(x, y);
+                        }
+                    }
+                }
+                rewrites += 1;
+            }
+
+            total += rewrites;
+            if rewrites == 0 {
+                break;
+            }
+        }
+        total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Pattern, RewriteStrategy};
+    use crate::grid::Grid;
+
+    fn row(cells: &str) -> Grid<char> {
+        let mut grid = Grid::new();
+        for (y, cell) in cells.chars().enumerate() {
+            grid.set(0, y as isize, cell);
+        }
+        grid
+    }
+
+    fn text(grid: &Grid<char>) -> String {
+        grid.iter().map(|(_, cell)| *cell).collect()
+    }
+
+    #[test]
+    fn rewrites_once_or_until_stable() {
+        // `x` spreads into the floor to its right, one cell per pass.
+        let mut pattern = Pattern::new();
+        pattern.set(0, 0, Some('x'));
+        pattern.set(0, 1, Some('.'));
+        let mut replacement = Pattern::new();
+        replacement.set(0, 1, Some('x'));
+        let rules = [(pattern, replacement)];
+
+        let mut grid = row("x....#x.");
+        assert_eq!(grid.rewrite(&rules, RewriteStrategy::ONE_PASS), 2);
+        assert_eq!(text(&grid), "xx...#xx");
+
+        assert_eq!(grid.rewrite(&rules, RewriteStrategy::until_stable(2)), 2);
+        assert_eq!(text(&grid), "xxxx.#xx");
+        assert_eq!(grid.rewrite(&rules, RewriteStrategy::until_stable(10)), 1);
+        assert_eq!(grid.rewrite(&rules, RewriteStrategy::until_stable(10)), 0);
+    }
+
+    #[test]
+    fn rotated_rules_match_columns() {
+        let mut pattern = Pattern::new();
+        pattern.set(0, 0, Some('a'));
+        pattern.set(0, 1, None);
+        let mut replacement = Pattern::new();
+        replacement.set(0, 1, None);
+
+        let mut grid = Grid::new();
+        grid.set(0, 0, 'a');
+        grid.set(-1, 0, 'b');
+        assert_eq!(
+            grid.rewrite(
+                &[(pattern.clone(), replacement.clone())],
+                RewriteStrategy::ONE_PASS
+            ),
+            0
+        );
+        let strategy = RewriteStrategy::ONE_PASS.with_rotations();
+        assert_eq!(grid.rewrite(&[(pattern, replacement)], strategy), 1);
+        assert_eq!(grid.len(), 1);
+    }
+}
@@ -0,0 +1,118 @@
+use super::{Grid, GridRead};
+
+// How lookups outside the grid bounds (or on empty cells) are resolved.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Edge {
+    Empty,
+    Clamp,
+    Wrap,
+    Constant(f64),
+}
+
+// How `edge` resolves (x, y) on any readable grid. Takes coordinates in i128
+// so offsets from cells at the far ends of the coordinate space can be
+// resolved without overflowing.
+pub(crate) fn resolve<R>(grid: &R, x: i128, y: i128, edge: Edge) -> Option<f64>
+where
+    R: GridRead<f64> + ?Sized,
+{
+    let bounds = grid.bounds();
+    let (min_x, max_x) = (bounds.min_x as i128, bounds.max_x as i128);
+    let (min_y, max_y) = (bounds.min_y as i128, bounds.max_y as i128);
+    let (x, y) = match edge {
+        Edge::Clamp => (x.clamp(min_x, max_x), y.clamp(min_y, max_y)),
+        Edge::Wrap => (
+            min_x + (x - min_x).rem_euclid(max_x - min_x + 1),
+            min_y + (y - min_y).rem_euclid(max_y - min_y + 1),
+        ),
+        Edge::Empty | Edge::Constant(_) => (x, y),
+    };
+
+    let stored = match (isize::try_from(x), isize::try_from(y)) {
+        (Ok(x), Ok(y)) => grid.get(x, y),
+        _ => None,
+    };
+    match (stored, edge) {
+        (Some(value), _) => Some(*value),
+        (None, Edge::Constant(value)) => Some(value),
+        (None, _) => None,
+    }
+}
+
+impl Grid<f64> {
+    pub fn sample(&self, x: f64, y: f64) -> Option<f64> {
+        self.sample_with(x, y, Edge::Empty)
+    }
+
+    pub fn sample_with(&self, x: f64, y: f64, edge: Edge) -> Option<f64> {
+        if !x.is_finite() || !y.is_finite() {
+            return None;
+        }
+
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as isize as i128, y0 as isize as i128);
+        let (x1, y1) = (x0 + 1, y0 + 1);
+
+        let corners = [
+            (x0, y0, (1.0 - tx) * (1.0 - ty)),
+            (x1, y0, tx * (1.0 - ty)),
+            (x0, y1, (1.0 - tx) * ty),
+            (x1, y1, tx * ty),
+        ];
+
+        let mut value = 0.0;
+        for (x, y, weight) in corners {
+            // Corners that don't contribute may be missing, so sampling exactly on a
+            // cell at the edge of the grid still succeeds.
+            if weight > 0.0 {
+                value += resolve(self, x, y, edge)? * weight;
+            }
+        }
+
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Edge, Grid};
+
+    fn square() -> Grid<f64> {
+        let mut grid = Grid::new();
+        grid.set(0, 0, 0.0);
+        grid.set(1, 0, 10.0);
+        grid.set(0, 1, 20.0);
+        grid.set(1, 1, 30.0);
+        grid
+    }
+
+    #[test]
+    fn bilinear_interpolation() {
+        let grid = square();
+
+        assert_eq!(grid.sample(0.0, 0.0), Some(0.0));
+        assert_eq!(grid.sample(1.0, 1.0), Some(30.0));
+        assert_eq!(grid.sample(0.5, 0.0), Some(5.0));
+        assert_eq!(grid.sample(0.5, 0.5), Some(15.0));
+        assert_eq!(grid.sample(0.25, 1.0), Some(22.5));
+    }
+
+    #[test]
+    fn out_of_bounds_behavior() {
+        let grid = square();
+
+        assert_eq!(grid.sample(1.5, 0.0), None);
+        assert_eq!(grid.sample_with(1.5, 0.0, Edge::Clamp), Some(10.0));
+        assert_eq!(grid.sample_with(1.5, 0.0, Edge::Wrap), Some(5.0));
+        assert_eq!(grid.sample_with(1.5, 0.0, Edge::Constant(-10.0)), Some(0.0));
+        assert_eq!(grid.sample_with(f64::NAN, 0.0, Edge::Clamp), None);
+        assert_eq!(
+            grid.sample_with(isize::MAX as f64, 0.0, Edge::Clamp),
+            Some(10.0)
+        );
+        assert_eq!(grid.sample_with(1e300, 1e300, Edge::Clamp), Some(30.0));
+        assert_eq!(grid.sample_with(1e300, 0.0, Edge::Wrap), Some(10.0));
+        assert_eq!(grid.sample_with(-1e300, 0.5, Edge::Wrap), Some(10.0));
+    }
+}
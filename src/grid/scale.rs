@@ -0,0 +1,142 @@
+use super::Grid;
+use crate::error::GridError;
+use crate::rect::Rect;
+
+impl<T> Grid<T> {
+    // `interpolate` receives the source grid and the position of the target cell's
+    // center in source coordinates, where source cells are centered on integers.
+    // Fails with `CoordinateOverflow` when the scaled bounds do not fit.
+    pub fn upscale_with<U, F>(
+        &self,
+        factor: usize,
+        mut interpolate: F,
+    ) -> Result<Grid<U>, GridError>
+    where
+        F: FnMut(&Self, f64, f64) -> Option<U>,
+    {
+        assert!(factor > 0, "upscale factor must be positive");
+
+        let bounds = self.scaled_bounds(factor)?;
+        let scale = factor as f64;
+        let mut grid = Grid::new();
+
+        for (x, y) in bounds.iter() {
+            let source_x = (x as f64 + 0.5) / scale - 0.5;
+            let source_y = (y as f64 + 0.5) / scale - 0.5;
+
+            if let Some(item) = interpolate(self, source_x, source_y) {
+                grid.try_set(x, y, item)?;
+            }
+        }
+
+        grid.set_boundaries(bounds);
+        Ok(grid)
+    }
+
+    // Each axis becomes `min * factor..=max * factor + factor - 1`, checked so
+    // large coordinates or factors are an error rather than an overflow.
+    fn scaled_bounds(&self, factor: usize) -> Result<Rect, GridError> {
+        let overflow = |x, y| GridError::CoordinateOverflow { x, y };
+        let factor = isize::try_from(factor).map_err(|_| overflow(self.max_x, self.max_y))?;
+        let low = |value: isize| value.checked_mul(factor);
+        let high = |value: isize| value.checked_mul(factor)?.checked_add(factor - 1);
+
+        let rect = Rect::new(
+            low(self.min_x).ok_or(overflow(self.min_x, self.min_y))?,
+            low(self.min_y).ok_or(overflow(self.min_x, self.min_y))?,
+            high(self.max_x).ok_or(overflow(self.max_x, self.max_y))?,
+            high(self.max_y).ok_or(overflow(self.max_x, self.max_y))?,
+        );
+        let span = |min: isize, max: isize| max.checked_sub(min)?.checked_add(1);
+        if span(rect.min_x, rect.max_x).is_none() || span(rect.min_y, rect.max_y).is_none() {
+            return Err(overflow(self.max_x, self.max_y));
+        }
+        Ok(rect)
+    }
+
+    fn set_boundaries(&mut self, bounds: Rect) {
+        self.min_x = bounds.min_x;
+        self.max_x = bounds.max_x;
+        self.min_y = bounds.min_y;
+        self.max_y = bounds.max_y;
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn upscale(&self, factor: usize) -> Result<Self, GridError> {
+        assert!(factor > 0, "upscale factor must be positive");
+
+        let bounds = self.scaled_bounds(factor)?;
+        let factor = factor as isize;
+        let mut grid = Grid::new();
+
+        for ((x, y), item) in self.iter() {
+            for dx in 0..factor {
+                for dy in 0..factor {
+                    grid.try_set(x * factor + dx, y * factor + dy, item.clone())?;
+                }
+            }
+        }
+
+        grid.set_boundaries(bounds);
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::error::GridError;
+    use crate::rect::Rect;
+
+    #[test]
+    fn upscale_repeats_blocks() {
+        let mut grid = Grid::new();
+        grid.set(-1, 0, 'a');
+        grid.set(1, -1, 'b');
+
+        let scaled = grid.upscale(3).unwrap();
+
+        assert_eq!((scaled.min_x(), scaled.max_x()), (-3, 5));
+        assert_eq!((scaled.min_y(), scaled.max_y()), (-3, 2));
+
+        for dx in 0..3 {
+            for dy in 0..3 {
+                assert_eq!(scaled.get(-3 + dx, dy), Some(&'a'));
+                assert_eq!(scaled.get(3 + dx, -3 + dy), Some(&'b'));
+            }
+        }
+
+        assert_eq!(scaled.get(0, 0), None);
+
+        let wide = Grid::<char>::with_bounds(Rect::new(0, 0, isize::MAX / 2, 0));
+        assert_eq!(
+            wide.upscale(3).err(),
+            Some(GridError::CoordinateOverflow {
+                x: isize::MAX / 2,
+                y: 0
+            })
+        );
+        assert!(grid.upscale(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn upscale_with_interpolation() {
+        let mut grid = Grid::new();
+        grid.set(0, 0, 0.0);
+        grid.set(1, 0, 4.0);
+
+        let scaled = grid
+            .upscale_with(2, |grid: &Grid<f64>, x, _| {
+                let low = x.floor().clamp(0.0, 1.0);
+                let t = (x - low).clamp(0.0, 1.0);
+                let a = *grid.get(low as isize, 0)?;
+                let b = *grid.get(low as isize + 1, 0).unwrap_or(&a);
+                Some(a + (b - a) * t)
+            })
+            .unwrap();
+
+        let row: Vec<f64> = (0..4).map(|x| *scaled.get(x, 0).unwrap()).collect();
+        assert_eq!(row, vec![0.0, 1.0, 3.0, 4.0]);
+    }
+}
@@ -0,0 +1,163 @@
+use super::Grid;
+use crate::rect::Rect;
+
+// Squared euclidean distance from every sample to the nearest zero of `f`,
+// where `f` is 0 at features and infinite elsewhere (Felzenszwalb and
+// Huttenlocher's lower envelope of parabolas).
+fn envelope(f: &[f64]) -> Vec<f64> {
+    let n = f.len();
+    let mut distances = vec![f64::INFINITY; n];
+    let mut vertices = vec![0; n];
+    let mut boundaries = vec![0.0; n + 1];
+    let mut k = 0;
+
+    let Some(first) = f.iter().position(|value| value.is_finite()) else {
+        return distances;
+    };
+    vertices[0] = first;
+    boundaries[0] = f64::NEG_INFINITY;
+    boundaries[1] = f64::INFINITY;
+
+    for q in first + 1..n {
+        if !f[q].is_finite() {
+            continue;
+        }
+        let parabola = |v: usize| f[v] + (v * v) as f64;
+        let intersection = |v: usize| (parabola(q) - parabola(v)) / (2 * (q - v)) as f64;
+        let mut s = intersection(vertices[k]);
+        while s <= boundaries[k] {
+            k -= 1;
+            s = intersection(vertices[k]);
+        }
+        k += 1;
+        vertices[k] = q;
+        boundaries[k] = s;
+        boundaries[k + 1] = f64::INFINITY;
+    }
+
+    k = 0;
+    for (q, distance) in distances.iter_mut().enumerate() {
+        while boundaries[k + 1] < q as f64 {
+            k += 1;
+        }
+        let v = vertices[k];
+        *distance = (q as f64 - v as f64).powi(2) + f[v];
+    }
+    distances
+}
+
+// Exact euclidean distances, in row-major order over `bounds`, from each
+// cell to the nearest cell for which `feature` holds.
+fn distance_transform<F>(bounds: Rect, feature: F) -> Vec<f64>
+where
+    F: Fn(isize, isize) -> bool,
+{
+    let (height, width) = (bounds.height(), bounds.width());
+    let mut squared: Vec<f64> = bounds
+        .iter()
+        .map(|(x, y)| if feature(x, y) { 0.0 } else { f64::INFINITY })
+        .collect();
+
+    for row in squared.chunks_mut(width) {
+        let distances = envelope(row);
+        row.copy_from_slice(&distances);
+    }
+    for y in 0..width {
+        let column: Vec<f64> = (0..height).map(|x| squared[x * width + y]).collect();
+        for (x, distance) in envelope(&column).into_iter().enumerate() {
+            squared[x * width + y] = distance;
+        }
+    }
+
+    squared.into_iter().map(f64::sqrt).collect()
+}
+
+impl<T> Grid<T> {
+    // For every cell of the bounds, the distance between cell centers to the
+    // nearest stored cell matching the predicate: 0 on matching cells and
+    // infinite when no cell matches.
+    pub fn distance_field<F>(&self, predicate: F) -> Grid<f64>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let bounds = self.bounds();
+        let inside = |x, y| self.get(x, y).is_some_and(&predicate);
+        let distances = distance_transform(bounds, inside);
+
+        let mut field = Grid::with_boundaries_of(self);
+        for ((x, y), distance) in bounds.iter().zip(distances) {
+            field.set(x, y, distance);
+        }
+        field
+    }
+
+    // Distance to the shape of matching cells, negative inside it: outside
+    // cells get the distance to the nearest inside cell, inside cells minus
+    // the distance to the nearest outside one, so the edge lies between -1
+    // and 1. Empty cells count as outside.
+    pub fn signed_distance_field<F>(&self, predicate: F) -> Grid<f64>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let bounds = self.bounds();
+        let inside = |x, y| self.get(x, y).is_some_and(&predicate);
+        let to_inside = distance_transform(bounds, inside);
+        let to_outside = distance_transform(bounds, |x, y| !inside(x, y));
+
+        let mut field = Grid::with_boundaries_of(self);
+        for (((x, y), to_inside), to_outside) in bounds.iter().zip(to_inside).zip(to_outside) {
+            field.set(
+                x,
+                y,
+                if to_inside == 0.0 {
+                    -to_outside
+                } else {
+                    to_inside
+                },
+            );
+        }
+        field
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::rect::Rect;
+
+    #[test]
+    fn matches_brute_force_distances() {
+        let mut grid = Grid::new();
+        grid.fill_rect(Rect::new(-4, -5, 6, 7), false);
+        for (x, y) in [(-3, 2), (0, 0), (4, -5), (5, 6)] {
+            grid.set(x, y, true);
+        }
+
+        let field = grid.distance_field(|&feature| feature);
+        for ((x, y), &distance) in field.iter() {
+            let nearest = grid
+                .iter()
+                .filter(|(_, &feature)| feature)
+                .map(|((fx, fy), _)| (((fx - x).pow(2) + (fy - y).pow(2)) as f64).sqrt())
+                .fold(f64::INFINITY, f64::min);
+            assert!((distance - nearest).abs() < 1e-9, "({x}, {y})");
+        }
+        assert!(Grid::<bool>::new()
+            .distance_field(|&feature| feature)
+            .get(0, 0)
+            .is_some_and(|distance| distance.is_infinite()));
+    }
+
+    #[test]
+    fn signs_inside_and_outside() {
+        let mut grid = Grid::new();
+        grid.fill_rect(Rect::new(0, 0, 6, 6), false);
+        grid.fill_rect(Rect::new(1, 1, 5, 5), true);
+
+        let field = grid.signed_distance_field(|&solid| solid);
+        assert_eq!(field.get(3, 3), Some(&-3.0));
+        assert_eq!(field.get(1, 1), Some(&-1.0));
+        assert_eq!(field.get(0, 3), Some(&1.0));
+        assert_eq!(field.get(0, 0), Some(&2f64.sqrt()));
+    }
+}
@@ -0,0 +1,87 @@
+use super::{Grid, GridRead, Wrapping};
+use crate::direction::Direction;
+
+// A start cell and the direction a match runs in.
+type Occurrence = ((isize, isize), Direction);
+
+fn find_in<T, R>(
+    grid: &R,
+    starts: &Grid<T>,
+    needle: &[T],
+    directions: &[Direction],
+) -> Vec<Occurrence>
+where
+    T: PartialEq,
+    R: GridRead<T>,
+{
+    if needle.is_empty() {
+        return vec![];
+    }
+    let mut found = vec![];
+    for ((x, y), _) in starts.iter() {
+        for &direction in directions {
+            let (dx, dy) = direction.offset();
+            let matches = needle.iter().enumerate().all(|(i, expected)| {
+                let i = i as isize;
+                grid.get(x + dx * i, y + dy * i) == Some(expected)
+            });
+            if matches {
+                found.push(((x, y), direction));
+            }
+        }
+    }
+    found
+}
+
+impl<T: PartialEq> Grid<T> {
+    // Every straight-line occurrence of `needle` running in one of
+    // `directions`, as in a word search, in row-major order of the start
+    // cells; pass `Direction::ALL` for all eight.
+    pub fn find_sequences(&self, needle: &[T], directions: &[Direction]) -> Vec<Occurrence> {
+        find_in(self, self, needle, directions)
+    }
+
+    // Like `find_sequences`, with occurrences allowed to run off one edge of
+    // the bounds and continue from the opposite one.
+    pub fn find_sequences_wrapping(
+        &self,
+        needle: &[T],
+        directions: &[Direction],
+    ) -> Vec<Occurrence> {
+        find_in(&Wrapping(self), self, needle, directions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::direction::Direction;
+    use crate::grid::Grid;
+
+    fn parse(rows: &[&str]) -> Grid<char> {
+        let mut grid = Grid::new();
+        for (x, row) in rows.iter().enumerate() {
+            for (y, cell) in row.chars().enumerate() {
+                grid.set(x as isize, y as isize, cell);
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn finds_words_in_all_directions() {
+        let grid = parse(&["CATX", "AXXX", "TXCA"]);
+        let cat: Vec<char> = "CAT".chars().collect();
+
+        assert_eq!(
+            grid.find_sequences(&cat, &Direction::ALL),
+            [((0, 0), Direction::East), ((0, 0), Direction::South),]
+        );
+        assert_eq!(grid.find_sequences(&cat, &[Direction::West]), []);
+
+        let wrapped = grid.find_sequences_wrapping(&cat, &[Direction::East]);
+        assert_eq!(
+            wrapped,
+            [((0, 0), Direction::East), ((2, 2), Direction::East)]
+        );
+    }
+}
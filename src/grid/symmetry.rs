@@ -0,0 +1,175 @@
+use super::Grid;
+
+// The eight rotations and reflections of the square, acting on coordinates
+// around the origin.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    FlipX,
+    FlipY,
+    Transpose,
+    AntiTranspose,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::FlipX,
+        Symmetry::FlipY,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+    ];
+
+    // Rotations are clockwise with x pointing down and y pointing right.
+    pub fn apply(self, (x, y): (isize, isize)) -> (isize, isize) {
+        match self {
+            Symmetry::Identity => (x, y),
+            Symmetry::Rotate90 => (y, -x),
+            Symmetry::Rotate180 => (-x, -y),
+            Symmetry::Rotate270 => (-y, x),
+            Symmetry::FlipX => (-x, y),
+            Symmetry::FlipY => (x, -y),
+            Symmetry::Transpose => (y, x),
+            Symmetry::AntiTranspose => (-y, -x),
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    // Stored cells relative to the top left corner of their tight bounds, in
+    // row-major order.
+    fn normalized_cells(&self, symmetry: Symmetry) -> Vec<((isize, isize), &T)> {
+        let mut cells: Vec<((isize, isize), &T)> = self
+            .iter()
+            .map(|(position, item)| (symmetry.apply(position), item))
+            .collect();
+
+        let min_x = cells.iter().map(|((x, _), _)| *x).min().unwrap_or(0);
+        let min_y = cells.iter().map(|((_, y), _)| *y).min().unwrap_or(0);
+        for ((x, y), _) in &mut cells {
+            *x -= min_x;
+            *y -= min_y;
+        }
+
+        cells.sort_by_key(|(position, _)| *position);
+        cells
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn transformed(&self, symmetry: Symmetry) -> Grid<T> {
+        let mut grid = Grid::new();
+
+        for (position, item) in self.iter() {
+            let (x, y) = symmetry.apply(position);
+            grid.set(x, y, item.clone());
+        }
+
+        grid
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    // The symmetries mapping the occupied pattern onto itself up to
+    // translation; always contains the identity.
+    pub fn symmetries(&self) -> Vec<Symmetry> {
+        let original = self.normalized_cells(Symmetry::Identity);
+
+        Symmetry::ALL
+            .into_iter()
+            .filter(|symmetry| self.normalized_cells(*symmetry) == original)
+            .collect()
+    }
+}
+
+impl<T: Ord + Clone> Grid<T> {
+    // Of all rotations and reflections, the one whose row-major list of
+    // (position, value) entries is smallest, placed with its tight bounds
+    // starting at (0, 0). Patterns equal up to symmetry and translation share
+    // the same canonical form.
+    pub fn canonicalize(&self) -> Grid<T> {
+        let cells = Symmetry::ALL
+            .into_iter()
+            .map(|symmetry| self.normalized_cells(symmetry))
+            .min()
+            .unwrap_or_default();
+
+        let mut grid = Grid::new();
+        for ((x, y), item) in cells {
+            grid.set(x, y, item.clone());
+        }
+
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Grid, Symmetry};
+
+    fn pattern(rows: &[&str], x: isize, y: isize) -> Grid<char> {
+        let mut grid = Grid::new();
+        for (dx, row) in rows.iter().enumerate() {
+            for (dy, cell) in row.chars().enumerate() {
+                if cell != '.' {
+                    grid.set(x + dx as isize, y + dy as isize, cell);
+                }
+            }
+        }
+        grid
+    }
+
+    fn cells(grid: &Grid<char>) -> Vec<((isize, isize), char)> {
+        grid.bounds()
+            .iter()
+            .filter_map(|(x, y)| Some(((x, y), *grid.get(x, y)?)))
+            .collect()
+    }
+
+    #[test]
+    fn detects_symmetries() {
+        let square = pattern(&["##", "##"], 3, -7);
+        assert_eq!(square.symmetries(), Symmetry::ALL.to_vec());
+
+        let l_piece = pattern(&["#.", "#.", "##"], 0, 0);
+        assert_eq!(l_piece.symmetries(), vec![Symmetry::Identity]);
+
+        let bar = pattern(&["#a#"], 0, 0);
+        assert_eq!(
+            bar.symmetries(),
+            vec![
+                Symmetry::Identity,
+                Symmetry::Rotate180,
+                Symmetry::FlipX,
+                Symmetry::FlipY
+            ]
+        );
+    }
+
+    #[test]
+    fn canonical_forms_match_across_orientations() {
+        let l_piece = pattern(&["#.", "#.", "##"], 5, 5);
+        let canonical = cells(&l_piece.canonicalize());
+
+        for symmetry in Symmetry::ALL {
+            let moved = l_piece.transformed(symmetry);
+            assert_eq!(cells(&moved.canonicalize()), canonical);
+        }
+
+        let s_piece = pattern(&[".##", "##."], 0, 0);
+        assert_ne!(cells(&s_piece.canonicalize()), canonical);
+
+        let mut sparse = Grid::new_with_default('.');
+        sparse.set(0, 0, '#');
+        sparse.set(0, 1, '#');
+        sparse.set(4, 9, '.');
+        assert_eq!(sparse.symmetries().len(), 4);
+        assert_eq!(sparse.transformed(Symmetry::Transpose).len(), 2);
+    }
+}
@@ -0,0 +1,145 @@
+use super::Grid;
+use crate::direction::Direction;
+use std::error::Error;
+use std::fmt;
+
+// An instruction `Turtle::run` could not read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InstructionError {
+    pub instruction: String,
+}
+
+impl fmt::Display for InstructionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid turtle instruction {:?}", self.instruction)
+    }
+}
+
+impl Error for InstructionError {}
+
+// A heading and an optional pen on a mutably borrowed grid, for tracing
+// instruction lists. Moving with the pen down paints every cell passed,
+// including the one the move starts from, and every position is recorded.
+pub struct Turtle<'a, T> {
+    grid: &'a mut Grid<T>,
+    heading: Direction,
+    pen: Option<T>,
+    path: Vec<(isize, isize)>,
+}
+
+impl<T> Grid<T> {
+    pub fn turtle(&mut self, x: isize, y: isize, heading: Direction) -> Turtle<'_, T> {
+        Turtle {
+            grid: self,
+            heading,
+            pen: None,
+            path: vec![(x, y)],
+        }
+    }
+}
+
+impl<T: Clone> Turtle<'_, T> {
+    pub fn position(&self) -> (isize, isize) {
+        *self.path.last().unwrap()
+    }
+
+    pub fn heading(&self) -> Direction {
+        self.heading
+    }
+
+    // Every position so far, from the start, one per step.
+    pub fn path(&self) -> &[(isize, isize)] {
+        &self.path
+    }
+
+    pub fn pen(&mut self, item: T) {
+        self.pen = Some(item);
+    }
+
+    pub fn pen_up(&mut self) {
+        self.pen = None;
+    }
+
+    // Quarter turns.
+    pub fn turn_left(&mut self) {
+        self.heading = self.heading.counter_clockwise().counter_clockwise();
+    }
+
+    pub fn turn_right(&mut self) {
+        self.heading = self.heading.clockwise().clockwise();
+    }
+
+    // Stops early at the edge of the coordinate space.
+    pub fn forward(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.paint();
+            let Some(next) = self.heading.checked_step(self.position()) else {
+                return;
+            };
+            self.path.push(next);
+        }
+        self.paint();
+    }
+
+    fn paint(&mut self) {
+        if let Some(item) = &self.pen {
+            let (x, y) = self.position();
+            self.grid.set(x, y, item.clone());
+        }
+    }
+
+    // Follows instructions such as "R5, L3, F2" separated by commas or
+    // whitespace: `L` and `R` turn before moving, `F` moves straight on,
+    // and the number gives the steps to move. Instructions before an
+    // invalid one have already been followed.
+    pub fn run(&mut self, instructions: &str) -> Result<(), InstructionError> {
+        let tokens = instructions
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|token| !token.is_empty());
+        for token in tokens {
+            let invalid = || InstructionError {
+                instruction: token.to_string(),
+            };
+            let mut chars = token.chars();
+            let turn = chars.next().ok_or_else(invalid)?;
+            let steps: usize = chars.as_str().parse().map_err(|_| invalid())?;
+            match turn.to_ascii_uppercase() {
+                'L' => self.turn_left(),
+                'R' => self.turn_right(),
+                'F' => {}
+                _ => return Err(invalid()),
+            }
+            self.forward(steps);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::direction::Direction;
+
+    #[test]
+    fn traces_instructions() {
+        let mut grid = Grid::new();
+        let mut turtle = grid.turtle(0, 0, Direction::North);
+        turtle.pen('#');
+        turtle.run("R2, R1 R1").unwrap();
+        assert_eq!(turtle.position(), (1, 1));
+        assert_eq!(turtle.heading(), Direction::West);
+        assert_eq!(turtle.path(), [(0, 0), (0, 1), (0, 2), (1, 2), (1, 1)]);
+
+        turtle.pen_up();
+        turtle.run("f1").unwrap();
+        turtle.turn_left();
+        turtle.forward(3);
+        assert_eq!(turtle.position(), (4, 0));
+        assert!(turtle.run("L2, X1").is_err());
+        assert!(turtle.run("R").is_err());
+
+        assert_eq!(grid.len(), 5);
+        assert_eq!(grid.get(1, 1), Some(&'#'));
+        assert_eq!(grid.get(1, 0), None);
+    }
+}
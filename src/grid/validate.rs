@@ -0,0 +1,144 @@
+use super::{Grid, NegativeIndexVec};
+use std::fmt;
+
+// A broken internal invariant found by `Grid::validate`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Violation {
+    InvertedBounds,
+    OutsideBounds { x: isize, y: isize },
+    OutsideMaxBounds { x: isize, y: isize },
+    StoredDefault { x: isize, y: isize },
+    LenMismatch { recorded: usize, counted: usize },
+    MemoryMismatch { recorded: usize, allocated: usize },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::InvertedBounds => write!(f, "bounds have min greater than max"),
+            Violation::OutsideBounds { x, y } => write!(f, "cell ({x}, {y}) is outside the bounds"),
+            Violation::OutsideMaxBounds { x, y } => {
+                write!(f, "cell ({x}, {y}) is outside the maximum bounds")
+            }
+            Violation::StoredDefault { x, y } => {
+                write!(f, "cell ({x}, {y}) stores the default value")
+            }
+            Violation::LenMismatch { recorded, counted } => {
+                write!(f, "len is {recorded} but {counted} cells are stored")
+            }
+            Violation::MemoryMismatch {
+                recorded,
+                allocated,
+            } => write!(
+                f,
+                "memory usage is {recorded} bytes but {allocated} bytes of slots exist"
+            ),
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    // Checks the bookkeeping against the stored cells, returning every
+    // violation found. A grid only built through the public API always
+    // passes; this is for testing new storage code and transforms.
+    pub fn validate(&self) -> Result<(), Vec<Violation>> {
+        let mut violations = vec![];
+        if self.min_x > self.max_x || self.min_y > self.max_y {
+            violations.push(Violation::InvertedBounds);
+        }
+
+        let bounds = self.bounds();
+        let mut counted = 0;
+        for ((x, y), item) in self.iter() {
+            counted += 1;
+            if !bounds.contains(x, y) {
+                violations.push(Violation::OutsideBounds { x, y });
+            }
+            if self.max_bounds.is_some_and(|max| !max.contains(x, y)) {
+                violations.push(Violation::OutsideMaxBounds { x, y });
+            }
+            if self
+                .default
+                .as_ref()
+                .is_some_and(|default| (self.is_default)(item, default))
+            {
+                violations.push(Violation::StoredDefault { x, y });
+            }
+        }
+        if counted != self.len {
+            violations.push(Violation::LenMismatch {
+                recorded: self.len,
+                counted,
+            });
+        }
+
+        let rows = self.positive.len() + self.negative.len();
+        let cells: usize = self
+            .positive
+            .iter()
+            .chain(&self.negative)
+            .flatten()
+            .map(|row| row.positive.len() + row.negative.len())
+            .sum();
+        let allocated =
+            rows * size_of::<Option<NegativeIndexVec<T>>>() + cells * size_of::<Option<T>>();
+        if allocated != self.memory {
+            violations.push(Violation::MemoryMismatch {
+                recorded: self.memory,
+                allocated,
+            });
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    // Run after bulk operations that touch the storage directly; costs a
+    // full scan, so only in debug builds.
+    pub(crate) fn debug_validate(&self) {
+        #[cfg(debug_assertions)]
+        if let Err(violations) = self.validate() {
+            let report: Vec<String> = violations.iter().map(ToString::to_string).collect();
+            panic!("grid invariants broken: {}", report.join("; "));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Grid, Violation};
+    use crate::grid::Edit;
+
+    #[test]
+    fn passes_after_public_edits() {
+        let mut grid = Grid::new();
+        grid.set(-3, 4, 1);
+        grid.set(2, -7, 2);
+        grid.remove(-3, 4);
+        grid.apply_batch((0..20).map(|y| Edit::Set(5, y - 10, y)));
+        assert_eq!(grid.validate(), Ok(()));
+
+        let mut defaulted = Grid::new_with_default(0);
+        defaulted.set(1, 1, 3);
+        defaulted.set(1, 1, 0);
+        assert_eq!(defaulted.validate(), Ok(()));
+    }
+
+    #[test]
+    fn reports_broken_bookkeeping() {
+        let mut grid = Grid::new();
+        grid.set(1, 1, 'a');
+        grid.len = 4;
+        grid.max_x = 0;
+
+        let violations = grid.validate().unwrap_err();
+        assert!(violations.contains(&Violation::OutsideBounds { x: 1, y: 1 }));
+        assert!(violations.contains(&Violation::LenMismatch {
+            recorded: 4,
+            counted: 1
+        }));
+    }
+}
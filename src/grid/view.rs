@@ -0,0 +1,210 @@
+use super::Grid;
+use crate::rect::Rect;
+use std::marker::PhantomData;
+
+// Read access shared by grids, views and adapters, so algorithms can take
+// any of them. `bounds` is the region worth visiting; cells outside it may
+// still hold values for types that do not clip.
+pub trait GridRead<T> {
+    fn get(&self, x: isize, y: isize) -> Option<&T>;
+
+    fn bounds(&self) -> Rect;
+
+    // Only the cells inside `rect`, at their own coordinates.
+    fn view(&self, rect: Rect) -> GridView<'_, T, Self> {
+        GridView {
+            grid: self,
+            rect,
+            visible: Some(rect),
+            offset: (0, 0),
+            cells: PhantomData,
+        }
+    }
+
+    // The cells inside the bounds moved by (dx, dy), so (x, y) reads
+    // (x - dx, y - dy).
+    fn translated(&self, dx: isize, dy: isize) -> GridView<'_, T, Self> {
+        let bounds = self.bounds();
+        GridView {
+            grid: self,
+            rect: bounds,
+            visible: Some(bounds),
+            offset: (0, 0),
+            cells: PhantomData,
+        }
+        .translated(dx, dy)
+    }
+}
+
+pub trait GridWrite<T>: GridRead<T> {
+    fn set(&mut self, x: isize, y: isize, item: T);
+
+    fn remove(&mut self, x: isize, y: isize) -> Option<T>;
+}
+
+impl<T> GridRead<T> for Grid<T> {
+    fn get(&self, x: isize, y: isize) -> Option<&T> {
+        Grid::get(self, x, y)
+    }
+
+    fn bounds(&self) -> Rect {
+        Grid::bounds(self)
+    }
+}
+
+impl<T> GridWrite<T> for Grid<T> {
+    fn set(&mut self, x: isize, y: isize, item: T) {
+        Grid::set(self, x, y, item);
+    }
+
+    fn remove(&mut self, x: isize, y: isize) -> Option<T> {
+        Grid::remove(self, x, y)
+    }
+}
+
+// References read and write through, so adapters can wrap a borrowed grid.
+impl<T, G: GridRead<T> + ?Sized> GridRead<T> for &G {
+    fn get(&self, x: isize, y: isize) -> Option<&T> {
+        (**self).get(x, y)
+    }
+
+    fn bounds(&self) -> Rect {
+        (**self).bounds()
+    }
+}
+
+impl<T, G: GridRead<T> + ?Sized> GridRead<T> for &mut G {
+    fn get(&self, x: isize, y: isize) -> Option<&T> {
+        (**self).get(x, y)
+    }
+
+    fn bounds(&self) -> Rect {
+        (**self).bounds()
+    }
+}
+
+impl<T, G: GridWrite<T> + ?Sized> GridWrite<T> for &mut G {
+    fn set(&mut self, x: isize, y: isize, item: T) {
+        (**self).set(x, y, item);
+    }
+
+    fn remove(&mut self, x: isize, y: isize) -> Option<T> {
+        (**self).remove(x, y)
+    }
+}
+
+fn shifted(rect: Rect, dx: isize, dy: isize) -> Rect {
+    Rect {
+        min_x: rect.min_x.saturating_add(dx),
+        min_y: rect.min_y.saturating_add(dy),
+        max_x: rect.max_x.saturating_add(dx),
+        max_y: rect.max_y.saturating_add(dy),
+    }
+}
+
+// A borrowed window onto a grid or adapter. Views of views borrow the same
+// grid rather than the outer view, keeping only the cells visible through
+// both.
+pub struct GridView<'a, T, G: ?Sized> {
+    grid: &'a G,
+    rect: Rect,
+    visible: Option<Rect>,
+    offset: (isize, isize),
+    cells: PhantomData<fn() -> T>,
+}
+
+impl<T, G: ?Sized> Clone for GridView<'_, T, G> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, G: ?Sized> Copy for GridView<'_, T, G> {}
+
+impl<'a, T, G: GridRead<T> + ?Sized> GridView<'a, T, G> {
+    pub fn view(&self, rect: Rect) -> GridView<'a, T, G> {
+        GridView {
+            rect,
+            visible: self.visible.and_then(|visible| visible.intersection(&rect)),
+            ..*self
+        }
+    }
+
+    pub fn translated(&self, dx: isize, dy: isize) -> GridView<'a, T, G> {
+        GridView {
+            rect: shifted(self.rect, dx, dy),
+            visible: self.visible.map(|visible| shifted(visible, dx, dy)),
+            offset: (
+                self.offset.0.saturating_add(dx),
+                self.offset.1.saturating_add(dy),
+            ),
+            ..*self
+        }
+    }
+}
+
+impl<T, G: GridRead<T> + ?Sized> GridRead<T> for GridView<'_, T, G> {
+    fn get(&self, x: isize, y: isize) -> Option<&T> {
+        if !self.visible?.contains(x, y) {
+            return None;
+        }
+
+        let (dx, dy) = self.offset;
+        self.grid.get(x.checked_sub(dx)?, y.checked_sub(dy)?)
+    }
+
+    fn bounds(&self) -> Rect {
+        self.rect
+    }
+}
+
+impl<T: Clone, G: GridRead<T> + ?Sized> GridView<'_, T, G> {
+    pub fn to_grid(&self) -> Grid<T> {
+        let mut grid = Grid::with_bounds(self.rect);
+        for (x, y) in self.rect.iter() {
+            if let Some(item) = self.get(x, y) {
+                grid.set(x, y, item.clone());
+            }
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GridRead;
+    use crate::grid::Grid;
+    use crate::path::astar;
+    use crate::rect::Rect;
+
+    #[test]
+    fn views_compose() {
+        let mut grid = Grid::new();
+        for (x, y) in Rect::new(0, 0, 4, 4).iter() {
+            grid.set(x, y, x * 10 + y);
+        }
+
+        let view = grid.view(Rect::new(1, 1, 3, 3));
+        assert_eq!(view.get(0, 0), None);
+        assert_eq!(view.get(2, 3), Some(&23));
+
+        let inner = view.view(Rect::new(3, 0, 4, 4)).translated(-3, 5);
+        assert_eq!(inner.bounds(), Rect::new(0, 5, 1, 9));
+        assert_eq!(inner.get(0, 6), Some(&31));
+        assert_eq!(inner.get(1, 6), None);
+        assert_eq!(inner.to_grid().get(0, 8), Some(&33));
+    }
+
+    #[test]
+    fn algorithms_accept_views() {
+        let mut grid = Grid::new();
+        for (x, y) in Rect::new(0, 0, 4, 4).iter() {
+            grid.set(x, y, true);
+        }
+
+        let view = grid.view(Rect::new(0, 0, 0, 4)).translated(7, 0);
+        let path = astar(&view, (7, 0), (7, 4), |&open| open).unwrap();
+        assert_eq!(path.len(), 5);
+        assert!(astar(&view, (0, 0), (0, 4), |&open| open).is_none());
+    }
+}
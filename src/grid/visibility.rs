@@ -0,0 +1,144 @@
+use super::Grid;
+use crate::coordset::CoordSet;
+use crate::path::segment_clear;
+use std::collections::HashMap;
+
+type CellPair = ((isize, isize), (isize, isize));
+
+// Remembered `mutual_visibility` answers, keyed by the unordered pair of
+// cells. Sight lines depend on every cell between the two, so the cache
+// must be cleared whenever a cell's transparency changes.
+#[derive(Clone, Debug, Default)]
+pub struct VisibilityCache {
+    pairs: HashMap<CellPair, bool>,
+}
+
+impl VisibilityCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.pairs.clear();
+    }
+}
+
+impl<T> Grid<T> {
+    // Whether `target` can be seen from `origin`: every cell the segment
+    // between their centers crosses is transparent, except the target
+    // itself, so walls are seen but not seen through. Empty cells block
+    // sight, and diagonal gaps need both cells beside the corner, as for
+    // `line_of_sight`.
+    fn sees<F>(&self, origin: (isize, isize), target: (isize, isize), transparent: &F) -> bool
+    where
+        F: Fn(&T) -> bool,
+    {
+        let see_through = |cell: (isize, isize)| {
+            cell == target || self.get(cell.0, cell.1).is_some_and(transparent)
+        };
+        segment_clear(&see_through, origin, target)
+    }
+
+    // The stored cells within euclidean `radius` of `origin` that it can
+    // see, including the origin.
+    pub fn visibility_from<F>(
+        &self,
+        origin: (isize, isize),
+        radius: usize,
+        transparent: F,
+    ) -> CoordSet
+    where
+        F: Fn(&T) -> bool,
+    {
+        let radius = radius as isize;
+        let mut visible = CoordSet::new();
+        for dx in -radius..=radius {
+            for dy in -radius..=radius {
+                let target = (origin.0 + dx, origin.1 + dy);
+                if dx * dx + dy * dy <= radius * radius
+                    && self.get(target.0, target.1).is_some()
+                    && self.sees(origin, target, &transparent)
+                {
+                    visible.insert(target.0, target.1);
+                }
+            }
+        }
+        visible
+    }
+
+    // Whether `a` and `b` can each see the other.
+    pub fn mutual_visibility<F>(&self, a: (isize, isize), b: (isize, isize), transparent: F) -> bool
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.sees(a, b, &transparent) && self.sees(b, a, &transparent)
+    }
+
+    // `mutual_visibility` answered from `cache` when the pair was checked
+    // before, for AI that re-checks the same pairs every turn.
+    pub fn mutual_visibility_cached<F>(
+        &self,
+        a: (isize, isize),
+        b: (isize, isize),
+        transparent: F,
+        cache: &mut VisibilityCache,
+    ) -> bool
+    where
+        F: Fn(&T) -> bool,
+    {
+        let key = (a.min(b), a.max(b));
+        *cache
+            .pairs
+            .entry(key)
+            .or_insert_with(|| self.mutual_visibility(a, b, transparent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Grid, VisibilityCache};
+    use crate::rect::Rect;
+
+    #[test]
+    fn walls_block_sight() {
+        // A wall in row 2 with a gap at column 4.
+        let mut grid = Grid::new();
+        grid.fill_rect(Rect::new(0, 0, 4, 6), '.');
+        grid.fill_rect(Rect::new(2, 0, 2, 6), '#');
+        grid.set(2, 4, '.');
+
+        let visible = grid.visibility_from((0, 4), 10, |&cell| cell == '.');
+        assert!(visible.contains(0, 4));
+        assert!(visible.contains(2, 5));
+        assert!(visible.contains(4, 4));
+        assert!(!visible.contains(4, 0));
+        assert!(!visible.contains(3, 0));
+
+        let close = grid.visibility_from((0, 4), 1, |&cell| cell == '.');
+        assert_eq!(close.len(), 4);
+    }
+
+    #[test]
+    fn caches_pairs() {
+        let mut grid = Grid::new();
+        grid.fill_rect(Rect::new(0, 0, 0, 4), true);
+        grid.set(0, 2, false);
+
+        let mut cache = VisibilityCache::new();
+        assert!(!grid.mutual_visibility_cached((0, 0), (0, 4), |&open| open, &mut cache));
+        assert!(grid.mutual_visibility((0, 0), (0, 1), |&open| open));
+        grid.set(0, 2, true);
+        assert!(!grid.mutual_visibility_cached((0, 4), (0, 0), |&open| open, &mut cache));
+        assert_eq!(cache.len(), 1);
+        cache.clear();
+        assert!(grid.mutual_visibility_cached((0, 4), (0, 0), |&open| open, &mut cache));
+    }
+}
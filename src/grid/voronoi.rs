@@ -0,0 +1,99 @@
+use super::Grid;
+use crate::direction::Direction;
+use crate::metric::Metric;
+use crate::rect::Rect;
+use std::collections::VecDeque;
+
+impl Grid<usize> {
+    // Fills the region with the index of the nearest seed. Grid metrics use a
+    // breadth-first search from all seeds at once; seeds outside the region
+    // are ignored and ties go to whichever seed reaches a cell first.
+    pub fn voronoi(region: Rect, seeds: &[(isize, isize)], metric: Metric) -> Grid<usize> {
+        let mut grid = Grid::new();
+        let seeds: Vec<(usize, (isize, isize))> = seeds
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, (x, y))| region.contains(*x, *y))
+            .collect();
+
+        let directions: &[Direction] = match metric {
+            Metric::Manhattan => &Direction::CARDINAL,
+            Metric::Chebyshev => &Direction::ALL,
+            Metric::Euclidean => {
+                for (x, y) in region.iter() {
+                    let nearest = seeds.iter().min_by(|(_, a), (_, b)| {
+                        metric
+                            .distance(*a, (x, y))
+                            .total_cmp(&metric.distance(*b, (x, y)))
+                    });
+
+                    if let Some((index, _)) = nearest {
+                        grid.set(x, y, *index);
+                    }
+                }
+
+                return grid;
+            }
+        };
+
+        let mut queue = VecDeque::new();
+        for &(index, (x, y)) in &seeds {
+            if grid.get(x, y).is_none() {
+                grid.set(x, y, index);
+                queue.push_back((x, y));
+            }
+        }
+
+        while let Some((x, y)) = queue.pop_front() {
+            let index = *grid.get(x, y).unwrap();
+
+            for direction in directions {
+                let (dx, dy) = direction.offset();
+                let (nx, ny) = (x + dx, y + dy);
+
+                if region.contains(nx, ny) && grid.get(nx, ny).is_none() {
+                    grid.set(nx, ny, index);
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::metric::Metric;
+    use crate::rect::Rect;
+
+    #[test]
+    fn cells_belong_to_nearest_seed() {
+        let region = Rect::new(-5, -5, 5, 5);
+        let seeds = [(-3, -3), (3, 3), (0, 4), (50, 50)];
+
+        for metric in [Metric::Manhattan, Metric::Chebyshev, Metric::Euclidean] {
+            let grid = Grid::voronoi(region, &seeds, metric);
+
+            assert_eq!(grid.bounds(), region);
+            for (x, y) in region.iter() {
+                let owner = *grid.get(x, y).unwrap();
+                let best = seeds[..3]
+                    .iter()
+                    .map(|seed| metric.distance(*seed, (x, y)))
+                    .fold(f64::INFINITY, f64::min);
+
+                assert_ne!(owner, 3);
+                assert_eq!(metric.distance(seeds[owner], (x, y)), best);
+            }
+        }
+    }
+
+    #[test]
+    fn no_seeds_leaves_region_empty() {
+        let grid = Grid::voronoi(Rect::new(0, 0, 3, 3), &[], Metric::Manhattan);
+        assert_eq!(grid.get(1, 1), None);
+    }
+}
@@ -0,0 +1,134 @@
+use super::Grid;
+use crate::direction::Direction;
+use std::f64::consts::SQRT_2;
+
+impl Grid<f64> {
+    fn steepest_descent(&self, x: isize, y: isize) -> Option<(isize, isize)> {
+        let value = *self.get(x, y)?;
+        let mut best = None;
+        let mut steepest = 0.0;
+
+        for direction in Direction::ALL {
+            let (dx, dy) = direction.offset();
+            if let Some(neighbor) = self.get(x + dx, y + dy) {
+                let distance = if direction.is_diagonal() { SQRT_2 } else { 1.0 };
+                let slope = (value - neighbor) / distance;
+
+                if slope > steepest {
+                    steepest = slope;
+                    best = Some((x + dx, y + dy));
+                }
+            }
+        }
+
+        best
+    }
+
+    // Labels every cell with the basin its steepest descent path ends in and
+    // returns the labels together with the size of each basin. Cells without
+    // a lower neighbor are sinks; touching sinks of equal height form a single
+    // flat basin. Basins are numbered in row-major order of their sinks.
+    pub fn watershed(&self) -> (Grid<usize>, Vec<usize>) {
+        let mut labels = Grid::with_boundaries_of(self);
+        let mut sizes = vec![];
+        let bounds = self.bounds();
+
+        for (x, y) in bounds.iter() {
+            let Some(&value) = self.get(x, y) else {
+                continue;
+            };
+            if labels.get(x, y).is_some() || self.steepest_descent(x, y).is_some() {
+                continue;
+            }
+
+            let basin = sizes.len();
+            // NaN compares unequal to itself; touching NaN cells are one basin.
+            let same = |other: &f64| *other == value || (other.is_nan() && value.is_nan());
+            let sinks = self.flood_fill((x, y), same);
+            let mut size = 0;
+            for (x, y) in sinks {
+                if self.steepest_descent(x, y).is_none() {
+                    labels.set(x, y, basin);
+                    size += 1;
+                }
+            }
+            sizes.push(size);
+        }
+
+        for (x, y) in bounds.iter() {
+            if self.get(x, y).is_none() || labels.get(x, y).is_some() {
+                continue;
+            }
+
+            let mut path = vec![(x, y)];
+            let mut current = (x, y);
+            let basin = loop {
+                if let Some(&basin) = labels.get(current.0, current.1) {
+                    break basin;
+                }
+                current = self
+                    .steepest_descent(current.0, current.1)
+                    .expect("cells without a lower neighbor are labeled as sinks");
+                path.push(current);
+            };
+
+            for (x, y) in path {
+                if labels.get(x, y).is_none() {
+                    labels.set(x, y, basin);
+                    sizes[basin] += 1;
+                }
+            }
+        }
+
+        (labels, sizes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+
+    #[test]
+    fn two_valleys() {
+        let mut grid = Grid::new();
+        for y in 0..9 {
+            let height = ((y as f64) - 2.0).abs().min((y as f64 - 6.0).abs());
+            for x in 0..3 {
+                grid.set(x, y, height + x as f64 * 0.1);
+            }
+        }
+
+        let (labels, sizes) = grid.watershed();
+
+        assert_eq!(sizes.len(), 2);
+        assert_eq!(sizes.iter().sum::<usize>(), 27);
+        for x in 0..3 {
+            assert_eq!(labels.get(x, 0), Some(&0));
+            assert_eq!(labels.get(x, 3), Some(&0));
+            assert_eq!(labels.get(x, 5), Some(&1));
+            assert_eq!(labels.get(x, 8), Some(&1));
+        }
+    }
+
+    #[test]
+    fn flat_minimum_is_one_basin() {
+        let mut grid = Grid::new();
+        for x in 0..5 {
+            for y in 0..5 {
+                let flat = (1..=3).contains(&x) && (1..=3).contains(&y);
+                grid.set(x, y, if flat { 0.0 } else { 1.0 + y as f64 });
+            }
+        }
+
+        let (labels, sizes) = grid.watershed();
+        assert_eq!(sizes, vec![25]);
+        assert_eq!(labels.get(4, 4), Some(&0));
+
+        grid.set(0, 0, f64::NAN);
+        grid.set(0, 1, f64::NAN);
+        let (labels, sizes) = grid.watershed();
+        assert_eq!(sizes, vec![2, 23]);
+        assert_eq!(labels.get(0, 1), Some(&0));
+        assert_eq!(labels.get(4, 4), Some(&1));
+    }
+}
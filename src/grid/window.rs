@@ -0,0 +1,92 @@
+use super::Grid;
+use crate::rect::Rect;
+use crate::summed::SummedAreaTable;
+
+// What `window_aggregate` knows about one window. Empty cells add nothing
+// to the sum and are not counted.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Window {
+    pub rect: Rect,
+    pub sum: f64,
+    pub count: usize,
+}
+
+impl Window {
+    // The mean of the stored cells, NaN when there are none.
+    pub fn mean(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+}
+
+impl<T: Copy + Into<f64>> Grid<T> {
+    // Applies `aggregate` to every `width` x `height` window (columns by
+    // rows) that fits inside the bounds, storing each result at the
+    // window's top left cell. Every window costs the same thanks to a
+    // `SummedAreaTable`, so e.g. the densest k x k region is one scan over
+    // `window_aggregate(k, k, |window| window.sum)`.
+    pub fn window_aggregate<U, F>(&self, width: usize, height: usize, mut aggregate: F) -> Grid<U>
+    where
+        F: FnMut(&Window) -> U,
+    {
+        let mut result = Grid::new();
+        let bounds = self.bounds();
+        if width == 0 || height == 0 || width > bounds.width() || height > bounds.height() {
+            return result;
+        }
+
+        let table = SummedAreaTable::new(self);
+        let (dx, dy) = (height as isize - 1, width as isize - 1);
+        for (x, y) in Rect::new(
+            bounds.min_x,
+            bounds.min_y,
+            bounds.max_x - dx,
+            bounds.max_y - dy,
+        )
+        .iter()
+        {
+            let rect = Rect::new(x, y, x + dx, y + dy);
+            result.set(
+                x,
+                y,
+                aggregate(&Window {
+                    rect,
+                    sum: table.sum_rect(rect),
+                    count: table.count_rect(rect),
+                }),
+            );
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::rect::Rect;
+
+    #[test]
+    fn aggregates_windows() {
+        let mut grid = Grid::new();
+        for (x, y) in Rect::new(0, 0, 3, 4).iter() {
+            grid.set(x, y, (x * 5 + y) as i32);
+        }
+        grid.remove(0, 0);
+
+        let sums = grid.window_aggregate(2, 3, |window| window.sum);
+        assert_eq!(sums.bounds(), Rect::new(0, 0, 1, 3));
+        for ((x, y), &sum) in sums.iter() {
+            let expected: i32 = Rect::new(x, y, x + 2, y + 1)
+                .iter()
+                .filter_map(|(x, y)| grid.get(x, y))
+                .sum();
+            assert_eq!(sum, expected as f64);
+        }
+
+        let means = grid.window_aggregate(2, 2, |window| window.mean());
+        assert_eq!(means.get(0, 0), Some(&4.0));
+        assert_eq!(means.get(2, 3), Some(&((13 + 14 + 18 + 19) as f64 / 4.0)));
+        assert!(grid
+            .window_aggregate(6, 1, |window| window.count)
+            .is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+use crate::error::GridError;
+use crate::grid::{Grid, GridRead};
+use crate::rect::Rect;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::Hash;
+
+// A grid that also maps every stored value to the cells holding it, so
+// finding a value costs a lookup instead of a scan. The grid is only
+// reachable immutably; edits go through the wrapper to keep the index
+// current.
+#[derive(Clone)]
+pub struct IndexedGrid<T> {
+    grid: Grid<T>,
+    index: HashMap<T, BTreeSet<(isize, isize)>>,
+}
+
+impl<T: Eq + Hash + Clone> IndexedGrid<T> {
+    pub fn new() -> Self {
+        Self::from(Grid::new())
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    pub fn into_grid(self) -> Grid<T> {
+        self.grid
+    }
+
+    pub fn get(&self, x: isize, y: isize) -> Option<&T> {
+        self.grid.get(x, y)
+    }
+
+    pub fn set(&mut self, x: isize, y: isize, item: T) {
+        if let Err(error) = self.try_set(x, y, item) {
+            panic!("cannot set ({x}, {y}): {error}");
+        }
+    }
+
+    pub fn try_set(&mut self, x: isize, y: isize, item: T) -> Result<(), GridError> {
+        let (x, y) = self.grid.wrap(x, y);
+        let old = self.grid.get(x, y).cloned();
+        self.grid.try_set(x, y, item.clone())?;
+
+        if let Some(old) = old {
+            self.unindex(&old, (x, y));
+        }
+        if self.grid.default_value() != Some(&item) {
+            self.index.entry(item).or_default().insert((x, y));
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, x: isize, y: isize) -> Option<T> {
+        let (x, y) = self.grid.wrap(x, y);
+        let item = self.grid.remove(x, y)?;
+        self.unindex(&item, (x, y));
+        Some(item)
+    }
+
+    fn unindex(&mut self, item: &T, position: (isize, isize)) {
+        if let Some(positions) = self.index.get_mut(item) {
+            positions.remove(&position);
+            if positions.is_empty() {
+                self.index.remove(item);
+            }
+        }
+    }
+
+    // The cells holding `item`, in row-major order. A default value is not
+    // stored and so never found.
+    pub fn positions_of(&self, item: &T) -> impl Iterator<Item = (isize, isize)> + '_ {
+        self.index.get(item).into_iter().flatten().copied()
+    }
+
+    pub fn position_of(&self, item: &T) -> Option<(isize, isize)> {
+        self.positions_of(item).next()
+    }
+
+    pub fn count_of(&self, item: &T) -> usize {
+        self.index.get(item).map_or(0, BTreeSet::len)
+    }
+}
+
+impl<T: Eq + Hash + Clone> From<Grid<T>> for IndexedGrid<T> {
+    fn from(grid: Grid<T>) -> Self {
+        let mut index: HashMap<T, BTreeSet<(isize, isize)>> = HashMap::new();
+        for (position, item) in grid.iter() {
+            index.entry(item.clone()).or_default().insert(position);
+        }
+
+        Self { grid, index }
+    }
+}
+
+impl<T: Eq + Hash + Clone> Default for IndexedGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> GridRead<T> for IndexedGrid<T> {
+    fn get(&self, x: isize, y: isize) -> Option<&T> {
+        self.grid.get(x, y)
+    }
+
+    fn bounds(&self) -> Rect {
+        self.grid.bounds()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IndexedGrid;
+    use crate::grid::Grid;
+
+    #[test]
+    fn tracks_positions() {
+        let mut grid = Grid::new();
+        grid.set(3, 3, 42);
+        let mut entities = IndexedGrid::from(grid);
+
+        entities.set(-1, 0, 7);
+        entities.set(2, 5, 7);
+        assert_eq!(entities.position_of(&42), Some((3, 3)));
+        assert_eq!(
+            entities.positions_of(&7).collect::<Vec<_>>(),
+            [(-1, 0), (2, 5)]
+        );
+
+        entities.set(3, 3, 7);
+        assert_eq!(entities.position_of(&42), None);
+        assert_eq!(entities.count_of(&7), 3);
+
+        assert_eq!(entities.remove(-1, 0), Some(7));
+        assert_eq!(entities.position_of(&7), Some((2, 5)));
+        assert_eq!(entities.grid().len(), 2);
+    }
+}
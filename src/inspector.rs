@@ -0,0 +1,135 @@
+use crate::grid::GridRead;
+use egui::{Color32, Pos2, Rect as Area, ScrollArea, Sense, Ui, Vec2};
+use std::fmt::Debug;
+
+const MIN_CELL_SIZE: f32 = 2.0;
+const MAX_CELL_SIZE: f32 = 64.0;
+
+// A scrollable debug view of a grid. Rows (x) run down the screen, columns
+// (y) across it. Ctrl or pinch zooms while hovering, and the hovered cell
+// shows its coordinates and `Debug` output in a tooltip. Keep the inspector
+// between frames so the zoom level sticks.
+pub struct GridInspector {
+    cell_size: f32,
+}
+
+impl Default for GridInspector {
+    fn default() -> Self {
+        Self { cell_size: 12.0 }
+    }
+}
+
+impl GridInspector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    pub fn set_cell_size(&mut self, cell_size: f32) {
+        self.cell_size = cell_size.clamp(MIN_CELL_SIZE, MAX_CELL_SIZE);
+    }
+
+    // Paints only the cells inside the scrolled viewport and returns the
+    // hovered cell, if any.
+    pub fn show<T: Debug, R: GridRead<T> + ?Sized>(
+        &mut self,
+        ui: &mut Ui,
+        grid: &R,
+        color: impl Fn(&T) -> Color32,
+    ) -> Option<(isize, isize)> {
+        let bounds = grid.bounds();
+        let size = self.cell_size;
+        let content = Vec2::new(bounds.width() as f32, bounds.height() as f32) * size;
+        let gap = if size >= 8.0 { 1.0 } else { 0.0 };
+
+        ScrollArea::both()
+            .show_viewport(ui, |ui, viewport| {
+                let (response, painter) = ui.allocate_painter(content, Sense::hover());
+                let origin = response.rect.min;
+                let cell = |row: usize, column: usize| {
+                    Area::from_min_size(
+                        origin + Vec2::new(column as f32, row as f32) * size,
+                        Vec2::splat(size - gap),
+                    )
+                };
+
+                let rows = visible(viewport.min.y, viewport.max.y, size, bounds.height());
+                let columns = visible(viewport.min.x, viewport.max.x, size, bounds.width());
+                for row in rows {
+                    for column in columns.clone() {
+                        let (x, y) = (bounds.min_x + row as isize, bounds.min_y + column as isize);
+                        if let Some(item) = grid.get(x, y) {
+                            painter.rect_filled(cell(row, column), 0.0, color(item));
+                        }
+                    }
+                }
+
+                let hovered = response.hover_pos().and_then(|pointer: Pos2| {
+                    let offset = (pointer - origin) / size;
+                    let (row, column) = (offset.y as usize, offset.x as usize);
+                    (offset.min_elem() >= 0.0 && row < bounds.height() && column < bounds.width())
+                        .then(|| (bounds.min_x + row as isize, bounds.min_y + column as isize))
+                });
+
+                if response.hovered() {
+                    let zoom = ui.input(|input| input.zoom_delta());
+                    if zoom != 1.0 {
+                        self.set_cell_size(size * zoom);
+                    }
+                }
+
+                if let Some((x, y)) = hovered {
+                    let text = match grid.get(x, y) {
+                        Some(item) => format!("({x}, {y})\n{item:?}"),
+                        None => format!("({x}, {y})\nempty"),
+                    };
+                    response.on_hover_text_at_pointer(text);
+                }
+
+                hovered
+            })
+            .inner
+    }
+}
+
+// The cell indices overlapping the span `start..end` of the content.
+fn visible(start: f32, end: f32, size: f32, count: usize) -> std::ops::Range<usize> {
+    let first = (start / size).floor().max(0.0) as usize;
+    let last = ((end / size).ceil().max(0.0) as usize).min(count);
+    first.min(last)..last
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{visible, GridInspector};
+    use crate::grid::Grid;
+    use egui::{Color32, Context, RawInput};
+
+    #[test]
+    fn paints_without_a_pointer() {
+        let mut grid = Grid::new();
+        for y in -50..50 {
+            grid.set(y / 2, y, y);
+        }
+
+        let mut inspector = GridInspector::new();
+        let mut hovered = Some((0, 0));
+        let mut output = Context::default().run_ui(RawInput::default(), |ui| {
+            hovered = inspector.show(ui, &grid, |&value| Color32::from_gray(value as u8));
+        });
+
+        assert_eq!(hovered, None);
+        assert!(!output.shapes.is_empty());
+        output.textures_delta.clear();
+    }
+
+    #[test]
+    fn clamps_the_visible_range() {
+        assert_eq!(visible(-5.0, 30.0, 10.0, 100), 0..3);
+        assert_eq!(visible(95.0, 400.0, 10.0, 20), 9..20);
+        assert_eq!(visible(500.0, 600.0, 10.0, 20), 20..20);
+    }
+}
@@ -0,0 +1,83 @@
+use crate::grid::Grid;
+
+// Isometric projection for 2.5D rendering. Rows run down-left and columns
+// down-right on screen, and a cell's screen position is the center of its
+// diamond; with a 2:1 tile size this is the usual isometric pixel-art view,
+// other ratios give dimetric views.
+
+// The screen position of the center of cell (x, y), with (0, 0) at the
+// screen origin.
+pub fn to_iso(x: isize, y: isize, tile_width: f64, tile_height: f64) -> (f64, f64) {
+    (
+        (y as f64 - x as f64) * tile_width / 2.0,
+        (x as f64 + y as f64) * tile_height / 2.0,
+    )
+}
+
+// The cell whose diamond contains the screen position, e.g. for mouse
+// picking.
+pub fn from_iso(screen_x: f64, screen_y: f64, tile_width: f64, tile_height: f64) -> (isize, isize) {
+    let across = screen_x / (tile_width / 2.0);
+    let down = screen_y / (tile_height / 2.0);
+    (
+        ((down - across) / 2.0).round() as isize,
+        ((down + across) / 2.0).round() as isize,
+    )
+}
+
+impl<T> Grid<T> {
+    // The stored cells in painter's order for the isometric view: each
+    // diagonal of equal x + y from the top of the screen down, so nearer
+    // tiles are drawn over the ones behind them.
+    pub fn iter_back_to_front(&self) -> impl Iterator<Item = ((isize, isize), &T)> {
+        let mut cells: Vec<_> = self.iter().collect();
+        cells.sort_by_key(|&((x, y), _)| (x as i128 + y as i128, x));
+        cells.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_iso, to_iso};
+    use crate::grid::Grid;
+    use crate::rect::Rect;
+
+    #[test]
+    fn round_trips_screen_positions() {
+        assert_eq!(to_iso(0, 0, 64.0, 32.0), (0.0, 0.0));
+        assert_eq!(to_iso(1, 0, 64.0, 32.0), (-32.0, 16.0));
+        assert_eq!(to_iso(2, 3, 64.0, 32.0), (32.0, 80.0));
+
+        for (x, y) in [(0, 0), (-3, 7), (5, -2)] {
+            let (screen_x, screen_y) = to_iso(x, y, 64.0, 32.0);
+            assert_eq!(
+                from_iso(screen_x + 15.0, screen_y - 7.0, 64.0, 32.0),
+                (x, y)
+            );
+        }
+        assert_eq!(from_iso(0.0, 15.0, 64.0, 32.0), (0, 0));
+        assert_eq!(from_iso(0.0, 17.0, 64.0, 32.0), (1, 1));
+        assert_eq!(
+            to_iso(isize::MIN, isize::MIN, 2.0, 2.0),
+            (0.0, 2.0 * isize::MIN as f64)
+        );
+    }
+
+    #[test]
+    fn draws_far_cells_first() {
+        let mut grid = Grid::new();
+        for (x, y) in [(2, 0), (0, 1), (1, 1), (-1, 0)] {
+            grid.set(x, y, ());
+        }
+        let order: Vec<_> = grid.iter_back_to_front().map(|(cell, _)| cell).collect();
+        assert_eq!(order, [(-1, 0), (0, 1), (1, 1), (2, 0)]);
+
+        let mut defaulted = Grid::new_with_default(0);
+        defaulted.set(3, -3, 1);
+        defaulted.set(-2, 1, 2);
+        let order: Vec<_> = defaulted.iter_back_to_front().collect();
+        assert_eq!(order, [((-2, 1), &2), ((3, -3), &1)]);
+        let far = Rect::new(isize::MIN / 2 - 10, isize::MIN / 2 - 10, 0, 0);
+        assert_eq!(Grid::<()>::with_bounds(far).iter_back_to_front().count(), 0);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::grid::Grid;
+use crate::rect::Rect;
+
+// Several aligned grids sharing one coordinate system, such as terrain,
+// entities and decorations. Layers are named by a key, usually a small enum,
+// and keep the order they were added in, which is the order stacks and
+// combined iteration visit them. Layers differing in type can store an enum
+// of the per-layer types.
+#[derive(Clone)]
+pub struct LayeredGrid<K, T> {
+    layers: Vec<(K, Grid<T>)>,
+}
+
+impl<K: PartialEq, T> LayeredGrid<K, T> {
+    pub fn new() -> Self {
+        Self { layers: vec![] }
+    }
+
+    // The layer named `key`, added on top if it does not exist yet.
+    pub fn layer_or_insert(&mut self, key: K) -> &mut Grid<T> {
+        let index = match self.layers.iter().position(|(other, _)| *other == key) {
+            Some(index) => index,
+            None => {
+                self.layers.push((key, Grid::new()));
+                self.layers.len() - 1
+            }
+        };
+        &mut self.layers[index].1
+    }
+
+    pub fn layer(&self, key: &K) -> Option<&Grid<T>> {
+        self.layers
+            .iter()
+            .find_map(|(other, grid)| (other == key).then_some(grid))
+    }
+
+    pub fn layer_mut(&mut self, key: &K) -> Option<&mut Grid<T>> {
+        self.layers
+            .iter_mut()
+            .find_map(|(other, grid)| (other == key).then_some(grid))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.layers.iter().map(|(key, _)| key)
+    }
+
+    pub fn set(&mut self, key: K, x: isize, y: isize, item: T) {
+        self.layer_or_insert(key).set(x, y, item);
+    }
+
+    pub fn get(&self, key: &K, x: isize, y: isize) -> Option<&T> {
+        self.layer(key)?.get(x, y)
+    }
+
+    pub fn remove(&mut self, key: &K, x: isize, y: isize) -> Option<T> {
+        self.layer_mut(key)?.remove(x, y)
+    }
+
+    // The bounds covering every layer.
+    pub fn bounds(&self) -> Rect {
+        let mut bounds = self.layers.iter().map(|(_, grid)| grid.bounds());
+        let first = bounds.next().unwrap_or_else(|| Grid::<T>::new().bounds());
+        bounds.fold(first, |all, bounds| all.union(&bounds))
+    }
+
+    // The values at (x, y), bottom layer first.
+    pub fn stack(&self, x: isize, y: isize) -> impl Iterator<Item = (&K, &T)> {
+        self.layers
+            .iter()
+            .filter_map(move |(key, grid)| Some((key, grid.get(x, y)?)))
+    }
+
+    // Every stored value of every layer, in row-major order and, within a
+    // cell, bottom layer first.
+    pub fn iter(&self) -> impl Iterator<Item = ((isize, isize), &K, &T)> {
+        let mut layers: Vec<_> = self
+            .layers
+            .iter()
+            .map(|(key, grid)| (key, grid.iter().peekable()))
+            .collect();
+
+        std::iter::from_fn(move || {
+            let (key, cells) = layers
+                .iter_mut()
+                .filter_map(|(key, cells)| Some((cells.peek()?.0, key, cells)))
+                .min_by_key(|&(position, ..)| position)
+                .map(|(_, key, cells)| (*key, cells))?;
+            let (position, item) = cells.next()?;
+            Some((position, key, item))
+        })
+    }
+}
+
+impl<K: PartialEq, T> Default for LayeredGrid<K, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LayeredGrid;
+    use crate::rect::Rect;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Layer {
+        Terrain,
+        Items,
+    }
+
+    #[test]
+    fn stacks_aligned_layers() {
+        let mut map = LayeredGrid::new();
+        map.set(Layer::Terrain, 0, 0, '.');
+        map.set(Layer::Terrain, 0, 1, '#');
+        map.set(Layer::Items, 0, 0, '$');
+        map.set(Layer::Items, -2, 3, '!');
+
+        assert_eq!(map.bounds(), Rect::new(-2, 0, 0, 3));
+        assert_eq!(
+            map.keys().copied().collect::<Vec<_>>(),
+            [Layer::Terrain, Layer::Items]
+        );
+        assert_eq!(map.get(&Layer::Items, 0, 0), Some(&'$'));
+        assert_eq!(map.stack(0, 0).map(|(_, &c)| c).collect::<String>(), ".$");
+
+        let cells: Vec<_> = map.iter().map(|(position, _, &c)| (position, c)).collect();
+        assert_eq!(
+            cells,
+            [((-2, 3), '!'), ((0, 0), '.'), ((0, 0), '$'), ((0, 1), '#')]
+        );
+
+        assert_eq!(map.remove(&Layer::Terrain, 0, 1), Some('#'));
+        assert_eq!(map.layer(&Layer::Terrain).unwrap().len(), 1);
+    }
+}
@@ -1,3 +1,89 @@
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+#[cfg(feature = "bevy")]
+pub mod bevy;
+mod bitgrid;
+#[cfg(feature = "bumpalo")]
+mod bump;
+#[cfg(feature = "compact")]
+mod compact;
+mod connectivity;
+mod coordset;
+mod direction;
+mod error;
+#[cfg(feature = "ffi")]
+mod ffi;
+pub mod gen;
+mod graph;
 mod grid;
+mod indexed;
+#[cfg(feature = "egui")]
+mod inspector;
+pub mod iso;
+mod layered;
+pub mod life;
+mod metric;
+#[cfg(feature = "mmap")]
+mod mmap;
+pub mod packing;
+mod palette;
+pub mod path;
+#[cfg(feature = "serde")]
+mod payload;
+#[cfg(feature = "plotters")]
+mod plot;
+mod polyomino;
+#[cfg(feature = "raster")]
+pub mod raster;
+#[cfg(feature = "recorder")]
+mod recorder;
+mod rect;
+mod region;
+mod rle;
+mod scratch;
+pub mod sim;
+mod stamp;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+mod stream;
+mod summed;
+pub mod trigrid;
+mod visited;
 
-pub use grid::Grid as Vec2D;
+pub use bitgrid::BitGrid;
+#[cfg(feature = "bumpalo")]
+pub use bump::BumpGrid;
+pub use connectivity::Connectivity;
+pub use coordset::CoordSet;
+pub use direction::Direction;
+pub use error::GridError;
+pub use graph::GridGraph;
+pub use grid::{
+    convolve, count_neighbors, step, Annealing, Axis, Boundary, Cells, Clamped, Cursor, Delta,
+    Edge, Edit, Grid as Vec2D, GridBuilder, GridRead, GridView, GridWrite, InstructionError,
+    IntoIter, Iter, IterMut, MinCut, Mirrored, Mutation, NoiseParams, Pattern, Pushability,
+    RegionStats, Relaxation, Replacement, RewriteStrategy, Symmetry, Turtle, Violation,
+    VisibilityCache, Window, Wrapping,
+};
+pub use indexed::IndexedGrid;
+#[cfg(feature = "egui")]
+pub use inspector::GridInspector;
+pub use layered::LayeredGrid;
+pub use metric::Metric;
+#[cfg(feature = "mmap")]
+pub use mmap::MmapGrid;
+pub use palette::PaletteGrid;
+#[cfg(feature = "serde")]
+pub use payload::Payload;
+pub use polyomino::Polyomino;
+#[cfg(feature = "recorder")]
+pub use recorder::Recorder;
+pub use rect::Rect;
+pub use region::Region;
+pub use rle::RleGrid;
+pub use scratch::ScratchGrid;
+pub use stamp::Stamp;
+pub use stream::{Codec, LazyGrid};
+pub use summed::SummedAreaTable;
+pub use trigrid::TriGrid;
+pub use visited::VisitedGrid;
@@ -0,0 +1,319 @@
+use crate::bitgrid::BitGrid;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RleError {
+    MissingHeader,
+    InvalidHeader(String),
+    UnexpectedCharacter(char),
+    // A run count, or the position it leads to, does not fit in an isize.
+    RunTooLong,
+    // The header asks for more than `MAX_RLE_CELLS` cells.
+    BoardTooLarge,
+    OutOfBounds,
+}
+
+// Boards loaded from RLE are capped at 2^30 cells, 128 MiB per bit grid.
+pub const MAX_RLE_CELLS: usize = 1 << 30;
+
+impl fmt::Display for RleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RleError::MissingHeader => write!(f, "missing RLE header line"),
+            RleError::InvalidHeader(header) => write!(f, "invalid RLE header: {header}"),
+            RleError::UnexpectedCharacter(c) => write!(f, "unexpected character {c:?} in RLE data"),
+            RleError::RunTooLong => write!(f, "run count too large in RLE data"),
+            RleError::BoardTooLarge => {
+                write!(f, "RLE board larger than {MAX_RLE_CELLS} cells")
+            }
+            RleError::OutOfBounds => write!(f, "pattern does not fit on the board"),
+        }
+    }
+}
+
+impl Error for RleError {}
+
+struct Pattern {
+    width: usize,
+    height: usize,
+    alive: Vec<(isize, isize)>,
+}
+
+// RLE rows become board rows, so the header's `x` is the pattern width and
+// `y` its height.
+fn parse_rle(rle: &str) -> Result<Pattern, RleError> {
+    let mut lines = rle
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+    let header = lines.next().ok_or(RleError::MissingHeader)?;
+    let invalid = || RleError::InvalidHeader(header.to_string());
+
+    let mut size: (Option<usize>, Option<usize>) = (None, None);
+    for field in header.split(',') {
+        let (key, value) = field.split_once('=').ok_or_else(invalid)?;
+        match key.trim() {
+            "x" => size.0 = Some(value.trim().parse().map_err(|_| invalid())?),
+            "y" => size.1 = Some(value.trim().parse().map_err(|_| invalid())?),
+            _ => {}
+        }
+    }
+    let (Some(width), Some(height)) = size else {
+        return Err(invalid());
+    };
+    if width
+        .checked_mul(height)
+        .is_none_or(|cells| cells > MAX_RLE_CELLS)
+    {
+        return Err(RleError::BoardTooLarge);
+    }
+
+    let mut alive = vec![];
+    let (mut x, mut y) = (0, 0);
+    let mut count: Option<isize> = None;
+
+    'data: for line in lines {
+        for c in line.chars() {
+            if let Some(digit) = c.to_digit(10) {
+                count = count
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|count: isize| count.checked_add(digit as isize));
+                if count.is_none() {
+                    return Err(RleError::RunTooLong);
+                }
+                continue;
+            }
+
+            let run = count.take().unwrap_or(1);
+            let advance = |position: isize| position.checked_add(run).ok_or(RleError::RunTooLong);
+            match c {
+                'b' | '.' => y = advance(y)?,
+                'o' | 'A'..='Z' => {
+                    let end = advance(y)?;
+                    alive.extend((y..end).map(|y| (x, y)));
+                    y = end;
+                }
+                '$' => {
+                    x = advance(x)?;
+                    y = 0;
+                }
+                '!' => break 'data,
+                c if c.is_whitespace() => {}
+                c => return Err(RleError::UnexpectedCharacter(c)),
+            }
+        }
+    }
+
+    Ok(Pattern {
+        width,
+        height,
+        alive,
+    })
+}
+
+// Conway's Game of Life on a finite board whose outside is permanently dead.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    cells: BitGrid,
+    next: BitGrid,
+}
+
+impl Board {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            cells: BitGrid::new(width, height),
+            next: BitGrid::new(width, height),
+        }
+    }
+
+    pub fn from_rle(rle: &str) -> Result<Self, RleError> {
+        let pattern = parse_rle(rle)?;
+        let mut board = Board::new(pattern.width, pattern.height);
+        board.place(&pattern, 0, 0)?;
+        Ok(board)
+    }
+
+    // Places the pattern with its top left corner at (x, y).
+    pub fn load_rle(&mut self, rle: &str, x: isize, y: isize) -> Result<(), RleError> {
+        let pattern = parse_rle(rle)?;
+        self.place(&pattern, x, y)
+    }
+
+    fn place(&mut self, pattern: &Pattern, x: isize, y: isize) -> Result<(), RleError> {
+        let fits = |(dx, dy): (isize, isize)| {
+            let (Some(x), Some(y)) = (x.checked_add(dx), y.checked_add(dy)) else {
+                return false;
+            };
+            x >= 0 && y >= 0 && (x as usize) < self.height() && (y as usize) < self.width()
+        };
+
+        if !pattern.alive.iter().all(|cell| fits(*cell)) {
+            return Err(RleError::OutOfBounds);
+        }
+
+        for &(dx, dy) in &pattern.alive {
+            self.cells.set(x + dx, y + dy, true);
+        }
+
+        Ok(())
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.height()
+    }
+
+    pub fn cells(&self) -> &BitGrid {
+        &self.cells
+    }
+
+    pub fn get(&self, x: isize, y: isize) -> bool {
+        self.cells.get(x, y)
+    }
+
+    pub fn set(&mut self, x: isize, y: isize, alive: bool) {
+        self.cells.set(x, y, alive);
+    }
+
+    pub fn population(&self) -> usize {
+        self.cells.count_ones()
+    }
+
+    // Updates 64 cells at a time: the eight neighbor bit vectors of a word are
+    // summed with bitwise adders and the rule is applied to the bit counters.
+    pub fn step(&mut self) {
+        let words = self.cells.words_per_row();
+        let height = self.height();
+        let empty = vec![0; words];
+
+        for x in 0..height {
+            let above = if x > 0 { self.cells.row(x - 1) } else { &empty };
+            let current = self.cells.row(x);
+            let below = if x + 1 < height {
+                self.cells.row(x + 1)
+            } else {
+                &empty
+            };
+            let out = self.next.row_mut(x);
+
+            for w in 0..words {
+                let west = |row: &[u64]| row[w] << 1 | if w > 0 { row[w - 1] >> 63 } else { 0 };
+                let east =
+                    |row: &[u64]| row[w] >> 1 | if w + 1 < words { row[w + 1] << 63 } else { 0 };
+
+                let neighbors = [
+                    west(above),
+                    above[w],
+                    east(above),
+                    west(current),
+                    east(current),
+                    west(below),
+                    below[w],
+                    east(below),
+                ];
+
+                let (mut ones, mut twos, mut fours) = (0u64, 0u64, 0u64);
+                for neighbor in neighbors {
+                    let carry = ones & neighbor;
+                    ones ^= neighbor;
+                    fours |= twos & carry;
+                    twos ^= carry;
+                }
+
+                // Exactly two or three neighbors (no bit at four or above),
+                // where two only keeps a live cell alive.
+                out[w] = !fours & twos & (ones | current[w]);
+            }
+        }
+
+        self.next.mask_padding();
+        std::mem::swap(&mut self.cells, &mut self.next);
+    }
+
+    pub fn step_n(&mut self, n: usize) {
+        for _ in 0..n {
+            self.step();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Board, RleError};
+    use crate::rect::Rect;
+
+    const GLIDER: &str = "#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!";
+
+    #[test]
+    fn rle_loading() {
+        let board = Board::from_rle(GLIDER).unwrap();
+
+        assert_eq!((board.width(), board.height()), (3, 3));
+        assert_eq!(board.population(), 5);
+        assert!(board.get(0, 1) && board.get(1, 2) && board.get(2, 0));
+        assert!(!board.get(0, 0));
+
+        let mut small = Board::new(4, 4);
+        assert_eq!(small.load_rle(GLIDER, 2, 2), Err(RleError::OutOfBounds));
+        assert_eq!(
+            Board::from_rle("bo!").unwrap_err(),
+            RleError::InvalidHeader("bo!".into())
+        );
+        assert_eq!(
+            Board::from_rle("x = 12, y = 1\n12o!").unwrap().population(),
+            12
+        );
+        for rle in [
+            "x = 3, y = 1\n99999999999999999999o!",
+            "x = 3, y = 1\n9223372036854775807b2o!",
+        ] {
+            assert_eq!(Board::from_rle(rle), Err(RleError::RunTooLong));
+        }
+        for rle in [
+            "x = 18446744073709551615, y = 2\no!",
+            "x = 65536, y = 65536\no!",
+        ] {
+            assert_eq!(Board::from_rle(rle), Err(RleError::BoardTooLarge));
+        }
+    }
+
+    #[test]
+    fn glider_travels() {
+        let mut board = Board::new(10, 10);
+        board.load_rle(GLIDER, 1, 1).unwrap();
+
+        board.step_n(4);
+
+        let alive: Vec<(isize, isize)> = Rect::new(0, 0, 9, 9)
+            .iter()
+            .filter(|&(x, y)| board.get(x, y))
+            .collect();
+        assert_eq!(alive, vec![(2, 3), (3, 4), (4, 2), (4, 3), (4, 4)]);
+    }
+
+    #[test]
+    fn matches_naive_rule_across_word_boundaries() {
+        let mut board = Board::new(150, 6);
+        for (x, y) in Rect::new(0, 0, 5, 149).iter() {
+            board.set(x, y, (x * 7 + y * 13) % 5 < 2);
+        }
+
+        let before = board.clone();
+        board.step();
+
+        for (x, y) in Rect::new(0, 0, 5, 149).iter() {
+            let neighbors = Rect::new(x - 1, y - 1, x + 1, y + 1)
+                .iter()
+                .filter(|&(nx, ny)| (nx, ny) != (x, y) && before.get(nx, ny))
+                .count();
+            let expected = neighbors == 3 || (neighbors == 2 && before.get(x, y));
+            assert_eq!(board.get(x, y), expected, "cell ({x}, {y})");
+        }
+    }
+}
@@ -0,0 +1,32 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Metric {
+    // 4-connected steps.
+    Manhattan,
+    // 8-connected steps, diagonals costing the same as orthogonal moves.
+    Chebyshev,
+    Euclidean,
+}
+
+impl Metric {
+    pub fn distance(self, (ax, ay): (isize, isize), (bx, by): (isize, isize)) -> f64 {
+        let (dx, dy) = (ax.abs_diff(bx) as f64, ay.abs_diff(by) as f64);
+
+        match self {
+            Metric::Manhattan => dx + dy,
+            Metric::Chebyshev => dx.max(dy),
+            Metric::Euclidean => dx.hypot(dy),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Metric;
+
+    #[test]
+    fn distances() {
+        assert_eq!(Metric::Manhattan.distance((0, 0), (-3, 4)), 7.0);
+        assert_eq!(Metric::Chebyshev.distance((0, 0), (-3, 4)), 4.0);
+        assert_eq!(Metric::Euclidean.distance((0, 0), (-3, 4)), 5.0);
+    }
+}
@@ -0,0 +1,172 @@
+use crate::grid::{Grid, GridRead};
+use crate::rect::Rect;
+use bytemuck::Pod;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{self, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+
+// Dense file layout, all integers little-endian: b"PHGD", version u8, three
+// zero bytes, cell size u32, bounds as four i64, zeros up to 64 bytes, then
+// every cell of the bounds in row-major order. The 64 byte header keeps the
+// cells aligned in the page aligned mapping.
+const MAGIC: &[u8; 4] = b"PHGD";
+const VERSION: u8 = 1;
+const HEADER: usize = 64;
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+impl<T: Pod> Grid<T> {
+    // Writes every cell of the bounds, empty ones as `fill`.
+    pub fn write_dense(&self, mut writer: impl Write, fill: T) -> io::Result<()> {
+        let bounds = self.bounds();
+        let mut header = [0; HEADER];
+        header[..4].copy_from_slice(MAGIC);
+        header[4] = VERSION;
+        header[8..12].copy_from_slice(&(size_of::<T>() as u32).to_le_bytes());
+        for (i, value) in [bounds.min_x, bounds.min_y, bounds.max_x, bounds.max_y]
+            .into_iter()
+            .enumerate()
+        {
+            header[12 + i * 8..20 + i * 8].copy_from_slice(&(value as i64).to_le_bytes());
+        }
+        writer.write_all(&header)?;
+
+        let mut row = Vec::with_capacity(bounds.width());
+        for x in bounds.min_x..=bounds.max_x {
+            row.clear();
+            row.extend((bounds.min_y..=bounds.max_y).map(|y| *self.get(x, y).unwrap_or(&fill)));
+            writer.write_all(bytemuck::cast_slice(&row))?;
+        }
+
+        writer.flush()
+    }
+}
+
+// A read-only grid over a file written with `write_dense`, served straight
+// from the memory mapping without copying. Every cell of the bounds holds a
+// value.
+pub struct MmapGrid<T> {
+    map: Mmap,
+    bounds: Rect,
+    cells: PhantomData<T>,
+}
+
+impl<T: Pod> MmapGrid<T> {
+    // The file must not be changed while it is mapped; the mapping would
+    // change under the references handed out.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let map = unsafe { Mmap::map(&file)? };
+
+        if map.len() < HEADER || &map[..4] != MAGIC {
+            return Err(invalid("not a dense grid file"));
+        }
+        if map[4] != VERSION {
+            return Err(invalid("unsupported dense grid version"));
+        }
+        if u32::from_le_bytes(map[8..12].try_into().unwrap()) as usize != size_of::<T>() {
+            return Err(invalid("cell size does not match the cell type"));
+        }
+
+        let coordinate = |i: usize| {
+            let value = i64::from_le_bytes(map[12 + i * 8..20 + i * 8].try_into().unwrap());
+            isize::try_from(value).map_err(|_| invalid("coordinate out of range"))
+        };
+        let bounds = Rect::new(
+            coordinate(0)?,
+            coordinate(1)?,
+            coordinate(2)?,
+            coordinate(3)?,
+        );
+
+        let cells = bounds.area().checked_mul(size_of::<T>());
+        if cells.and_then(|cells| cells.checked_add(HEADER)) != Some(map.len()) {
+            return Err(invalid("file size does not match the bounds"));
+        }
+
+        Ok(Self {
+            map,
+            bounds,
+            cells: PhantomData,
+        })
+    }
+
+    fn cells(&self) -> &[T] {
+        bytemuck::cast_slice(&self.map[HEADER..])
+    }
+
+    pub fn row(&self, x: isize) -> Option<&[T]> {
+        if x < self.bounds.min_x || x > self.bounds.max_x {
+            return None;
+        }
+
+        let width = self.bounds.width();
+        let start = x.abs_diff(self.bounds.min_x) * width;
+        Some(&self.cells()[start..start + width])
+    }
+
+    // The cells of `rect` inside the bounds, in row-major order.
+    pub fn iter_rect(&self, rect: Rect) -> impl Iterator<Item = ((isize, isize), &T)> {
+        let region = rect.intersection(&self.bounds);
+        region.into_iter().flat_map(move |region| {
+            (region.min_x..=region.max_x).flat_map(move |x| {
+                let row = self.row(x).unwrap();
+                let start = region.min_y.abs_diff(self.bounds.min_y);
+                (region.min_y..=region.max_y)
+                    .zip(&row[start..start + region.width()])
+                    .map(move |(y, cell)| ((x, y), cell))
+            })
+        })
+    }
+}
+
+impl<T: Pod> GridRead<T> for MmapGrid<T> {
+    fn get(&self, x: isize, y: isize) -> Option<&T> {
+        if !self.bounds.contains(x, y) {
+            return None;
+        }
+        self.row(x)?.get(y.abs_diff(self.bounds.min_y))
+    }
+
+    fn bounds(&self) -> Rect {
+        self.bounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MmapGrid;
+    use crate::grid::{Grid, GridRead};
+    use crate::rect::Rect;
+    use std::fs::{self, File};
+
+    #[test]
+    fn maps_dense_files() {
+        let mut grid = Grid::new();
+        for (x, y) in Rect::new(-3, 2, 4, 9).iter() {
+            grid.set(x, y, (x * 100 + y) as f32);
+        }
+        grid.set(10, 10, 1.5);
+
+        let path = std::env::temp_dir().join(format!("phoony-mmap-{}.grid", std::process::id()));
+        grid.write_dense(File::create(&path).unwrap(), f32::NAN)
+            .unwrap();
+
+        let mapped = MmapGrid::<f32>::open(&path).unwrap();
+        assert_eq!(mapped.bounds(), grid.bounds());
+        assert_eq!(mapped.get(-3, 2), Some(&-298.0));
+        assert_eq!(mapped.get(10, 10), Some(&1.5));
+        assert!(mapped.get(0, 0).unwrap().is_nan());
+        assert_eq!(mapped.get(11, 0), None);
+
+        let cells: Vec<_> = mapped.iter_rect(Rect::new(-10, 8, -3, 9)).collect();
+        assert_eq!(cells, [((-3, 8), &-292.0), ((-3, 9), &-291.0)]);
+        assert!(MmapGrid::<u8>::open(&path).is_err());
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,308 @@
+use crate::coordset::CoordSet;
+use crate::grid::Symmetry;
+use crate::stamp::Stamp;
+use std::collections::{HashMap, HashSet};
+
+// Placing a set of pieces, such as polyominoes, onto the cells of a board
+// without overlap, by backtracking over bitmasks of the covered cells. Only
+// the stored cells of a piece's stamp grid count as its shape.
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PackOptions {
+    // Also try every rotation and reflection of the pieces.
+    pub orientations: bool,
+    // Only accept solutions covering the whole board (exact cover).
+    pub exact: bool,
+}
+
+// Where one piece goes: its stamp transformed by `symmetry` with the anchor
+// on `at`, as in `apply_stamp(&pieces[piece].transformed(symmetry), at, ..)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Placement {
+    pub piece: usize,
+    pub symmetry: Symmetry,
+    pub at: (isize, isize),
+}
+
+struct Candidate {
+    placement: Placement,
+    mask: Vec<u64>,
+}
+
+// A search level: the candidates tried for one piece, or for the first
+// uncovered cell when packing exactly.
+struct Frame {
+    candidates: Vec<usize>,
+    next: usize,
+    applied: Option<usize>,
+}
+
+// The solutions of `pack`, found one at a time. Each lists one placement per
+// piece, in piece order unless packing exactly.
+pub struct Packings {
+    options: PackOptions,
+    pieces: usize,
+    cells: usize,
+    candidates: Vec<Candidate>,
+    by_piece: Vec<Vec<usize>>,
+    // For exact packing, the candidates whose first cell is each cell.
+    by_first_cell: Vec<Vec<usize>>,
+    covered: Vec<u64>,
+    used: Vec<bool>,
+    stack: Vec<Frame>,
+    started: bool,
+}
+
+// Every way to place each of `pieces` once onto the cells of `board`.
+pub fn pack<T>(board: &CoordSet, pieces: &[Stamp<T>], options: PackOptions) -> Packings {
+    let cells: Vec<(isize, isize)> = board.iter().collect();
+    let index: HashMap<(isize, isize), usize> = cells
+        .iter()
+        .enumerate()
+        .map(|(bit, &cell)| (cell, bit))
+        .collect();
+    let words = cells.len().div_ceil(64);
+    let symmetries: &[Symmetry] = if options.orientations {
+        &Symmetry::ALL
+    } else {
+        &[Symmetry::Identity]
+    };
+
+    let mut packings = Packings {
+        options,
+        pieces: pieces.len(),
+        cells: cells.len(),
+        candidates: vec![],
+        by_piece: vec![vec![]; pieces.len()],
+        by_first_cell: vec![vec![]; cells.len()],
+        covered: vec![0; words],
+        used: vec![false; pieces.len()],
+        stack: vec![],
+        started: false,
+    };
+
+    let mut area = 0;
+    for (piece, stamp) in pieces.iter().enumerate() {
+        let (ax, ay) = stamp.anchor();
+        let shape: Vec<(isize, isize)> = stamp
+            .grid()
+            .iter()
+            .map(|((x, y), _)| (x - ax, y - ay))
+            .collect();
+        area += shape.len();
+        let mut seen = HashSet::new();
+
+        for &symmetry in symmetries {
+            let oriented: Vec<(isize, isize)> =
+                shape.iter().map(|&cell| symmetry.apply(cell)).collect();
+            let Some(&first) = oriented.iter().min() else {
+                continue;
+            };
+            for &(bx, by) in &cells {
+                let at = (bx - first.0, by - first.1);
+                let bits: Option<Vec<usize>> = oriented
+                    .iter()
+                    .map(|&(x, y)| index.get(&(x + at.0, y + at.1)).copied())
+                    .collect();
+                let Some(bits) = bits else {
+                    continue;
+                };
+                let mut mask = vec![0; words];
+                for bit in bits {
+                    mask[bit / 64] |= 1 << (bit % 64);
+                }
+                if !seen.insert(mask.clone()) {
+                    continue;
+                }
+
+                let id = packings.candidates.len();
+                packings.by_piece[piece].push(id);
+                packings.by_first_cell[index[&(bx, by)]].push(id);
+                packings.candidates.push(Candidate {
+                    placement: Placement {
+                        piece,
+                        symmetry,
+                        at,
+                    },
+                    mask,
+                });
+            }
+        }
+    }
+
+    if options.exact && area != cells.len() {
+        packings.started = true;
+    }
+    packings
+}
+
+impl Packings {
+    fn fits(&self, candidate: usize) -> bool {
+        let candidate = &self.candidates[candidate];
+        !self.used[candidate.placement.piece]
+            && candidate
+                .mask
+                .iter()
+                .zip(&self.covered)
+                .all(|(mask, covered)| mask & covered == 0)
+    }
+
+    fn toggle(&mut self, candidate: usize) {
+        let candidate = &self.candidates[candidate];
+        let piece = candidate.placement.piece;
+        self.used[piece] = !self.used[piece];
+        for (covered, mask) in self.covered.iter_mut().zip(&candidate.mask) {
+            *covered ^= mask;
+        }
+    }
+
+    fn first_uncovered(&self) -> Option<usize> {
+        let (word, covered) = self
+            .covered
+            .iter()
+            .enumerate()
+            .find(|(_, &covered)| covered != u64::MAX)?;
+        let bit = word * 64 + covered.trailing_ones() as usize;
+        (bit < self.cells).then_some(bit)
+    }
+
+    fn frame(&self) -> Frame {
+        let options: &[usize] = if self.options.exact {
+            self.first_uncovered()
+                .map_or(&[], |cell| self.by_first_cell[cell].as_slice())
+        } else {
+            self.by_piece
+                .get(self.stack.len())
+                .map_or(&[], Vec::as_slice)
+        };
+        Frame {
+            candidates: options
+                .iter()
+                .copied()
+                .filter(|&id| self.fits(id))
+                .collect(),
+            next: 0,
+            applied: None,
+        }
+    }
+
+    fn solution(&self) -> Vec<Placement> {
+        self.stack
+            .iter()
+            .filter_map(|frame| frame.applied)
+            .map(|id| self.candidates[id].placement)
+            .collect()
+    }
+}
+
+impl Iterator for Packings {
+    type Item = Vec<Placement>;
+
+    fn next(&mut self) -> Option<Vec<Placement>> {
+        if !self.started {
+            self.started = true;
+            if self.pieces == 0 {
+                return Some(vec![]);
+            }
+            let frame = self.frame();
+            self.stack.push(frame);
+        }
+
+        while let Some(frame) = self.stack.last_mut() {
+            if let Some(applied) = frame.applied.take() {
+                self.toggle(applied);
+                continue;
+            }
+            let Some(&candidate) = frame.candidates.get(frame.next) else {
+                self.stack.pop();
+                continue;
+            };
+            frame.next += 1;
+            frame.applied = Some(candidate);
+            self.toggle(candidate);
+
+            if self.stack.len() == self.pieces {
+                return Some(self.solution());
+            }
+            let frame = self.frame();
+            self.stack.push(frame);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pack, PackOptions};
+    use crate::coordset::CoordSet;
+    use crate::grid::Grid;
+    use crate::rect::Rect;
+    use crate::stamp::Stamp;
+
+    fn piece(cells: &[(isize, isize)]) -> Stamp<char> {
+        let mut grid = Grid::new();
+        for &(x, y) in cells {
+            grid.set(x, y, '#');
+        }
+        Stamp::new(grid, cells[0])
+    }
+
+    fn board(rect: Rect) -> CoordSet {
+        rect.iter().collect()
+    }
+
+    #[test]
+    fn tiles_boards_exactly() {
+        let dominoes = vec![piece(&[(0, 0), (0, 1)]); 3];
+        let exact = PackOptions {
+            orientations: true,
+            exact: true,
+        };
+        // Three tilings of a 2x3 board, times the orders of the dominoes.
+        assert_eq!(
+            pack(&board(Rect::new(0, 0, 1, 2)), &dominoes, exact).count(),
+            18
+        );
+
+        let pieces = [piece(&[(0, 0), (1, 0), (1, 1)]), piece(&[(0, 0)])];
+        let solutions: Vec<_> = pack(&board(Rect::new(0, 0, 1, 1)), &pieces, exact).collect();
+        assert_eq!(solutions.len(), 4);
+
+        let mut grid = Grid::new();
+        for placement in &solutions[0] {
+            let stamp = pieces[placement.piece].transformed(placement.symmetry);
+            grid.apply_stamp(&stamp, placement.at, |target, &cell| {
+                assert!(target.is_none());
+                cell
+            });
+        }
+        assert_eq!(grid.len(), 4);
+        assert_eq!(
+            pack(&board(Rect::new(0, 0, 0, 2)), &pieces, exact).next(),
+            None
+        );
+    }
+
+    #[test]
+    fn packs_without_overlap() {
+        let mut cells = board(Rect::new(0, 0, 0, 2));
+        let monominoes = [piece(&[(0, 0)]), piece(&[(0, 0)])];
+        assert_eq!(pack(&cells, &monominoes, PackOptions::default()).count(), 6);
+
+        // A vertical domino only fits once the board has a second row.
+        let upright = [piece(&[(0, 0), (1, 0)])];
+        assert_eq!(pack(&cells, &upright, PackOptions::default()).count(), 0);
+        cells.insert(1, 2);
+        let first = pack(&cells, &upright, PackOptions::default())
+            .next()
+            .unwrap();
+        assert_eq!(first[0].at, (0, 2));
+        assert!(pack(
+            &CoordSet::new(),
+            &[] as &[Stamp<char>],
+            PackOptions::default()
+        )
+        .next()
+        .is_some());
+    }
+}
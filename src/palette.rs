@@ -0,0 +1,152 @@
+use crate::grid::Grid;
+use crate::rect::Rect;
+
+// A grid storing a small index per cell into a palette of values, which
+// takes far less memory than storing the values when only a few distinct
+// ones occur, as in tilemaps. Ids without a palette entry read as empty.
+#[derive(Clone)]
+pub struct PaletteGrid<V> {
+    ids: Grid<u16>,
+    palette: Vec<V>,
+}
+
+impl<V: PartialEq> PaletteGrid<V> {
+    pub fn new() -> Self {
+        Self {
+            ids: Grid::new(),
+            palette: vec![],
+        }
+    }
+
+    // Replaces the palette; cells keep their ids and so show the new values.
+    pub fn set_palette(&mut self, palette: Vec<V>) {
+        assert!(
+            palette.len() <= usize::from(u16::MAX) + 1,
+            "palettes hold at most 65536 values"
+        );
+        self.palette = palette;
+    }
+
+    pub fn palette(&self) -> &[V] {
+        &self.palette
+    }
+
+    // The id of `value`, added to the palette if it is not in it yet.
+    pub fn register(&mut self, value: V) -> u16 {
+        if let Some(id) = self.id_of(&value) {
+            return id;
+        }
+
+        let id = u16::try_from(self.palette.len()).expect("palettes hold at most 65536 values");
+        self.palette.push(value);
+        id
+    }
+
+    pub fn id_of(&self, value: &V) -> Option<u16> {
+        let id = self.palette.iter().position(|other| other == value)?;
+        Some(id as u16)
+    }
+
+    pub fn set_id(&mut self, x: isize, y: isize, id: u16) {
+        self.ids.set(x, y, id);
+    }
+
+    pub fn get_id(&self, x: isize, y: isize) -> Option<u16> {
+        self.ids.get(x, y).copied()
+    }
+
+    pub fn set_value(&mut self, x: isize, y: isize, value: V) {
+        let id = self.register(value);
+        self.ids.set(x, y, id);
+    }
+
+    pub fn get_value(&self, x: isize, y: isize) -> Option<&V> {
+        self.palette.get(usize::from(self.get_id(x, y)?))
+    }
+
+    pub fn remove(&mut self, x: isize, y: isize) -> Option<u16> {
+        self.ids.remove(x, y)
+    }
+
+    pub fn ids(&self) -> &Grid<u16> {
+        &self.ids
+    }
+
+    pub fn bounds(&self) -> Rect {
+        self.ids.bounds()
+    }
+}
+
+impl<V: PartialEq + Clone> PaletteGrid<V> {
+    pub fn to_grid(&self) -> Grid<V> {
+        let mut grid = Grid::with_boundaries_of(&self.ids);
+        for ((x, y), &id) in self.ids.iter() {
+            if let Some(value) = self.palette.get(usize::from(id)) {
+                grid.set(x, y, value.clone());
+            }
+        }
+        grid
+    }
+}
+
+impl<V: PartialEq + Clone> From<&Grid<V>> for PaletteGrid<V> {
+    fn from(grid: &Grid<V>) -> Self {
+        let mut palette = PaletteGrid::new();
+        palette.ids = Grid::with_boundaries_of(grid);
+        for ((x, y), value) in grid.iter() {
+            palette.set_value(x, y, value.clone());
+        }
+        palette
+    }
+}
+
+impl<V: PartialEq> Default for PaletteGrid<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PaletteGrid;
+    use crate::grid::Grid;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Tile {
+        Grass,
+        Water { depth: u32 },
+    }
+
+    #[test]
+    fn stores_ids_into_the_palette() {
+        let mut map = PaletteGrid::new();
+        map.set_value(0, 0, Tile::Grass);
+        map.set_value(0, 1, Tile::Water { depth: 3 });
+        map.set_value(-4, 2, Tile::Grass);
+
+        assert_eq!(map.palette().len(), 2);
+        assert_eq!(map.get_id(-4, 2), Some(0));
+        assert_eq!(map.get_value(0, 1), Some(&Tile::Water { depth: 3 }));
+
+        map.set_palette(vec![Tile::Water { depth: 1 }]);
+        assert_eq!(map.get_value(0, 0), Some(&Tile::Water { depth: 1 }));
+        assert_eq!(map.get_value(0, 1), None);
+
+        map.set_id(0, 1, 0);
+        let grid = map.to_grid();
+        assert_eq!(grid.len(), 3);
+        assert_eq!(PaletteGrid::from(&grid).palette().len(), 1);
+    }
+
+    #[test]
+    fn uses_less_memory_than_values() {
+        let mut grid = Grid::new();
+        for y in 0..1000 {
+            grid.set(0, y, [y as u64 % 3; 4]);
+        }
+
+        let palette = PaletteGrid::from(&grid);
+        assert!(palette.ids().memory_usage() * 4 < grid.memory_usage());
+        assert_eq!(palette.to_grid().get(0, 5), Some(&[2; 4]));
+    }
+}
@@ -0,0 +1,235 @@
+use super::{is_open, neighbors, octile, reconstruct, Node};
+use crate::grid::GridRead;
+use crate::visited::VisitedGrid;
+use std::collections::{BinaryHeap, HashMap};
+
+// Returns the cells of a shortest path from `start` to `goal`, both included.
+pub fn astar<T, R, F>(
+    grid: &R,
+    start: (isize, isize),
+    goal: (isize, isize),
+    passable: F,
+) -> Option<Vec<(isize, isize)>>
+where
+    R: GridRead<T> + ?Sized,
+    F: Fn(&T) -> bool,
+{
+    shortest_path(&|position| is_open(grid, &passable, position), start, goal)
+}
+
+pub(super) fn shortest_path<O>(
+    open: &O,
+    start: (isize, isize),
+    goal: (isize, isize),
+) -> Option<Vec<(isize, isize)>>
+where
+    O: Fn((isize, isize)) -> bool,
+{
+    weighted_path(open, start, goal, |_, _, length| length, 1.0)
+}
+
+// A* where moving between two neighbors costs `step_cost(from, to, length)`.
+// The heuristic is the octile distance times `scale`, which must not exceed
+// the cheapest cost per unit of length for paths to stay shortest.
+pub(super) fn weighted_path<O, C>(
+    open: &O,
+    start: (isize, isize),
+    goal: (isize, isize),
+    step_cost: C,
+    scale: f64,
+) -> Option<Vec<(isize, isize)>>
+where
+    O: Fn((isize, isize)) -> bool,
+    C: Fn((isize, isize), (isize, isize), f64) -> f64,
+{
+    if !open(start) || !open(goal) {
+        return None;
+    }
+
+    let successors = |position, next: &mut Vec<_>| {
+        next.extend(
+            neighbors(open, position).map(|(to, length)| (to, step_cost(position, to, length))),
+        );
+    };
+    search_path(successors, start, goal, |position| {
+        octile(position, goal) * scale
+    })
+}
+
+// The A* loop over any step model: `successors` appends the cells reachable
+// from a cell with the cost of each step, and `heuristic` must never
+// overestimate the remaining cost.
+pub(super) fn search_path<S, H>(
+    successors: S,
+    start: (isize, isize),
+    goal: (isize, isize),
+    heuristic: H,
+) -> Option<Vec<(isize, isize)>>
+where
+    S: Fn((isize, isize), &mut Vec<((isize, isize), f64)>),
+    H: Fn((isize, isize)) -> f64,
+{
+    let mut heap = BinaryHeap::new();
+    let mut closed = VisitedGrid::new();
+    let mut costs = HashMap::new();
+    let mut parents = HashMap::new();
+    let mut next = vec![];
+
+    costs.insert(start, 0.0);
+    heap.push(Node {
+        priority: heuristic(start),
+        position: start,
+    });
+
+    while let Some(Node { position, .. }) = heap.pop() {
+        if position == goal {
+            return Some(reconstruct(&parents, goal));
+        }
+        if !closed.insert(position.0, position.1) {
+            continue;
+        }
+
+        let cost = costs[&position];
+        next.clear();
+        successors(position, &mut next);
+        for &(to, step) in &next {
+            let next_cost = cost + step;
+            if costs.get(&to).is_none_or(|&known| next_cost < known) {
+                costs.insert(to, next_cost);
+                parents.insert(to, position);
+                heap.push(Node {
+                    priority: next_cost + heuristic(to),
+                    position: to,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+pub(super) struct Search {
+    pub costs: HashMap<(isize, isize), f64>,
+    pub parents: HashMap<(isize, isize), (isize, isize)>,
+    pub reached: Option<(isize, isize)>,
+}
+
+// Dijkstra from all of `starts` at once, stopping at the first settled cell
+// for which `stop` holds.
+pub(super) fn dijkstra<O, S>(open: &O, starts: &[(isize, isize)], stop: S) -> Search
+where
+    O: Fn((isize, isize)) -> bool,
+    S: FnMut((isize, isize)) -> bool,
+{
+    let starts: Vec<(isize, isize)> = starts
+        .iter()
+        .copied()
+        .filter(|&start| open(start))
+        .collect();
+    let successors = |position, next: &mut Vec<_>| next.extend(neighbors(open, position));
+    search_all(successors, &starts, stop)
+}
+
+// Dijkstra over any step model, see `search_path`.
+pub(super) fn search_all<N, S>(successors: N, starts: &[(isize, isize)], mut stop: S) -> Search
+where
+    N: Fn((isize, isize), &mut Vec<((isize, isize), f64)>),
+    S: FnMut((isize, isize)) -> bool,
+{
+    let mut heap = BinaryHeap::new();
+    let mut closed = VisitedGrid::new();
+    let mut search = Search {
+        costs: HashMap::new(),
+        parents: HashMap::new(),
+        reached: None,
+    };
+    let mut next = vec![];
+
+    for &start in starts {
+        search.costs.insert(start, 0.0);
+        heap.push(Node {
+            priority: 0.0,
+            position: start,
+        });
+    }
+
+    while let Some(Node { priority, position }) = heap.pop() {
+        if !closed.insert(position.0, position.1) {
+            continue;
+        }
+        if stop(position) {
+            search.reached = Some(position);
+            break;
+        }
+
+        next.clear();
+        successors(position, &mut next);
+        for &(to, step) in &next {
+            let next_cost = priority + step;
+            if search.costs.get(&to).is_none_or(|&known| next_cost < known) {
+                search.costs.insert(to, next_cost);
+                search.parents.insert(to, position);
+                heap.push(Node {
+                    priority: next_cost,
+                    position: to,
+                });
+            }
+        }
+    }
+
+    search
+}
+
+pub(super) fn distances<O>(open: &O, start: (isize, isize)) -> HashMap<(isize, isize), f64>
+where
+    O: Fn((isize, isize)) -> bool,
+{
+    dijkstra(open, &[start], |_| false).costs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::astar;
+    use crate::grid::Grid;
+    use crate::path::path_cost;
+
+    fn parse(rows: &[&str]) -> Grid<char> {
+        let mut grid = Grid::new();
+        for (x, row) in rows.iter().enumerate() {
+            for (y, cell) in row.chars().enumerate() {
+                grid.set(x as isize, y as isize, cell);
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn routes_around_walls() {
+        let grid = parse(&["....", ".##.", ".#..", "...."]);
+        let path = astar(&grid, (0, 0), (2, 2), |c| *c == '.').unwrap();
+
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(2, 2)));
+        assert!((path_cost(&path) - 6.0).abs() < 1e-9);
+
+        let open = parse(&["....", "....", "...."]);
+        let path = astar(&open, (0, 0), (2, 3), |c| *c == '.').unwrap();
+        assert!((path_cost(&path) - (1.0 + 2.0 * 2f64.sqrt())).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stays_within_the_bounds_of_default_grids() {
+        let mut grid = Grid::new_with_default('.');
+        grid.set(0, 0, '#');
+        grid.set(3, 3, '#');
+        assert!(astar(&grid, (0, 1), (3, 2), |c| *c == '.').is_some());
+        assert_eq!(astar(&grid, (0, 1), (9, 9), |c| *c == '.'), None);
+    }
+
+    #[test]
+    fn no_corner_cutting() {
+        let grid = parse(&[".#", "#."]);
+        assert_eq!(astar(&grid, (0, 0), (1, 1), |c| *c == '.'), None);
+        assert_eq!(astar(&grid, (0, 0), (5, 5), |c| *c == '.'), None);
+    }
+}
@@ -0,0 +1,175 @@
+use super::{is_open, neighbors, octile, Node};
+use crate::grid::GridRead;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+type Cell = (isize, isize);
+
+// Cells and moves claimed by agents that were routed earlier, by tick.
+// Paths are indexed by tick, with tick 0 at the start cell, so a cell
+// repeating on consecutive ticks is a wait.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReservationTable {
+    cells: HashSet<(Cell, usize)>,
+    moves: HashSet<(Cell, Cell, usize)>,
+    // The last reserved tick of each cell.
+    latest: HashMap<Cell, usize>,
+    // Cells occupied from a tick on by agents that stay there.
+    parked: HashMap<Cell, usize>,
+}
+
+impl ReservationTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reserve(&mut self, x: isize, y: isize, tick: usize) {
+        self.cells.insert(((x, y), tick));
+        let latest = self.latest.entry((x, y)).or_insert(tick);
+        *latest = (*latest).max(tick);
+    }
+
+    // Reserves each cell of `path` at its tick along with the moves between
+    // them, so no other agent swaps places with this one, and keeps the last
+    // cell for the agent from then on.
+    pub fn reserve_path(&mut self, path: &[Cell]) {
+        for (tick, &(x, y)) in path.iter().enumerate() {
+            self.reserve(x, y, tick);
+        }
+        for (tick, step) in path.windows(2).enumerate() {
+            self.moves.insert((step[0], step[1], tick));
+        }
+        if let Some(&last) = path.last() {
+            let tick = self.parked.entry(last).or_insert(path.len() - 1);
+            *tick = (*tick).min(path.len() - 1);
+        }
+    }
+
+    pub fn is_reserved(&self, x: isize, y: isize, tick: usize) -> bool {
+        self.cells.contains(&((x, y), tick))
+            || self.parked.get(&(x, y)).is_some_and(|&from| from <= tick)
+    }
+
+    // Whether an agent may step from `from` to `to` between `tick` and the
+    // next one.
+    fn allows(&self, from: Cell, to: Cell, tick: usize) -> bool {
+        !self.is_reserved(to.0, to.1, tick + 1) && !self.moves.contains(&(to, from, tick))
+    }
+
+    // Whether an agent can stay on `cell` from `tick` on.
+    fn free_from(&self, cell: Cell, tick: usize) -> bool {
+        !self.parked.contains_key(&cell) && self.latest.get(&cell).is_none_or(|&last| last < tick)
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.moves.clear();
+        self.latest.clear();
+        self.parked.clear();
+    }
+}
+
+// Windowed cooperative A* (WHCA*, with the octile heuristic in place of a
+// reverse search) for one agent: a search over (cell, tick) states that
+// waits or moves each tick and avoids the cells and swaps in
+// `reservations`. Only the first `window` ticks are checked, after which the
+// search goes on as plain A*. Agents are routed one after the other by
+// planning each against `reservations` and then reserving its path, and
+// replanned before the window runs out. Returns the cell at every tick up to
+// the one where the agent reaches the goal and can stay.
+pub fn space_time_astar<T, R, F>(
+    grid: &R,
+    start: Cell,
+    goal: Cell,
+    passable: F,
+    reservations: &ReservationTable,
+    window: usize,
+) -> Option<Vec<Cell>>
+where
+    R: GridRead<T> + ?Sized,
+    F: Fn(&T) -> bool,
+{
+    let open = |position| is_open(grid, &passable, position);
+    if !open(start) || !open(goal) {
+        return None;
+    }
+
+    // Ticks past the window are all stored as `window`, which keeps the
+    // state space finite.
+    let state = (start, 0);
+    let mut heap = BinaryHeap::new();
+    let mut closed = HashSet::new();
+    let mut costs = HashMap::from([(state, 0.0)]);
+    let mut parents = HashMap::new();
+    heap.push(Node {
+        priority: octile(start, goal),
+        position: state,
+    });
+
+    while let Some(Node { position, .. }) = heap.pop() {
+        let (cell, tick) = position;
+        if cell == goal && (tick >= window || reservations.free_from(goal, tick)) {
+            let mut states = vec![position];
+            while let Some(&parent) = parents.get(states.last().unwrap()) {
+                states.push(parent);
+            }
+            states.reverse();
+            return Some(states.into_iter().map(|(cell, _)| cell).collect());
+        }
+        if !closed.insert(position) {
+            continue;
+        }
+
+        let cost = costs[&position];
+        let wait = (tick < window).then_some((cell, 1.0));
+        for (next, length) in neighbors(&open, cell).chain(wait) {
+            if tick < window && !reservations.allows(cell, next, tick) {
+                continue;
+            }
+            let state = (next, (tick + 1).min(window));
+            let next_cost = cost + length;
+            if costs.get(&state).is_none_or(|&known| next_cost < known) {
+                costs.insert(state, next_cost);
+                parents.insert(state, position);
+                heap.push(Node {
+                    priority: next_cost + octile(next, goal),
+                    position: state,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{space_time_astar, ReservationTable};
+    use crate::grid::Grid;
+    use crate::rect::Rect;
+
+    #[test]
+    fn routes_around_reserved_agents() {
+        let mut grid = Grid::new();
+        grid.fill_rect(Rect::new(0, 0, 2, 2), true);
+
+        let mut table = ReservationTable::new();
+        let first = [(1, 0), (1, 1), (1, 2)];
+        table.reserve_path(&first);
+        assert!(table.is_reserved(1, 1, 1));
+        assert!(table.is_reserved(1, 2, 9));
+
+        let second = space_time_astar(&grid, (0, 1), (2, 1), |&open| open, &table, 8).unwrap();
+        assert_eq!(second.last(), Some(&(2, 1)));
+        for (tick, &cell) in second.iter().enumerate() {
+            assert!(!table.is_reserved(cell.0, cell.1, tick), "tick {tick}");
+        }
+
+        // The goal is only reached once nobody passes through it later.
+        table.reserve_path(&second);
+        let third = space_time_astar(&grid, (0, 0), (1, 1), |&open| open, &table, 8).unwrap();
+        assert_eq!(third.last(), Some(&(1, 1)));
+        for tick in third.len() - 1..12 {
+            assert!(!table.is_reserved(1, 1, tick), "tick {tick}");
+        }
+    }
+}
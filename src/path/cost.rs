@@ -0,0 +1,150 @@
+use super::astar::weighted_path;
+use crate::grid::Grid;
+use crate::rect::Rect;
+
+// Combines weighted layers (terrain, danger, attraction towards goals, ...)
+// into one grid of movement costs. A cell costs the base plus the weighted
+// sum of the layers covering it, clamped to zero from below; cells adding up
+// to an infinite or NaN cost are left empty and so become impassable.
+#[derive(Clone)]
+pub struct CostMap {
+    base: f64,
+    layers: Vec<(Grid<f64>, f64)>,
+}
+
+impl CostMap {
+    pub fn new(base: f64) -> Self {
+        Self {
+            base,
+            layers: vec![],
+        }
+    }
+
+    pub fn layer(mut self, field: &Grid<f64>, weight: f64) -> Self {
+        self.layers.push((field.clone(), weight));
+        self
+    }
+
+    pub fn layer_with<T, F>(mut self, grid: &Grid<T>, weight: f64, cost: F) -> Self
+    where
+        F: FnMut(&T) -> f64,
+    {
+        self.layers.push((grid.classify(cost), weight));
+        self
+    }
+
+    pub fn cost(&self, x: isize, y: isize) -> f64 {
+        let total = self
+            .layers
+            .iter()
+            .fold(self.base, |total, (field, weight)| match field.get(x, y) {
+                Some(value) => total + weight * value,
+                None => total,
+            });
+        if total.is_nan() {
+            return f64::INFINITY;
+        }
+        total.max(0.0)
+    }
+
+    pub fn build(&self, region: Rect) -> Grid<f64> {
+        let mut grid = Grid::new();
+
+        for (x, y) in region.iter() {
+            let cost = self.cost(x, y);
+            if cost.is_finite() {
+                grid.set(x, y, cost);
+            }
+        }
+
+        grid
+    }
+}
+
+// A* over a cost grid: a step costs its length times the mean cost of the
+// two cells it joins. Empty cells and NaN costs are impassable.
+pub fn weighted_astar(
+    costs: &Grid<f64>,
+    start: (isize, isize),
+    goal: (isize, isize),
+) -> Option<Vec<(isize, isize)>> {
+    let cost = |x, y| costs.get(x, y).copied().filter(|cost: &f64| !cost.is_nan());
+    let cheapest = costs
+        .bounds()
+        .iter()
+        .filter_map(|(x, y)| cost(x, y))
+        .fold(f64::INFINITY, f64::min);
+
+    let open = |(x, y): (isize, isize)| cost(x, y).is_some();
+    let step = |from: (isize, isize), to: (isize, isize), length: f64| {
+        let from = cost(from.0, from.1).unwrap();
+        let to = cost(to.0, to.1).unwrap();
+        length * (from + to) / 2.0
+    };
+
+    weighted_path(&open, start, goal, step, cheapest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{weighted_astar, CostMap};
+    use crate::grid::Grid;
+    use crate::rect::Rect;
+
+    #[test]
+    fn combines_layers() {
+        let mut terrain = Grid::new();
+        terrain.set(0, 0, 'w');
+        terrain.set(0, 1, '#');
+        terrain.set(0, 2, '.');
+
+        let mut danger = Grid::new();
+        danger.set(0, 2, 4.0);
+
+        let costs = CostMap::new(1.0)
+            .layer_with(&terrain, 1.0, |tile| match tile {
+                'w' => 2.0,
+                '#' => f64::INFINITY,
+                _ => 0.0,
+            })
+            .layer(&danger, 0.5)
+            .build(Rect::new(0, 0, 0, 3));
+
+        assert_eq!(costs.get(0, 0), Some(&3.0));
+        assert_eq!(costs.get(0, 1), None);
+        assert_eq!(costs.get(0, 2), Some(&3.0));
+        assert_eq!(costs.get(0, 3), Some(&1.0));
+
+        let mut bad = Grid::new();
+        bad.set(0, 0, f64::NAN);
+        let nan = CostMap::new(1.0).layer(&bad, 1.0);
+        assert_eq!(nan.cost(0, 0), f64::INFINITY);
+        assert_eq!(nan.build(Rect::new(0, 0, 0, 1)).get(0, 0), None);
+    }
+
+    #[test]
+    fn paths_avoid_danger() {
+        let mut danger = Grid::new();
+        for y in 3..=5 {
+            for x in 0..=5 {
+                danger.set(x, y, 10.0);
+            }
+        }
+
+        let costs = CostMap::new(1.0)
+            .layer(&danger, 1.0)
+            .build(Rect::new(0, 0, 8, 8));
+        let path = weighted_astar(&costs, (0, 0), (0, 8)).unwrap();
+
+        assert_eq!((path[0], path[path.len() - 1]), ((0, 0), (0, 8)));
+        assert!(path.iter().all(|&(x, y)| danger.get(x, y).is_none()));
+
+        let mut row = Grid::new();
+        for y in 0..3 {
+            row.set(0, y, 1.0);
+        }
+        assert!(weighted_astar(&row, (0, 0), (0, 2)).is_some());
+        row.set(0, 1, f64::NAN);
+        assert_eq!(weighted_astar(&row, (0, 0), (0, 2)), None);
+    }
+}
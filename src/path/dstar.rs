@@ -0,0 +1,265 @@
+use super::{is_open, neighbors, octile};
+use crate::grid::GridRead;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::marker::PhantomData;
+
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    key: (f64, f64),
+    position: (isize, isize),
+}
+
+fn compare(a: (f64, f64), b: (f64, f64)) -> Ordering {
+    a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1))
+}
+
+// Reversed so the binary heap pops the smallest key first.
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare(other.key, self.key).then_with(|| other.position.cmp(&self.position))
+    }
+}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+// D* Lite: searches backwards from the goal and keeps its cost estimates
+// between queries, so after a few cells change (`update`) or the agent moves
+// (`move_start`) only the affected part of the search is redone.
+pub struct DStarLite<T, F> {
+    passable: F,
+    start: (isize, isize),
+    goal: (isize, isize),
+    last: (isize, isize),
+    modifier: f64,
+    g: HashMap<(isize, isize), f64>,
+    rhs: HashMap<(isize, isize), f64>,
+    queue: BinaryHeap<Entry>,
+    queued: HashMap<(isize, isize), (f64, f64)>,
+    marker: PhantomData<fn(&T) -> bool>,
+}
+
+impl<T, F> DStarLite<T, F>
+where
+    F: Fn(&T) -> bool,
+{
+    pub fn new(start: (isize, isize), goal: (isize, isize), passable: F) -> Self {
+        let mut planner = Self {
+            passable,
+            start,
+            goal,
+            last: start,
+            modifier: 0.0,
+            g: HashMap::new(),
+            rhs: HashMap::new(),
+            queue: BinaryHeap::new(),
+            queued: HashMap::new(),
+            marker: PhantomData,
+        };
+
+        planner.rhs.insert(goal, 0.0);
+        let key = planner.key(goal);
+        planner.push(goal, key);
+        planner
+    }
+
+    fn g(&self, position: (isize, isize)) -> f64 {
+        self.g.get(&position).copied().unwrap_or(f64::INFINITY)
+    }
+
+    fn rhs(&self, position: (isize, isize)) -> f64 {
+        self.rhs.get(&position).copied().unwrap_or(f64::INFINITY)
+    }
+
+    fn key(&self, position: (isize, isize)) -> (f64, f64) {
+        let best = self.g(position).min(self.rhs(position));
+        (best + octile(self.start, position) + self.modifier, best)
+    }
+
+    fn push(&mut self, position: (isize, isize), key: (f64, f64)) {
+        self.queued.insert(position, key);
+        self.queue.push(Entry { key, position });
+    }
+
+    // Drops queue entries whose key has since been replaced or removed.
+    fn top(&mut self) -> Option<Entry> {
+        while let Some(&entry) = self.queue.peek() {
+            if self.queued.get(&entry.position) == Some(&entry.key) {
+                return Some(entry);
+            }
+            self.queue.pop();
+        }
+        None
+    }
+
+    fn update_vertex<R: GridRead<T> + ?Sized>(&mut self, grid: &R, position: (isize, isize)) {
+        let open = |position| is_open(grid, &self.passable, position);
+
+        if position != self.goal {
+            let rhs = if open(position) {
+                neighbors(&open, position)
+                    .map(|(next, cost)| cost + self.g(next))
+                    .fold(f64::INFINITY, f64::min)
+            } else {
+                f64::INFINITY
+            };
+            self.rhs.insert(position, rhs);
+        }
+
+        if self.g(position) == self.rhs(position) {
+            self.queued.remove(&position);
+        } else {
+            let key = self.key(position);
+            self.push(position, key);
+        }
+    }
+
+    fn update_neighbors<R: GridRead<T> + ?Sized>(&mut self, grid: &R, position: (isize, isize)) {
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                if (dx, dy) != (0, 0) {
+                    self.update_vertex(grid, (position.0 + dx, position.1 + dy));
+                }
+            }
+        }
+    }
+
+    fn compute<R: GridRead<T> + ?Sized>(&mut self, grid: &R) {
+        while let Some(Entry { key, position }) = self.top() {
+            let settled = self.rhs(self.start) == self.g(self.start);
+            if compare(key, self.key(self.start)) != Ordering::Less && settled {
+                break;
+            }
+
+            self.queue.pop();
+            self.queued.remove(&position);
+
+            let current = self.key(position);
+            if compare(key, current) == Ordering::Less {
+                self.push(position, current);
+            } else if self.g(position) > self.rhs(position) {
+                self.g.insert(position, self.rhs(position));
+                self.update_neighbors(grid, position);
+            } else {
+                self.g.insert(position, f64::INFINITY);
+                self.update_vertex(grid, position);
+                self.update_neighbors(grid, position);
+            }
+        }
+    }
+
+    // Call after the cell at (x, y) changed in `grid`.
+    pub fn update<R: GridRead<T> + ?Sized>(&mut self, grid: &R, x: isize, y: isize) {
+        self.update_vertex(grid, (x, y));
+        self.update_neighbors(grid, (x, y));
+    }
+
+    pub fn move_start(&mut self, start: (isize, isize)) {
+        self.start = start;
+        self.modifier += octile(self.last, start);
+        self.last = start;
+    }
+
+    // Returns the cells of a shortest path from the current start to the
+    // goal, both included.
+    pub fn path<R: GridRead<T> + ?Sized>(&mut self, grid: &R) -> Option<Vec<(isize, isize)>> {
+        self.compute(grid);
+        if self.g(self.start).is_infinite() {
+            return None;
+        }
+
+        let open = |position| is_open(grid, &self.passable, position);
+        let mut path = vec![self.start];
+        let mut current = self.start;
+
+        while current != self.goal {
+            let (next, _) = neighbors(&open, current)
+                .map(|(next, cost)| (next, cost + self.g(next)))
+                .min_by(|a, b| a.1.total_cmp(&b.1))?;
+            current = next;
+            path.push(current);
+        }
+
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DStarLite;
+    use crate::grid::Grid;
+    use crate::path::{astar, path_cost, scattered_walls};
+
+    fn cost(path: Option<Vec<(isize, isize)>>) -> Option<f64> {
+        path.map(|path| path_cost(&path))
+    }
+
+    fn same(a: Option<f64>, b: Option<f64>) -> bool {
+        match (a, b) {
+            (Some(a), Some(b)) => (a - b).abs() < 1e-9,
+            (a, b) => a == b,
+        }
+    }
+
+    #[test]
+    fn replans_after_changes() {
+        for seed in 0..10 {
+            let mut grid = scattered_walls(24, seed);
+            grid.set(23, 23, true);
+            let mut planner = DStarLite::new((0, 0), (23, 23), |open: &bool| *open);
+
+            let expected = cost(astar(&grid, (0, 0), (23, 23), |open| *open));
+            let first = planner.path(&grid);
+            assert!(same(cost(first.clone()), expected), "seed {seed}");
+
+            let Some(first) = first else {
+                continue;
+            };
+            for &(x, y) in first.iter().skip(3).step_by(5).take(3) {
+                if (x, y) != (23, 23) {
+                    grid.set(x, y, false);
+                    planner.update(&grid, x, y);
+                }
+            }
+
+            let expected = cost(astar(&grid, (0, 0), (23, 23), |open| *open));
+            assert!(same(cost(planner.path(&grid)), expected), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn follows_a_moving_agent() {
+        let mut grid = Grid::new();
+        for x in 0..10 {
+            for y in 0..10 {
+                grid.set(x, y, x != 5 || y == 9);
+            }
+        }
+
+        let mut planner = DStarLite::new((0, 0), (9, 0), |open: &bool| *open);
+        let path = planner.path(&grid).unwrap();
+
+        planner.move_start(path[4]);
+        grid.set(5, 9, false);
+        planner.update(&grid, 5, 9);
+        assert_eq!(planner.path(&grid), None);
+
+        grid.set(5, 0, true);
+        planner.update(&grid, 5, 0);
+        let path = planner.path(&grid).unwrap();
+        assert_eq!(path.first(), Some(&(0, 4)));
+        assert_eq!(path.len(), 10);
+    }
+}
@@ -0,0 +1,201 @@
+use super::astar::search_path;
+use super::{is_open, neighbors, octile, path_cost};
+use crate::grid::GridRead;
+use std::collections::HashSet;
+
+// Limits on the paths `count_paths` counts.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PathConstraints {
+    // The longest path counted, in steps; None for no limit.
+    pub max_steps: Option<usize>,
+    // Cells every counted path must pass, in any order.
+    pub through: Vec<(isize, isize)>,
+}
+
+// The number of paths from `start` to `goal` that visit no cell twice and
+// meet the constraints. This walks every such path, so it suits puzzle-sized
+// maps or a tight step limit.
+pub fn count_paths<T, R, F>(
+    grid: &R,
+    start: (isize, isize),
+    goal: (isize, isize),
+    passable: F,
+    constraints: &PathConstraints,
+) -> u64
+where
+    R: GridRead<T> + ?Sized,
+    F: Fn(&T) -> bool,
+{
+    let open = |position| is_open(grid, &passable, position);
+    if !open(start) || !open(goal) {
+        return 0;
+    }
+
+    let through: HashSet<_> = constraints.through.iter().copied().collect();
+    let mut walk = Walk {
+        open,
+        goal,
+        max_steps: constraints.max_steps.unwrap_or(usize::MAX),
+        passed: usize::from(through.contains(&start)),
+        through,
+        visited: HashSet::from([start]),
+    };
+    walk.count_from(start, 0)
+}
+
+// The state of the depth-first walk behind `count_paths`.
+struct Walk<O> {
+    open: O,
+    goal: (isize, isize),
+    max_steps: usize,
+    through: HashSet<(isize, isize)>,
+    visited: HashSet<(isize, isize)>,
+    // How many `through` cells the current path has passed.
+    passed: usize,
+}
+
+impl<O: Fn((isize, isize)) -> bool> Walk<O> {
+    fn count_from(&mut self, position: (isize, isize), steps: usize) -> u64 {
+        let goal = self.goal;
+        if position == goal {
+            return u64::from(self.passed == self.through.len());
+        }
+        let remaining = position.0.abs_diff(goal.0).max(position.1.abs_diff(goal.1));
+        if steps.saturating_add(remaining) > self.max_steps {
+            return 0;
+        }
+
+        let next: Vec<_> = neighbors(&self.open, position).collect();
+        let mut count = 0;
+        for (next, _) in next {
+            if !self.visited.insert(next) {
+                continue;
+            }
+            let required = usize::from(self.through.contains(&next));
+            self.passed += required;
+            count += self.count_from(next, steps + 1);
+            self.passed -= required;
+            self.visited.remove(&next);
+        }
+        count
+    }
+}
+
+// Up to `k` loopless paths from `start` to `goal` in order of cost, the
+// first being a shortest path, found with Yen's algorithm. Each later path
+// leaves an earlier one at some cell and takes the cheapest detour from
+// there, so the routes differ but may share long stretches.
+pub fn k_shortest_paths<T, R, F>(
+    grid: &R,
+    start: (isize, isize),
+    goal: (isize, isize),
+    passable: F,
+    k: usize,
+) -> Vec<Vec<(isize, isize)>>
+where
+    R: GridRead<T> + ?Sized,
+    F: Fn(&T) -> bool,
+{
+    let open = |position| is_open(grid, &passable, position);
+    let detour = |from, nodes: &HashSet<(isize, isize)>, edges: &HashSet<_>| {
+        let successors = |position, next: &mut Vec<_>| {
+            next.extend(
+                neighbors(&open, position)
+                    .filter(|&(to, _)| !nodes.contains(&to) && !edges.contains(&(position, to))),
+            );
+        };
+        search_path(successors, from, goal, |position| octile(position, goal))
+    };
+
+    let mut paths: Vec<Vec<(isize, isize)>> = vec![];
+    let mut candidates: Vec<Vec<(isize, isize)>> = vec![];
+    if k == 0 || !open(start) || !open(goal) {
+        return paths;
+    }
+    match detour(start, &HashSet::new(), &HashSet::new()) {
+        Some(path) => paths.push(path),
+        None => return paths,
+    }
+
+    while paths.len() < k {
+        let previous = paths.last().unwrap().clone();
+        for spur in 0..previous.len() - 1 {
+            let root = &previous[..=spur];
+            let edges: HashSet<_> = paths
+                .iter()
+                .filter(|path| path.len() > spur + 1 && path[..=spur] == *root)
+                .map(|path| (path[spur], path[spur + 1]))
+                .collect();
+            let nodes: HashSet<_> = root[..spur].iter().copied().collect();
+
+            if let Some(tail) = detour(previous[spur], &nodes, &edges) {
+                let mut path = root[..spur].to_vec();
+                path.extend(tail);
+                if !paths.contains(&path) && !candidates.contains(&path) {
+                    candidates.push(path);
+                }
+            }
+        }
+
+        let Some(best) = (0..candidates.len())
+            .min_by(|&a, &b| path_cost(&candidates[a]).total_cmp(&path_cost(&candidates[b])))
+        else {
+            break;
+        };
+        paths.push(candidates.remove(best));
+    }
+
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count_paths, k_shortest_paths, PathConstraints};
+    use crate::grid::Grid;
+    use crate::path::path_cost;
+    use crate::rect::Rect;
+
+    #[test]
+    fn counts_constrained_paths() {
+        let mut grid = Grid::new();
+        grid.fill_rect(Rect::new(0, 0, 1, 1), true);
+        let count = |constraints| count_paths(&grid, (0, 0), (1, 1), |&open| open, &constraints);
+
+        assert_eq!(count(PathConstraints::default()), 5);
+        assert_eq!(
+            count(PathConstraints {
+                max_steps: Some(1),
+                ..PathConstraints::default()
+            }),
+            1
+        );
+        assert_eq!(
+            count(PathConstraints {
+                through: vec![(0, 1)],
+                ..PathConstraints::default()
+            }),
+            3
+        );
+    }
+
+    #[test]
+    fn lists_alternatives_by_cost() {
+        let mut grid = Grid::new();
+        grid.fill_rect(Rect::new(0, 0, 2, 2), true);
+        let paths = k_shortest_paths(&grid, (0, 0), (0, 2), |&open| open, 4);
+        assert_eq!(paths.len(), 4);
+        assert_eq!(paths[0], [(0, 0), (0, 1), (0, 2)]);
+        assert_eq!(paths[1], [(0, 0), (1, 1), (0, 2)]);
+        for pair in paths.windows(2) {
+            assert!(path_cost(&pair[0]) <= path_cost(&pair[1]));
+            assert_ne!(pair[0], pair[1]);
+        }
+
+        let mut corridor = Grid::new();
+        corridor.fill_rect(Rect::new(0, 0, 0, 4), true);
+        assert_eq!(
+            k_shortest_paths(&corridor, (0, 0), (0, 4), |&open| open, 3).len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,390 @@
+use super::astar::{distances, shortest_path};
+use super::{is_open, octile, reconstruct, Node};
+use crate::grid::GridRead;
+use crate::rect::Rect;
+use crate::visited::VisitedGrid;
+use std::collections::{BinaryHeap, HashMap};
+use std::marker::PhantomData;
+
+type Cluster = (isize, isize);
+type Transition = ((isize, isize), (isize, isize));
+type Links = HashMap<(isize, isize), Vec<((isize, isize), f64)>>;
+
+// HPA*: the grid's bounds at construction are split into square clusters.
+// Every run of open cells along the border of two clusters gets one
+// transition in its middle, and the entrances of each cluster are connected
+// by their shortest paths inside it. Queries search this abstract graph and
+// then refine it into cells, so paths are close to but not always as short as
+// those of `astar`.
+pub struct Hierarchy<T, F> {
+    passable: F,
+    cluster_size: isize,
+    bounds: Rect,
+    // Pairs of cells crossing from a cluster into the one below it, or into
+    // the one to its right when the flag is set.
+    transitions: HashMap<(Cluster, bool), Vec<Transition>>,
+    edges: HashMap<Cluster, Links>,
+    marker: PhantomData<fn(&T) -> bool>,
+}
+
+impl<T, F> Hierarchy<T, F>
+where
+    F: Fn(&T) -> bool,
+{
+    pub fn new<R: GridRead<T> + ?Sized>(grid: &R, cluster_size: usize, passable: F) -> Self {
+        assert!(cluster_size > 0, "clusters must not be empty");
+
+        let mut hierarchy = Self {
+            passable,
+            cluster_size: cluster_size as isize,
+            bounds: grid.bounds(),
+            transitions: HashMap::new(),
+            edges: HashMap::new(),
+            marker: PhantomData,
+        };
+
+        let clusters: Vec<Cluster> = hierarchy.clusters().iter().collect();
+        for &cluster in &clusters {
+            hierarchy.find_transitions(grid, cluster, false);
+            hierarchy.find_transitions(grid, cluster, true);
+        }
+        for &cluster in &clusters {
+            hierarchy.connect(grid, cluster);
+        }
+
+        hierarchy
+    }
+
+    fn clusters(&self) -> Rect {
+        let (x, y) = self.cluster_of((self.bounds.max_x, self.bounds.max_y));
+        Rect::new(0, 0, x, y)
+    }
+
+    fn cluster_of(&self, (x, y): (isize, isize)) -> Cluster {
+        (
+            (x - self.bounds.min_x).div_euclid(self.cluster_size),
+            (y - self.bounds.min_y).div_euclid(self.cluster_size),
+        )
+    }
+
+    fn cluster_rect(&self, (cx, cy): Cluster) -> Rect {
+        let min_x = self.bounds.min_x + cx * self.cluster_size;
+        let min_y = self.bounds.min_y + cy * self.cluster_size;
+        Rect::new(
+            min_x,
+            min_y,
+            (min_x + self.cluster_size - 1).min(self.bounds.max_x),
+            (min_y + self.cluster_size - 1).min(self.bounds.max_y),
+        )
+    }
+
+    fn local<'a, R: GridRead<T> + ?Sized>(
+        &'a self,
+        grid: &'a R,
+        cluster: Cluster,
+    ) -> impl Fn((isize, isize)) -> bool + 'a {
+        let rect = self.cluster_rect(cluster);
+        move |(x, y)| rect.contains(x, y) && is_open(grid, &self.passable, (x, y))
+    }
+
+    fn find_transitions<R: GridRead<T> + ?Sized>(
+        &mut self,
+        grid: &R,
+        cluster: Cluster,
+        right: bool,
+    ) {
+        let neighbor = if right {
+            (cluster.0, cluster.1 + 1)
+        } else {
+            (cluster.0 + 1, cluster.1)
+        };
+        let clusters = self.clusters();
+        if !clusters.contains(cluster.0, cluster.1) || !clusters.contains(neighbor.0, neighbor.1) {
+            return;
+        }
+
+        let rect = self.cluster_rect(cluster);
+        let pairs: Vec<Transition> = if right {
+            (rect.min_x..=rect.max_x)
+                .map(|x| ((x, rect.max_y), (x, rect.max_y + 1)))
+                .collect()
+        } else {
+            (rect.min_y..=rect.max_y)
+                .map(|y| ((rect.max_x, y), (rect.max_x + 1, y)))
+                .collect()
+        };
+
+        let mut transitions = vec![];
+        let mut run = vec![];
+        for pair in pairs {
+            let open =
+                is_open(grid, &self.passable, pair.0) && is_open(grid, &self.passable, pair.1);
+            if open {
+                run.push(pair);
+            } else if !run.is_empty() {
+                transitions.push(run[run.len() / 2]);
+                run.clear();
+            }
+        }
+        if !run.is_empty() {
+            transitions.push(run[run.len() / 2]);
+        }
+
+        self.transitions.insert((cluster, right), transitions);
+    }
+
+    fn crossings(&self, cluster: Cluster) -> Vec<Transition> {
+        let outgoing = [(cluster, false), (cluster, true)];
+        let incoming = [
+            ((cluster.0 - 1, cluster.1), false),
+            ((cluster.0, cluster.1 - 1), true),
+        ];
+
+        let mut crossings = vec![];
+        for key in outgoing {
+            crossings.extend(self.transitions.get(&key).into_iter().flatten().copied());
+        }
+        for key in incoming {
+            let transitions = self.transitions.get(&key).into_iter().flatten();
+            crossings.extend(transitions.map(|&(outside, inside)| (inside, outside)));
+        }
+        crossings
+    }
+
+    fn entrances(&self, cluster: Cluster) -> Vec<(isize, isize)> {
+        let mut entrances: Vec<(isize, isize)> = self
+            .crossings(cluster)
+            .into_iter()
+            .map(|(inside, _)| inside)
+            .collect();
+        entrances.sort_unstable();
+        entrances.dedup();
+        entrances
+    }
+
+    fn connect<R: GridRead<T> + ?Sized>(&mut self, grid: &R, cluster: Cluster) {
+        let entrances = self.entrances(cluster);
+        let open = self.local(grid, cluster);
+
+        let edges = entrances
+            .iter()
+            .map(|&entrance| {
+                let costs = distances(&open, entrance);
+                let reachable = entrances
+                    .iter()
+                    .filter(|&&other| other != entrance)
+                    .filter_map(|other| Some((*other, *costs.get(other)?)))
+                    .collect();
+                (entrance, reachable)
+            })
+            .collect();
+        drop(open);
+
+        self.edges.insert(cluster, edges);
+    }
+
+    fn links(&self, entrance: (isize, isize)) -> Vec<((isize, isize), f64)> {
+        let cluster = self.cluster_of(entrance);
+        let mut links = self
+            .edges
+            .get(&cluster)
+            .and_then(|edges| edges.get(&entrance))
+            .cloned()
+            .unwrap_or_default();
+
+        for (inside, outside) in self.crossings(cluster) {
+            if inside == entrance {
+                links.push((outside, 1.0));
+            }
+        }
+
+        links
+    }
+
+    // Call after the cell at (x, y) changed in `grid` to repair the clusters
+    // it affects.
+    pub fn update<R: GridRead<T> + ?Sized>(&mut self, grid: &R, x: isize, y: isize) {
+        if !self.bounds.contains(x, y) {
+            return;
+        }
+
+        let (cx, cy) = self.cluster_of((x, y));
+        self.find_transitions(grid, (cx, cy), false);
+        self.find_transitions(grid, (cx, cy), true);
+        self.find_transitions(grid, (cx - 1, cy), false);
+        self.find_transitions(grid, (cx, cy - 1), true);
+
+        let clusters = self.clusters();
+        for cluster in [
+            (cx, cy),
+            (cx - 1, cy),
+            (cx + 1, cy),
+            (cx, cy - 1),
+            (cx, cy + 1),
+        ] {
+            if clusters.contains(cluster.0, cluster.1) {
+                self.connect(grid, cluster);
+            }
+        }
+    }
+
+    // Returns the cells of a path from `start` to `goal`, both included.
+    pub fn path<R: GridRead<T> + ?Sized>(
+        &self,
+        grid: &R,
+        start: (isize, isize),
+        goal: (isize, isize),
+    ) -> Option<Vec<(isize, isize)>> {
+        if !self.bounds.contains(start.0, start.1) || !self.bounds.contains(goal.0, goal.1) {
+            return None;
+        }
+
+        let start_cluster = self.cluster_of(start);
+        let goal_cluster = self.cluster_of(goal);
+        let from_start = distances(&self.local(grid, start_cluster), start);
+        let to_goal = distances(&self.local(grid, goal_cluster), goal);
+        if from_start.is_empty() || to_goal.is_empty() {
+            return None;
+        }
+
+        let mut heap = BinaryHeap::new();
+        let mut closed = VisitedGrid::new();
+        let mut costs = HashMap::new();
+        let mut parents = HashMap::new();
+
+        costs.insert(start, 0.0);
+        heap.push(Node {
+            priority: octile(start, goal),
+            position: start,
+        });
+
+        while let Some(Node { position, .. }) = heap.pop() {
+            if position == goal {
+                return self.refine(grid, &reconstruct(&parents, goal));
+            }
+            if !closed.insert(position.0, position.1) {
+                continue;
+            }
+
+            let mut links = self.links(position);
+            if position == start {
+                let entrances = self.entrances(start_cluster).into_iter();
+                links.extend(entrances.filter_map(|e| Some((e, *from_start.get(&e)?))));
+            }
+            if self.cluster_of(position) == goal_cluster {
+                links.extend(to_goal.get(&position).map(|&cost| (goal, cost)));
+            }
+
+            let cost = costs[&position];
+            for (next, step) in links {
+                let next_cost = cost + step;
+                if costs.get(&next).is_none_or(|&known| next_cost < known) {
+                    costs.insert(next, next_cost);
+                    parents.insert(next, position);
+                    heap.push(Node {
+                        priority: next_cost + octile(next, goal),
+                        position: next,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    // None when the grid changed since the last `update` and a waypoint can
+    // no longer be reached.
+    fn refine<R: GridRead<T> + ?Sized>(
+        &self,
+        grid: &R,
+        waypoints: &[(isize, isize)],
+    ) -> Option<Vec<(isize, isize)>> {
+        let mut path = vec![waypoints[0]];
+
+        for pair in waypoints.windows(2) {
+            let cluster = self.cluster_of(pair[0]);
+            if cluster != self.cluster_of(pair[1]) {
+                if !is_open(grid, &self.passable, pair[1]) {
+                    return None;
+                }
+                path.push(pair[1]);
+                continue;
+            }
+
+            let segment = shortest_path(&self.local(grid, cluster), pair[0], pair[1])?;
+            path.extend_from_slice(&segment[1..]);
+        }
+
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Hierarchy;
+    use crate::grid::Grid;
+    use crate::path::{astar, can_step, path_cost, scattered_walls};
+
+    fn valid(grid: &Grid<bool>, path: &[(isize, isize)]) -> bool {
+        path.windows(2).all(|step| {
+            let (dx, dy) = (step[1].0 - step[0].0, step[1].1 - step[0].1);
+            dx.abs() <= 1
+                && dy.abs() <= 1
+                && can_step(&|(x, y)| grid.get(x, y) == Some(&true), step[0], dx, dy)
+        })
+    }
+
+    #[test]
+    fn finds_near_optimal_paths() {
+        for seed in 0..10 {
+            let grid = scattered_walls(40, seed);
+            let hierarchy = Hierarchy::new(&grid, 8, |open: &bool| *open);
+
+            for goal in [(39, 39), (3, 36), (20, 1)] {
+                let expected = astar(&grid, (0, 0), goal, |open| *open);
+                let found = hierarchy.path(&grid, (0, 0), goal);
+                assert_eq!(expected.is_some(), found.is_some(), "seed {seed}");
+
+                if let (Some(expected), Some(found)) = (expected, found) {
+                    assert_eq!((found[0], found[found.len() - 1]), ((0, 0), goal));
+                    assert!(valid(&grid, &found));
+                    assert!(path_cost(&found) >= path_cost(&expected) - 1e-9);
+                    assert!(path_cost(&found) <= path_cost(&expected) * 1.5);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn repairs_after_changes() {
+        let mut grid = Grid::new();
+        for x in 0..12 {
+            for y in 0..12 {
+                grid.set(x, y, y != 5 || x == 11);
+            }
+        }
+
+        let mut hierarchy = Hierarchy::new(&grid, 4, |open: &bool| *open);
+        let detour = hierarchy.path(&grid, (0, 0), (0, 11)).unwrap();
+        assert!(detour.contains(&(11, 5)));
+
+        grid.set(0, 5, true);
+        hierarchy.update(&grid, 0, 5);
+        let direct = hierarchy.path(&grid, (0, 0), (0, 11)).unwrap();
+        assert!(direct.contains(&(0, 5)) && direct.len() < detour.len());
+
+        grid.set(11, 5, false);
+        grid.set(0, 5, false);
+        hierarchy.update(&grid, 11, 5);
+        hierarchy.update(&grid, 0, 5);
+        assert_eq!(hierarchy.path(&grid, (0, 0), (0, 11)), None);
+
+        // Without `update` the stale entrance leads nowhere, which is no path
+        // rather than a panic.
+        grid.set(11, 5, true);
+        hierarchy.update(&grid, 11, 5);
+        assert!(hierarchy.path(&grid, (0, 0), (0, 11)).is_some());
+        grid.set(11, 5, false);
+        assert_eq!(hierarchy.path(&grid, (0, 0), (0, 11)), None);
+    }
+}
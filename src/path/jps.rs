@@ -0,0 +1,177 @@
+use super::{can_step, is_open, octile, reconstruct, Node};
+use crate::grid::GridRead;
+use crate::visited::VisitedGrid;
+use std::collections::{BinaryHeap, HashMap};
+
+// Walks from `position` in direction (dx, dy) and returns the first cell
+// that has to be expanded: the goal, a cell with a forced neighbor or, when
+// moving diagonally, a cell from which a straight jump finds one.
+fn jump<T, R, F>(
+    grid: &R,
+    passable: &F,
+    goal: (isize, isize),
+    mut position: (isize, isize),
+    dx: isize,
+    dy: isize,
+) -> Option<(isize, isize)>
+where
+    R: GridRead<T> + ?Sized,
+    F: Fn(&T) -> bool,
+{
+    let open = |(x, y)| is_open(grid, passable, (x, y));
+
+    loop {
+        if !can_step(&open, position, dx, dy) {
+            return None;
+        }
+        position = (position.0 + dx, position.1 + dy);
+        let (x, y) = position;
+
+        let forced = if position == goal {
+            true
+        } else if dx != 0 && dy != 0 {
+            jump(grid, passable, goal, position, dx, 0).is_some()
+                || jump(grid, passable, goal, position, 0, dy).is_some()
+        } else if dx != 0 {
+            open((x, y - 1)) && !open((x - dx, y - 1)) || open((x, y + 1)) && !open((x - dx, y + 1))
+        } else {
+            open((x - 1, y)) && !open((x - 1, y - dy)) || open((x + 1, y)) && !open((x + 1, y - dy))
+        };
+
+        if forced {
+            return Some(position);
+        }
+    }
+}
+
+// Directions worth searching from `position` given where it was reached from.
+fn successors(parent: Option<(isize, isize)>, position: (isize, isize)) -> Vec<(isize, isize)> {
+    let Some(parent) = parent else {
+        return vec![
+            (-1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+            (1, 0),
+            (1, -1),
+            (0, -1),
+            (-1, -1),
+        ];
+    };
+
+    let dx = (position.0 - parent.0).signum();
+    let dy = (position.1 - parent.1).signum();
+
+    if dx != 0 && dy != 0 {
+        vec![(dx, 0), (0, dy), (dx, dy)]
+    } else if dx != 0 {
+        vec![(dx, 0), (dx, 1), (dx, -1), (0, 1), (0, -1)]
+    } else {
+        vec![(0, dy), (1, dy), (-1, dy), (1, 0), (-1, 0)]
+    }
+}
+
+// Jump Point Search: finds paths as short as `astar` on the same grid, but
+// skips over the symmetric runs of open cells that A* would expand one by
+// one. The returned path contains every cell, not only the jump points.
+pub fn jps<T, R, F>(
+    grid: &R,
+    start: (isize, isize),
+    goal: (isize, isize),
+    passable: F,
+) -> Option<Vec<(isize, isize)>>
+where
+    R: GridRead<T> + ?Sized,
+    F: Fn(&T) -> bool,
+{
+    if !is_open(grid, &passable, start) || !is_open(grid, &passable, goal) {
+        return None;
+    }
+
+    let mut open = BinaryHeap::new();
+    let mut closed = VisitedGrid::new();
+    let mut costs = HashMap::new();
+    let mut parents = HashMap::new();
+
+    costs.insert(start, 0.0);
+    open.push(Node {
+        priority: octile(start, goal),
+        position: start,
+    });
+
+    while let Some(Node { position, .. }) = open.pop() {
+        if position == goal {
+            return Some(expand(&reconstruct(&parents, goal)));
+        }
+        if !closed.insert(position.0, position.1) {
+            continue;
+        }
+
+        let cost = costs[&position];
+        for (dx, dy) in successors(parents.get(&position).copied(), position) {
+            let Some(next) = jump(grid, &passable, goal, position, dx, dy) else {
+                continue;
+            };
+
+            let next_cost = cost + octile(position, next);
+            if costs.get(&next).is_none_or(|&known| next_cost < known) {
+                costs.insert(next, next_cost);
+                parents.insert(next, position);
+                open.push(Node {
+                    priority: next_cost + octile(next, goal),
+                    position: next,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+// Fills in the straight and diagonal runs between consecutive jump points.
+fn expand(jump_points: &[(isize, isize)]) -> Vec<(isize, isize)> {
+    let mut path = vec![jump_points[0]];
+
+    for pair in jump_points.windows(2) {
+        let (to_x, to_y) = pair[1];
+        let (mut x, mut y) = pair[0];
+        let dx = (to_x - x).signum();
+        let dy = (to_y - y).signum();
+
+        while (x, y) != (to_x, to_y) {
+            x += dx;
+            y += dy;
+            path.push((x, y));
+        }
+    }
+
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::jps;
+    use crate::path::{astar, can_step, path_cost, scattered_walls};
+
+    #[test]
+    fn matches_astar_costs() {
+        for seed in 0..20 {
+            let grid = scattered_walls(24, seed);
+            for goal in [(23, 23), (0, 23), (17, 5)] {
+                let expected = astar(&grid, (0, 0), goal, |open| *open);
+                let found = jps(&grid, (0, 0), goal, |open| *open);
+
+                match (expected, found) {
+                    (Some(expected), Some(found)) => {
+                        assert!((path_cost(&expected) - path_cost(&found)).abs() < 1e-9);
+                        assert!(found.windows(2).all(|step| {
+                            let (dx, dy) = (step[1].0 - step[0].0, step[1].1 - step[0].1);
+                            can_step(&|(x, y)| grid.get(x, y) == Some(&true), step[0], dx, dy)
+                        }));
+                    }
+                    (expected, found) => assert_eq!(expected, found, "seed {seed}"),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,139 @@
+mod astar;
+mod cooperative;
+mod cost;
+mod dstar;
+mod enumerate;
+mod hierarchy;
+mod jps;
+mod nearest;
+mod rules;
+mod smooth;
+mod turning;
+
+pub use astar::astar;
+pub use cooperative::{space_time_astar, ReservationTable};
+pub use cost::{weighted_astar, CostMap};
+pub use dstar::DStarLite;
+pub use enumerate::{count_paths, k_shortest_paths, PathConstraints};
+pub use hierarchy::Hierarchy;
+pub use jps::jps;
+pub use nearest::{
+    distance_map, nearest_goal_distances, path_to_area, path_to_nearest, path_to_region,
+};
+pub use rules::{
+    astar_with, bfs_with, distance_map_with, CornerCutting, GridMovement, MovementRules, Portals,
+};
+pub use smooth::{line_of_sight, path_to_directions, simplify_path};
+pub use turning::{turning_path, StraightRuns, TurnRule};
+
+pub(crate) use smooth::segment_clear;
+
+use crate::direction::Direction;
+use crate::grid::GridRead;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::f64::consts::SQRT_2;
+
+// All searches move in eight directions at cost 1 straight and sqrt(2)
+// diagonally. Empty cells are impassable and diagonal moves may not cut the
+// corner of an impassable cell.
+
+// A search state, a cell unless the search tracks more such as facing.
+#[derive(Clone, Copy, Debug)]
+struct Node<P = (isize, isize)> {
+    priority: f64,
+    position: P,
+}
+
+// Reversed so the binary heap pops the lowest priority first.
+impl<P: Ord> Ord for Node<P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .priority
+            .total_cmp(&self.priority)
+            .then_with(|| other.position.cmp(&self.position))
+    }
+}
+
+impl<P: Ord> PartialOrd for Node<P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P: Ord> PartialEq for Node<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<P: Ord> Eq for Node<P> {}
+
+fn octile(a: (isize, isize), b: (isize, isize)) -> f64 {
+    let dx = a.0.abs_diff(b.0) as f64;
+    let dy = a.1.abs_diff(b.1) as f64;
+    dx.max(dy) + (SQRT_2 - 1.0) * dx.min(dy)
+}
+
+fn is_open<T, R, F>(grid: &R, passable: &F, (x, y): (isize, isize)) -> bool
+where
+    R: GridRead<T> + ?Sized,
+    F: Fn(&T) -> bool,
+{
+    grid.bounds().contains(x, y) && grid.get(x, y).is_some_and(passable)
+}
+
+fn can_step<O>(open: &O, (x, y): (isize, isize), dx: isize, dy: isize) -> bool
+where
+    O: Fn((isize, isize)) -> bool,
+{
+    open((x + dx, y + dy)) && (dx == 0 || dy == 0 || open((x + dx, y)) && open((x, y + dy)))
+}
+
+fn neighbors<O>(
+    open: &O,
+    position: (isize, isize),
+) -> impl Iterator<Item = ((isize, isize), f64)> + '_
+where
+    O: Fn((isize, isize)) -> bool,
+{
+    Direction::ALL.into_iter().filter_map(move |direction| {
+        let (dx, dy) = direction.offset();
+        can_step(open, position, dx, dy).then(|| {
+            let cost = if direction.is_diagonal() { SQRT_2 } else { 1.0 };
+            ((position.0 + dx, position.1 + dy), cost)
+        })
+    })
+}
+
+fn reconstruct(
+    parents: &HashMap<(isize, isize), (isize, isize)>,
+    goal: (isize, isize),
+) -> Vec<(isize, isize)> {
+    let mut path = vec![goal];
+    while let Some(&parent) = parents.get(path.last().unwrap()) {
+        path.push(parent);
+    }
+    path.reverse();
+    path
+}
+
+pub fn path_cost(path: &[(isize, isize)]) -> f64 {
+    path.windows(2).map(|step| octile(step[0], step[1])).sum()
+}
+
+#[cfg(test)]
+fn scattered_walls(size: isize, seed: u64) -> crate::grid::Grid<bool> {
+    use crate::grid::Grid;
+    use rand::rngs::StdRng;
+    use rand::{RngExt, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut grid = Grid::new();
+    for x in 0..size {
+        for y in 0..size {
+            grid.set(x, y, (x, y) == (0, 0) || rng.random_bool(0.7));
+        }
+    }
+    grid
+}
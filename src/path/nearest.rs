@@ -0,0 +1,165 @@
+use super::astar::dijkstra;
+use super::{is_open, reconstruct};
+use crate::grid::{Grid, GridRead};
+use crate::region::Region;
+
+// Path cost from every reachable cell to the closest of `sources`, computed
+// in a single search.
+pub fn distance_map<T, R, F>(grid: &R, sources: &[(isize, isize)], passable: F) -> Grid<f64>
+where
+    R: GridRead<T> + ?Sized,
+    F: Fn(&T) -> bool,
+{
+    let open = |position| is_open(grid, &passable, position);
+    let mut map = Grid::with_bounds(grid.bounds());
+
+    for ((x, y), cost) in dijkstra(&open, sources, |_| false).costs {
+        map.set(x, y, cost);
+    }
+
+    map
+}
+
+// For each source, the path cost to the closest passable cell satisfying
+// `is_goal`, or None when no such cell can be reached.
+pub fn nearest_goal_distances<T, R, F, G>(
+    grid: &R,
+    sources: &[(isize, isize)],
+    is_goal: G,
+    passable: F,
+) -> Vec<Option<f64>>
+where
+    R: GridRead<T> + ?Sized,
+    F: Fn(&T) -> bool,
+    G: Fn(&T) -> bool,
+{
+    let goals: Vec<(isize, isize)> = grid
+        .bounds()
+        .iter()
+        .filter(|&(x, y)| grid.get(x, y).is_some_and(&is_goal))
+        .collect();
+    let map = distance_map(grid, &goals, passable);
+
+    sources
+        .iter()
+        .map(|&(x, y)| map.get(x, y).copied())
+        .collect()
+}
+
+// The shortest path from whichever of `starts` is closest to a passable cell
+// satisfying `is_goal`.
+pub fn path_to_nearest<T, R, F, G>(
+    grid: &R,
+    starts: &[(isize, isize)],
+    is_goal: G,
+    passable: F,
+) -> Option<Vec<(isize, isize)>>
+where
+    R: GridRead<T> + ?Sized,
+    F: Fn(&T) -> bool,
+    G: Fn(&T) -> bool,
+{
+    let open = |position| is_open(grid, &passable, position);
+    let search = dijkstra(&open, starts, |(x, y)| grid.get(x, y).is_some_and(&is_goal));
+
+    Some(reconstruct(&search.parents, search.reached?))
+}
+
+// Like `path_to_nearest` with goals chosen by position, so a goal can
+// depend on its surroundings, such as any cell next to water.
+pub fn path_to_area<T, R, F, G>(
+    grid: &R,
+    starts: &[(isize, isize)],
+    is_goal: G,
+    passable: F,
+) -> Option<Vec<(isize, isize)>>
+where
+    R: GridRead<T> + ?Sized,
+    F: Fn(&T) -> bool,
+    G: Fn((isize, isize)) -> bool,
+{
+    let open = |position| is_open(grid, &passable, position);
+    let search = dijkstra(&open, starts, is_goal);
+
+    Some(reconstruct(&search.parents, search.reached?))
+}
+
+// The shortest path into the passable part of `region`.
+pub fn path_to_region<T, R, F>(
+    grid: &R,
+    starts: &[(isize, isize)],
+    region: &Region,
+    passable: F,
+) -> Option<Vec<(isize, isize)>>
+where
+    R: GridRead<T> + ?Sized,
+    F: Fn(&T) -> bool,
+{
+    path_to_area(grid, starts, |(x, y)| region.contains(x, y), passable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        distance_map, nearest_goal_distances, path_to_area, path_to_nearest, path_to_region,
+    };
+    use crate::direction::Direction;
+    use crate::grid::Grid;
+    use crate::rect::Rect;
+    use crate::region::Region;
+
+    fn parse(rows: &[&str]) -> Grid<char> {
+        let mut grid = Grid::new();
+        for (x, row) in rows.iter().enumerate() {
+            for (y, cell) in row.chars().enumerate() {
+                grid.set(x as isize, y as isize, cell);
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn distances_to_nearest_source() {
+        let grid = parse(&["......", "..#...", "..#..."]);
+        let map = distance_map(&grid, &[(0, 0), (2, 5)], |c| *c != '#');
+
+        assert_eq!(map.get(0, 0), Some(&0.0));
+        assert_eq!(map.get(1, 2), None);
+        assert_eq!(map.get(2, 3), Some(&2.0));
+        assert_eq!(map.get(2, 1), Some(&(1.0 + 2f64.sqrt())));
+    }
+
+    #[test]
+    fn nearest_exits() {
+        let grid = parse(&["E....", ".###.", "....E", "#####", "....."]);
+        let passable = |c: &char| *c != '#';
+        let is_exit = |c: &char| *c == 'E';
+
+        let distances = nearest_goal_distances(&grid, &[(0, 1), (2, 2), (4, 0)], is_exit, passable);
+        assert_eq!(distances, vec![Some(1.0), Some(2.0), None]);
+
+        let path = path_to_nearest(&grid, &[(4, 4), (2, 0)], is_exit, passable).unwrap();
+        assert_eq!(path, vec![(2, 0), (1, 0), (0, 0)]);
+    }
+
+    #[test]
+    fn reaches_goal_areas() {
+        let grid = parse(&["....~", ".....", "#####", "....."]);
+        let passable = |c: &char| *c == '.';
+        let by_water = |(x, y): (isize, isize)| {
+            Direction::CARDINAL.iter().any(|direction| {
+                let (dx, dy) = direction.offset();
+                grid.get(x + dx, y + dy) == Some(&'~')
+            })
+        };
+
+        let path = path_to_area(&grid, &[(1, 0)], by_water, passable).unwrap();
+        assert_eq!(path.last(), Some(&(0, 3)));
+        assert_eq!(path.len(), 4);
+        assert_eq!(path_to_area(&grid, &[(3, 0)], by_water, passable), None);
+
+        let rooms = Region::from(vec![Rect::new(0, 2, 0, 3), Rect::new(2, 0, 3, 1)]);
+        let path = path_to_region(&grid, &[(1, 0)], &rooms, passable).unwrap();
+        assert_eq!((path.len(), path.last()), (3, Some(&(0, 2))));
+    }
+}
@@ -0,0 +1,330 @@
+use super::astar::{search_all, search_path};
+use super::{octile, reconstruct};
+use crate::direction::Direction;
+use crate::grid::{Grid, GridRead};
+use crate::rect::Rect;
+use crate::visited::VisitedGrid;
+use std::collections::{HashMap, VecDeque};
+use std::marker::PhantomData;
+
+// When a diagonal step may pass the corner between two orthogonal
+// neighbors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CornerCutting {
+    // Both cells beside the corner must be enterable, as in `astar`.
+    Never,
+    // One enterable cell beside the corner is enough.
+    OneSide,
+    Always,
+}
+
+// How a mover gets around, for the `_with` searches. The defaults match the
+// plain searches: eight directions, costs equal to step length and no
+// corner cutting, so usually only `can_enter` is needed. Rules that change
+// the steps or make them cheaper than their length must also override
+// `heuristic`, which must not overestimate.
+pub trait MovementRules {
+    // Whether the mover may step from `from` onto `to`; one-way doors and
+    // ledges check the direction here.
+    fn can_enter(&self, from: (isize, isize), to: (isize, isize)) -> bool;
+
+    fn steps(&self) -> Vec<(isize, isize)> {
+        Direction::ALL.map(Direction::offset).to_vec()
+    }
+
+    fn cost(&self, from: (isize, isize), to: (isize, isize)) -> f64 {
+        ((to.0 - from.0) as f64).hypot((to.1 - from.1) as f64)
+    }
+
+    fn corner_cutting(&self) -> CornerCutting {
+        CornerCutting::Never
+    }
+
+    fn heuristic(&self, from: (isize, isize), goal: (isize, isize)) -> f64 {
+        octile(from, goal)
+    }
+
+    // Steps beyond grid adjacency leaving `from`, such as stairs and
+    // portals, with their costs. They do not need `can_enter`.
+    fn extra_edges(&self, _from: (isize, isize)) -> Vec<((isize, isize), f64)> {
+        vec![]
+    }
+}
+
+// Movement over a grid's passable cells with default rules.
+pub struct GridMovement<'a, T, R: ?Sized, F> {
+    grid: &'a R,
+    passable: F,
+    cells: PhantomData<fn() -> T>,
+}
+
+impl<'a, T, R: GridRead<T> + ?Sized, F: Fn(&T) -> bool> GridMovement<'a, T, R, F> {
+    pub fn new(grid: &'a R, passable: F) -> Self {
+        Self {
+            grid,
+            passable,
+            cells: PhantomData,
+        }
+    }
+}
+
+impl<T, R, F> MovementRules for GridMovement<'_, T, R, F>
+where
+    R: GridRead<T> + ?Sized,
+    F: Fn(&T) -> bool,
+{
+    fn can_enter(&self, _from: (isize, isize), (x, y): (isize, isize)) -> bool {
+        self.grid.bounds().contains(x, y) && self.grid.get(x, y).is_some_and(&self.passable)
+    }
+}
+
+// A destination cell with the cost of getting there, and an extra edge as
+// `(from, to, cost)`.
+type Step = ((isize, isize), f64);
+type Link = ((isize, isize), (isize, isize), f64);
+
+// Adds one-way edges such as portals, stairs and wrap-around doors to other
+// rules; add both directions for two-way links. The heuristic is lowered so
+// it stays admissible when a path can shortcut through the edges.
+pub struct Portals<M> {
+    rules: M,
+    edges: HashMap<(isize, isize), Vec<Step>>,
+    exits: Vec<(isize, isize)>,
+    cheapest: f64,
+}
+
+impl<M: MovementRules> Portals<M> {
+    pub fn new(rules: M, edges: &[Link]) -> Self {
+        let mut portals = Self {
+            rules,
+            edges: HashMap::new(),
+            exits: vec![],
+            cheapest: f64::INFINITY,
+        };
+        for &(from, to, cost) in edges {
+            portals.edges.entry(from).or_default().push((to, cost));
+            portals.exits.push(to);
+            portals.cheapest = portals.cheapest.min(cost);
+        }
+        portals
+    }
+}
+
+impl<M: MovementRules> MovementRules for Portals<M> {
+    fn can_enter(&self, from: (isize, isize), to: (isize, isize)) -> bool {
+        self.rules.can_enter(from, to)
+    }
+
+    fn steps(&self) -> Vec<(isize, isize)> {
+        self.rules.steps()
+    }
+
+    fn cost(&self, from: (isize, isize), to: (isize, isize)) -> f64 {
+        self.rules.cost(from, to)
+    }
+
+    fn corner_cutting(&self) -> CornerCutting {
+        self.rules.corner_cutting()
+    }
+
+    // A path using edges walks to some entrance, takes an edge and walks
+    // from its last exit to the goal, so it costs at least this much.
+    fn heuristic(&self, from: (isize, isize), goal: (isize, isize)) -> f64 {
+        let nearest = |cells: &mut dyn Iterator<Item = (isize, isize)>, to| {
+            cells
+                .map(|cell| self.rules.heuristic(cell, to))
+                .fold(f64::INFINITY, f64::min)
+        };
+        let to_entrance = nearest(&mut self.edges.keys().copied(), from);
+        let from_exit = nearest(&mut self.exits.iter().copied(), goal);
+        self.rules
+            .heuristic(from, goal)
+            .min(to_entrance + self.cheapest + from_exit)
+    }
+
+    fn extra_edges(&self, from: (isize, isize)) -> Vec<((isize, isize), f64)> {
+        let mut edges = self.rules.extra_edges(from);
+        edges.extend(self.edges.get(&from).into_iter().flatten());
+        edges
+    }
+}
+
+// The steps allowed from `position` with their costs.
+fn successors<M: MovementRules + ?Sized>(
+    rules: &M,
+    steps: &[(isize, isize)],
+    position: (isize, isize),
+    next: &mut Vec<((isize, isize), f64)>,
+) {
+    let (x, y) = position;
+    for &(dx, dy) in steps {
+        let to = (x + dx, y + dy);
+        if !rules.can_enter(position, to) {
+            continue;
+        }
+        if dx != 0 && dy != 0 && dx.abs() == 1 && dy.abs() == 1 {
+            let sides = [(x + dx, y), (x, y + dy)].map(|side| rules.can_enter(position, side));
+            let allowed = match rules.corner_cutting() {
+                CornerCutting::Never => sides[0] && sides[1],
+                CornerCutting::OneSide => sides[0] || sides[1],
+                CornerCutting::Always => true,
+            };
+            if !allowed {
+                continue;
+            }
+        }
+        next.push((to, rules.cost(position, to)));
+    }
+    next.extend(rules.extra_edges(position));
+}
+
+// The cheapest path from `start` to `goal` under `rules`, both included.
+pub fn astar_with<M: MovementRules + ?Sized>(
+    rules: &M,
+    start: (isize, isize),
+    goal: (isize, isize),
+) -> Option<Vec<(isize, isize)>> {
+    let steps = rules.steps();
+    search_path(
+        |position, next: &mut Vec<_>| successors(rules, &steps, position, next),
+        start,
+        goal,
+        |position| rules.heuristic(position, goal),
+    )
+}
+
+// Path cost from every cell of `region` reachable from one of `sources`,
+// like `distance_map`.
+pub fn distance_map_with<M: MovementRules + ?Sized>(
+    rules: &M,
+    region: Rect,
+    sources: &[(isize, isize)],
+) -> Grid<f64> {
+    let steps = rules.steps();
+    let search = search_all(
+        |position, next: &mut Vec<_>| {
+            successors(rules, &steps, position, next);
+            next.retain(|((x, y), _)| region.contains(*x, *y));
+        },
+        sources,
+        |_| false,
+    );
+
+    let mut map = Grid::with_bounds(region);
+    for ((x, y), cost) in search.costs {
+        map.set(x, y, cost);
+    }
+    map
+}
+
+// The path with the fewest steps under `rules`, ignoring their costs.
+pub fn bfs_with<M: MovementRules + ?Sized>(
+    rules: &M,
+    start: (isize, isize),
+    goal: (isize, isize),
+) -> Option<Vec<(isize, isize)>> {
+    let steps = rules.steps();
+    let mut seen = VisitedGrid::new();
+    let mut parents = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    let mut next = vec![];
+    seen.insert(start.0, start.1);
+
+    while let Some(position) = queue.pop_front() {
+        if position == goal {
+            return Some(reconstruct(&parents, goal));
+        }
+        next.clear();
+        successors(rules, &steps, position, &mut next);
+        for &(to, _) in &next {
+            if seen.insert(to.0, to.1) {
+                parents.insert(to, position);
+                queue.push_back(to);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        astar_with, bfs_with, distance_map_with, CornerCutting, GridMovement, MovementRules,
+        Portals,
+    };
+    use crate::grid::Grid;
+    use crate::path::{astar, path_cost};
+    use crate::rect::Rect;
+
+    fn parse(rows: &[&str]) -> Grid<char> {
+        let mut grid = Grid::new();
+        for (x, row) in rows.iter().enumerate() {
+            for (y, cell) in row.chars().enumerate() {
+                grid.set(x as isize, y as isize, cell);
+            }
+        }
+        grid
+    }
+
+    // `>` cells can only be entered moving East; diagonal moves may cut
+    // corners.
+    struct Doors<'a>(&'a Grid<char>);
+
+    impl MovementRules for Doors<'_> {
+        fn can_enter(&self, from: (isize, isize), to: (isize, isize)) -> bool {
+            match self.0.get(to.0, to.1) {
+                Some('.') => true,
+                Some('>') => to == (from.0, from.1 + 1),
+                _ => false,
+            }
+        }
+
+        fn corner_cutting(&self) -> CornerCutting {
+            CornerCutting::Always
+        }
+    }
+
+    #[test]
+    fn default_rules_match_astar() {
+        let grid = parse(&["....", ".##.", ".#..", "...."]);
+        let rules = GridMovement::new(&grid, |c: &char| *c == '.');
+        let path = astar_with(&rules, (0, 0), (2, 2)).unwrap();
+        let expected = astar(&grid, (0, 0), (2, 2), |c| *c == '.').unwrap();
+        assert!((path_cost(&path) - path_cost(&expected)).abs() < 1e-9);
+
+        let map = distance_map_with(&rules, Rect::new(0, 0, 3, 3), &[(0, 0)]);
+        assert_eq!(map.get(0, 3), Some(&3.0));
+        assert_eq!(bfs_with(&rules, (0, 0), (2, 2)).unwrap().len(), 7);
+    }
+
+    #[test]
+    fn custom_rules_plug_in() {
+        let grid = parse(&[".#.", ".>.", "..."]);
+        let doors = Doors(&grid);
+        assert_eq!(
+            bfs_with(&doors, (1, 0), (1, 2)).unwrap(),
+            [(1, 0), (1, 1), (1, 2)]
+        );
+        assert_eq!(bfs_with(&doors, (1, 2), (1, 0)).unwrap().len(), 3);
+        assert!(bfs_with(&doors, (1, 2), (1, 0)).unwrap().contains(&(2, 1)));
+        let path = astar_with(&doors, (0, 2), (1, 1)).unwrap();
+        assert_eq!(path[path.len() - 2], (1, 0));
+    }
+
+    #[test]
+    fn portals_shortcut_paths() {
+        // Two rooms joined only by a portal pair.
+        let grid = parse(&["...#...", "...#...", "...#..."]);
+        let rules = GridMovement::new(&grid, |c: &char| *c == '.');
+        assert_eq!(astar_with(&rules, (1, 0), (1, 6)), None);
+
+        let portals = Portals::new(rules, &[((0, 2), (2, 4), 1.0), ((2, 4), (0, 2), 1.0)]);
+        let path = astar_with(&portals, (1, 0), (1, 6)).unwrap();
+        assert!(path.windows(2).any(|step| step == [(0, 2), (2, 4)]));
+        assert_eq!(bfs_with(&portals, (1, 6), (1, 0)).unwrap().len(), 6);
+
+        let map = distance_map_with(&portals, grid.bounds(), &[(0, 2)]);
+        assert_eq!(map.get(2, 4), Some(&1.0));
+    }
+}
@@ -0,0 +1,137 @@
+use super::is_open;
+use crate::direction::Direction;
+use crate::grid::GridRead;
+
+// Whether the straight segment between the centers of `a` and `b` only
+// crosses passable cells. A segment passing exactly through a corner needs
+// both cells beside the corner, in line with the no corner cutting rule.
+pub fn line_of_sight<T, R, F>(grid: &R, a: (isize, isize), b: (isize, isize), passable: F) -> bool
+where
+    R: GridRead<T> + ?Sized,
+    F: Fn(&T) -> bool,
+{
+    let open = |position| is_open(grid, &passable, position);
+    open(a) && segment_clear(&open, a, b)
+}
+
+// Walks the cells the segment from `a` to `b` crosses after `a`, with the
+// same corner rule, checking each one including `b`.
+pub(crate) fn segment_clear<O>(open: &O, a: (isize, isize), b: (isize, isize)) -> bool
+where
+    O: Fn((isize, isize)) -> bool,
+{
+    let (nx, ny) = (a.0.abs_diff(b.0) as isize, a.1.abs_diff(b.1) as isize);
+    let (sx, sy) = ((b.0 - a.0).signum(), (b.1 - a.1).signum());
+    let (mut x, mut y) = a;
+    let (mut ix, mut iy) = (0, 0);
+
+    while ix < nx || iy < ny {
+        let decision = (1 + 2 * ix) * ny - (1 + 2 * iy) * nx;
+        if decision == 0 {
+            if !open((x + sx, y)) || !open((x, y + sy)) {
+                return false;
+            }
+            x += sx;
+            y += sy;
+            ix += 1;
+            iy += 1;
+        } else if decision < 0 {
+            x += sx;
+            ix += 1;
+        } else {
+            y += sy;
+            iy += 1;
+        }
+
+        if !open((x, y)) {
+            return false;
+        }
+    }
+
+    true
+}
+
+// String pulling: drops every cell of `path` that the previous kept cell can
+// see past, leaving the waypoints of an any-angle path.
+pub fn simplify_path<T, R, F>(grid: &R, path: &[(isize, isize)], passable: F) -> Vec<(isize, isize)>
+where
+    R: GridRead<T> + ?Sized,
+    F: Fn(&T) -> bool,
+{
+    let Some((&first, rest)) = path.split_first() else {
+        return vec![];
+    };
+
+    let mut simplified = vec![first];
+    for (i, &cell) in rest.iter().enumerate().skip(1) {
+        let anchor = *simplified.last().unwrap();
+        if !line_of_sight(grid, anchor, cell, &passable) {
+            simplified.push(rest[i - 1]);
+        }
+    }
+    if let Some(&last) = rest.last() {
+        simplified.push(last);
+    }
+
+    simplified
+}
+
+// The moves between consecutive cells, or None if two of them are not
+// neighbors.
+pub fn path_to_directions(path: &[(isize, isize)]) -> Option<Vec<Direction>> {
+    path.windows(2)
+        .map(|step| Direction::from_offset(step[1].0 - step[0].0, step[1].1 - step[0].1))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{line_of_sight, path_to_directions, simplify_path};
+    use crate::direction::Direction;
+    use crate::grid::Grid;
+    use crate::path::astar;
+
+    fn parse(rows: &[&str]) -> Grid<char> {
+        let mut grid = Grid::new();
+        for (x, row) in rows.iter().enumerate() {
+            for (y, cell) in row.chars().enumerate() {
+                grid.set(x as isize, y as isize, cell);
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn pulls_strings_around_corners() {
+        let grid = parse(&[".......", ".......", "...#...", "...#...", "......."]);
+        let open = |c: &char| *c == '.';
+
+        assert!(line_of_sight(&grid, (0, 0), (1, 6), open));
+        assert!(!line_of_sight(&grid, (4, 0), (2, 6), open));
+        assert!(!line_of_sight(&grid, (3, 2), (2, 3), open));
+
+        let path = astar(&grid, (3, 0), (3, 6), open).unwrap();
+        let simplified = simplify_path(&grid, &path, open);
+        assert_eq!(simplified.first(), Some(&(3, 0)));
+        assert_eq!(simplified.last(), Some(&(3, 6)));
+        assert!(simplified.len() < path.len());
+        assert!(simplified
+            .windows(2)
+            .all(|pair| line_of_sight(&grid, pair[0], pair[1], open)));
+    }
+
+    #[test]
+    fn directions_between_cells() {
+        let path = [(0, 0), (-1, 0), (-1, 1), (0, 2)];
+        assert_eq!(
+            path_to_directions(&path),
+            Some(vec![
+                Direction::North,
+                Direction::East,
+                Direction::SouthEast
+            ])
+        );
+        assert_eq!(path_to_directions(&[(0, 0), (0, 2)]), None);
+        assert_eq!(path_to_directions(&[(0, 0)]), Some(vec![]));
+    }
+}
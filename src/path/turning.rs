@@ -0,0 +1,236 @@
+use super::{can_step, is_open, octile, Node};
+use crate::direction::Direction;
+use crate::grid::GridRead;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::f64::consts::SQRT_2;
+
+// A cell and the direction the mover faces on it.
+type State = ((isize, isize), Direction);
+
+// What turning costs a mover, for `turning_path`. `run` is how many steps
+// the mover has just taken straight ahead facing `facing`, so rules can ask
+// for a minimum or maximum straight run as crucible-style puzzles do.
+// Closures `Fn(facing, direction) -> Option<f64>` are rules ignoring runs.
+pub trait TurnRule {
+    // The extra cost of stepping towards `direction`, which is `facing` for
+    // a step straight ahead; None forbids the step. Costs must not be
+    // negative.
+    fn turn_cost(&self, facing: Direction, direction: Direction, run: usize) -> Option<f64>;
+
+    // Whether the mover may take another step straight ahead.
+    fn can_continue(&self, _facing: Direction, _run: usize) -> bool {
+        true
+    }
+
+    // Runs this long or longer are all treated alike, which keeps the search
+    // finite; 0 for rules that ignore runs.
+    fn longest_run(&self) -> usize {
+        0
+    }
+}
+
+impl<C: Fn(Direction, Direction) -> Option<f64>> TurnRule for C {
+    fn turn_cost(&self, facing: Direction, direction: Direction, _run: usize) -> Option<f64> {
+        self(facing, direction)
+    }
+}
+
+// A rule that only turns after at least `min` steps straight ahead and never
+// goes more than `max` steps straight, with the costs of `turns` otherwise.
+#[derive(Clone, Copy, Debug)]
+pub struct StraightRuns<C> {
+    pub min: usize,
+    pub max: usize,
+    pub turns: C,
+}
+
+impl<C: TurnRule> TurnRule for StraightRuns<C> {
+    fn turn_cost(&self, facing: Direction, direction: Direction, run: usize) -> Option<f64> {
+        if direction != facing && run < self.min {
+            return None;
+        }
+        self.turns.turn_cost(facing, direction, run)
+    }
+
+    fn can_continue(&self, facing: Direction, run: usize) -> bool {
+        run < self.max && self.turns.can_continue(facing, run)
+    }
+
+    fn longest_run(&self) -> usize {
+        // Without a maximum, runs past the minimum are all alike.
+        let longest = if self.max == usize::MAX {
+            self.min
+        } else {
+            self.max
+        };
+        longest.max(self.turns.longest_run())
+    }
+}
+
+// A* over (cell, facing, run) states for movers that pay for turning or
+// cannot turn freely. Each step moves to a neighbor and leaves the mover
+// facing the way it moved; it costs its length plus the rule's turn cost, so
+// forbidding diagonal directions limits the mover to four and forbidding
+// `facing.opposite()` rules out reversing. The mover starts on a run of 0,
+// so with a minimum run it first goes straight ahead. Returns the (cell,
+// facing) states from `start` to the first one reaching `goal`, with any
+// facing.
+pub fn turning_path<T, R, F, C>(
+    grid: &R,
+    start: State,
+    goal: (isize, isize),
+    passable: F,
+    rule: C,
+) -> Option<Vec<State>>
+where
+    R: GridRead<T> + ?Sized,
+    F: Fn(&T) -> bool,
+    C: TurnRule,
+{
+    let open = |position| is_open(grid, &passable, position);
+    if !open(start.0) || !open(goal) {
+        return None;
+    }
+
+    // Runs are capped at the longest the rule tells apart.
+    let longest = rule.longest_run();
+    let start = (start.0, start.1, 0);
+    let mut heap = BinaryHeap::new();
+    let mut closed = HashSet::new();
+    let mut costs = HashMap::new();
+    let mut parents = HashMap::new();
+
+    costs.insert(start, 0.0);
+    heap.push(Node {
+        priority: octile(start.0, goal),
+        position: start,
+    });
+
+    while let Some(Node { position, .. }) = heap.pop() {
+        let state = position;
+        let ((x, y), facing, run) = state;
+        if (x, y) == goal {
+            let mut path = vec![state];
+            while let Some(&parent) = parents.get(path.last().unwrap()) {
+                path.push(parent);
+            }
+            path.reverse();
+            return Some(
+                path.into_iter()
+                    .map(|(cell, facing, _)| (cell, facing))
+                    .collect(),
+            );
+        }
+        if !closed.insert(state) {
+            continue;
+        }
+
+        let cost = costs[&state];
+        for direction in Direction::ALL {
+            let (dx, dy) = direction.offset();
+            if !can_step(&open, (x, y), dx, dy) {
+                continue;
+            }
+            let straight = direction == facing;
+            if straight && !rule.can_continue(facing, run) {
+                continue;
+            }
+            let Some(turn) = rule.turn_cost(facing, direction, run) else {
+                continue;
+            };
+            let length = if direction.is_diagonal() { SQRT_2 } else { 1.0 };
+            let next_run = if straight { run.saturating_add(1) } else { 1 };
+            let next = ((x + dx, y + dy), direction, next_run.min(longest));
+            let next_cost = cost + length + turn;
+            if costs.get(&next).is_none_or(|&known| next_cost < known) {
+                costs.insert(next, next_cost);
+                parents.insert(next, state);
+                heap.push(Node {
+                    priority: next_cost + octile(next.0, goal),
+                    position: next,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{turning_path, StraightRuns};
+    use crate::direction::Direction;
+    use crate::grid::Grid;
+    use crate::rect::Rect;
+
+    #[test]
+    fn turns_cost_and_can_be_forbidden() {
+        let mut grid = Grid::new();
+        grid.fill_rect(Rect::new(0, 0, 4, 4), true);
+
+        // Four directions, two units per quarter turn and no reversing.
+        let robot = |facing: Direction, to: Direction| match facing.turns_to(to) {
+            0 => Some(0.0),
+            2 => Some(2.0),
+            _ => None,
+        };
+        let path = turning_path(
+            &grid,
+            ((0, 0), Direction::East),
+            (4, 4),
+            |&open| open,
+            robot,
+        )
+        .unwrap();
+        let turns = path
+            .windows(2)
+            .filter(|step| step[0].1 != step[1].1)
+            .count();
+        assert_eq!(path.len(), 9);
+        assert_eq!(turns, 1);
+        assert_eq!(path.last().map(|state| state.0), Some((4, 4)));
+
+        // Facing East on a dead-end row, the robot cannot turn back.
+        let mut row = Grid::new();
+        row.fill_rect(Rect::new(0, 0, 0, 3), true);
+        assert_eq!(
+            turning_path(&row, ((0, 3), Direction::East), (0, 0), |&open| open, robot),
+            None
+        );
+        let free = |_: Direction, _: Direction| Some(0.0);
+        assert_eq!(
+            turning_path(&row, ((0, 3), Direction::East), (0, 0), |&open| open, free)
+                .map(|path| path.len()),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn limits_straight_runs() {
+        let mut grid = Grid::new();
+        grid.fill_rect(Rect::new(0, 0, 0, 9), true);
+        let forward = |facing: Direction, to: Direction| (to != facing.opposite()).then_some(0.0);
+        let runs = |min, max| StraightRuns {
+            min,
+            max,
+            turns: forward,
+        };
+        let start = ((0, 0), Direction::East);
+        let path = |rule| turning_path(&grid, start, (0, 9), |&open| open, rule);
+        assert_eq!(path(runs(0, 9)).map(|path| path.len()), Some(10));
+        assert_eq!(path(runs(0, 8)), None);
+
+        // At least four steps before each turn: down the open field and back
+        // only works with a long enough first leg.
+        let mut field = Grid::new();
+        field.fill_rect(Rect::new(0, 0, 5, 0), true);
+        field.fill_rect(Rect::new(5, 0, 5, 5), true);
+        let start = ((0, 0), Direction::South);
+        let straight = |rule| turning_path(&field, start, (5, 5), |&open| open, rule);
+        let four = straight(runs(4, usize::MAX)).unwrap();
+        assert_eq!(four.len(), 11);
+        assert!(four.iter().all(|(_, facing)| !facing.is_diagonal()));
+        assert_eq!(straight(runs(6, usize::MAX)), None);
+        assert!(straight(runs(4, 4)).is_none());
+    }
+}
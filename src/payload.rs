@@ -0,0 +1,200 @@
+use crate::error::GridError;
+use crate::grid::Grid;
+use crate::rect::Rect;
+use serde::{Deserialize, Serialize};
+use std::io;
+
+// A self-describing grid layout for MessagePack, CBOR and other serde
+// formats, written as a map so other languages can read it without this
+// crate:
+//
+//   { "layout": "dense",  "bounds": [min_x, min_y, max_x, max_y],
+//     "cells": [value or nil, ...] }
+//   { "layout": "sparse", "bounds": [min_x, min_y, max_x, max_y],
+//     "cells": [[x, y, value], ...] }
+//
+// Dense cells cover every position of the bounds in row-major order, x
+// outermost, with nil for empty cells. Sparse cells list only the stored
+// positions. The bounds are always explicit, so empty edges survive.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "layout", rename_all = "lowercase")]
+pub enum Payload<T> {
+    Dense {
+        bounds: [isize; 4],
+        cells: Vec<Option<T>>,
+    },
+    Sparse {
+        bounds: [isize; 4],
+        cells: Vec<(isize, isize, T)>,
+    },
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn coordinates(bounds: Rect) -> [isize; 4] {
+    [bounds.min_x, bounds.min_y, bounds.max_x, bounds.max_y]
+}
+
+impl<T: Clone> Grid<T> {
+    // Dense once a quarter of the bounds is filled: a sparse cell repeats its
+    // coordinates, an empty dense cell takes a single byte.
+    pub fn to_payload(&self) -> Payload<T> {
+        let bounds = self.bounds();
+        if self.len() * 4 >= bounds.area() {
+            Payload::Dense {
+                bounds: coordinates(bounds),
+                cells: bounds
+                    .iter()
+                    .map(|(x, y)| self.get(x, y).cloned())
+                    .collect(),
+            }
+        } else {
+            Payload::Sparse {
+                bounds: coordinates(bounds),
+                cells: self
+                    .iter()
+                    .map(|((x, y), item)| (x, y, item.clone()))
+                    .collect(),
+            }
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_payload(payload: Payload<T>) -> io::Result<Grid<T>> {
+        let ([min_x, min_y, max_x, max_y], cells) = match &payload {
+            Payload::Dense { bounds, cells } => (*bounds, cells.len()),
+            Payload::Sparse { bounds, cells } => (*bounds, cells.len()),
+        };
+        if min_x > max_x || min_y > max_y {
+            return Err(invalid("payload bounds are inverted"));
+        }
+        let bounds = Rect::new(min_x, min_y, max_x, max_y);
+
+        let mut grid =
+            Grid::try_with_bounds(bounds).map_err(|_| invalid("payload bounds are too large"))?;
+        let stored = |error: GridError| invalid(&error.to_string());
+        match payload {
+            Payload::Dense { cells: values, .. } => {
+                if cells != bounds.area() {
+                    return Err(invalid("dense payload does not cover its bounds"));
+                }
+                for ((x, y), item) in bounds.iter().zip(values) {
+                    if let Some(item) = item {
+                        grid.try_set(x, y, item).map_err(stored)?;
+                    }
+                }
+            }
+            Payload::Sparse { cells: values, .. } => {
+                for (x, y, item) in values {
+                    if !bounds.contains(x, y) {
+                        return Err(invalid("sparse cell outside the payload bounds"));
+                    }
+                    grid.try_set(x, y, item).map_err(stored)?;
+                }
+            }
+        }
+
+        Ok(grid)
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl<T: Clone + Serialize + serde::de::DeserializeOwned> Grid<T> {
+    pub fn to_msgpack(&self) -> Vec<u8> {
+        rmp_serde::to_vec_named(&self.to_payload()).expect("grid payloads always serialize")
+    }
+
+    pub fn from_msgpack(bytes: &[u8]) -> io::Result<Grid<T>> {
+        let payload = rmp_serde::from_slice(bytes)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        Grid::from_payload(payload)
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl<T: Clone + Serialize + serde::de::DeserializeOwned> Grid<T> {
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        ciborium::into_writer(&self.to_payload(), &mut bytes)
+            .expect("grid payloads always serialize");
+        bytes
+    }
+
+    pub fn from_cbor(bytes: &[u8]) -> io::Result<Grid<T>> {
+        let payload = ciborium::from_reader(bytes)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        Grid::from_payload(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Payload;
+    use crate::grid::Grid;
+
+    fn sample(fill: bool) -> Grid<u16> {
+        let mut grid = Grid::new();
+        grid.set(-2, 5, 9);
+        grid.set(4, -1, 12);
+        if fill {
+            for (x, y) in grid.bounds().iter() {
+                if (x + y) % 2 == 0 {
+                    grid.set(x, y, 1);
+                }
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn selects_the_smaller_layout() {
+        assert!(matches!(sample(false).to_payload(), Payload::Sparse { .. }));
+        assert!(matches!(sample(true).to_payload(), Payload::Dense { .. }));
+
+        for grid in [sample(false), sample(true)] {
+            let restored = Grid::from_payload(grid.to_payload()).unwrap();
+            assert_eq!(restored.bounds(), grid.bounds());
+            assert!(restored.iter().eq(grid.iter()));
+        }
+
+        let outside = Payload::Sparse {
+            bounds: [0, 0, 1, 1],
+            cells: vec![(2, 0, 1)],
+        };
+        assert!(Grid::from_payload(outside).is_err());
+
+        let wide = Payload::Sparse {
+            bounds: [isize::MIN, 0, isize::MAX, 0],
+            cells: vec![(0, 0, 1)],
+        };
+        assert!(Grid::from_payload(wide).is_err());
+        let far = Payload::Sparse {
+            bounds: [0, 0, isize::MAX / 2, 0],
+            cells: vec![(isize::MAX / 2, 0, 1)],
+        };
+        assert!(Grid::from_payload(far).is_err());
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn round_trips_msgpack() {
+        for grid in [sample(false), sample(true)] {
+            let restored = Grid::<u16>::from_msgpack(&grid.to_msgpack()).unwrap();
+            assert_eq!(restored.bounds(), grid.bounds());
+            assert!(restored.iter().eq(grid.iter()));
+        }
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn round_trips_cbor() {
+        for grid in [sample(false), sample(true)] {
+            let restored = Grid::<u16>::from_cbor(&grid.to_cbor()).unwrap();
+            assert_eq!(restored.bounds(), grid.bounds());
+            assert!(restored.iter().eq(grid.iter()));
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use crate::grid::Grid;
+use plotters::coord::Shift;
+use plotters::element::Rectangle;
+use plotters::prelude::{Color, ColorMap, DrawingArea, DrawingAreaErrorKind, DrawingBackend};
+
+impl<T: Copy + Into<f64>> Grid<T> {
+    // Fills `area` with one rectangle per stored cell, the bounds stretched
+    // over the whole area with rows (x) running down it. Values are scaled
+    // between the smallest and largest stored value; empty cells are left
+    // unpainted. Use `DrawingArea::margin` or `split_*` to leave room for labels.
+    pub fn plot_heatmap<DB: DrawingBackend, C: Color, M: ColorMap<C, f64>>(
+        &self,
+        area: &DrawingArea<DB, Shift>,
+        colormap: &M,
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+        let bounds = self.bounds();
+        let (width, height) = area.dim_in_pixel();
+        let column_width = width as f64 / bounds.width() as f64;
+        let row_height = height as f64 / bounds.height() as f64;
+
+        let (min, max) = self
+            .iter()
+            .map(|(_, &value)| value.into())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+                (min.min(value), max.max(value))
+            });
+        // A flat grid all maps to the low end.
+        let max = if max > min { max } else { min + 1.0 };
+
+        for ((x, y), &value) in self.iter() {
+            let row = (x - bounds.min_x) as f64;
+            let column = (y - bounds.min_y) as f64;
+            let corner = |row: f64, column: f64| {
+                (
+                    (column * column_width).round() as i32,
+                    (row * row_height).round() as i32,
+                )
+            };
+            let color = colormap.get_color_normalized(value.into(), min, max);
+            area.draw(&Rectangle::new(
+                [corner(row, column), corner(row + 1.0, column + 1.0)],
+                color.filled(),
+            ))?;
+        }
+
+        area.present()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::Grid;
+    use plotters::prelude::{IntoDrawingArea, SVGBackend, ViridisRGB};
+
+    #[test]
+    fn draws_one_rectangle_per_cell() {
+        let mut grid = Grid::new();
+        for y in 0..4 {
+            grid.set(1, y, y as f32);
+        }
+        grid.set(3, 5, 10.0);
+
+        let mut svg = String::new();
+        {
+            let area = SVGBackend::with_string(&mut svg, (60, 40)).into_drawing_area();
+            grid.plot_heatmap(&area, &ViridisRGB).unwrap();
+        }
+
+        assert_eq!(svg.matches("<rect").count(), 5);
+        // Rows are 10 pixels tall and columns 10 wide.
+        assert!(svg.contains(r#"x="50" y="30" width="10" height="10""#));
+    }
+}
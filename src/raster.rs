@@ -0,0 +1,326 @@
+use crate::grid::Grid;
+use crate::rect::Rect;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Read, Seek};
+
+// Raster images load with the top image row at x = 0 and the leftmost column
+// at y = 0, the way they are stored on disk.
+
+// An affine map from cell positions to world coordinates, with the same six
+// coefficients as a GDAL geotransform. The world position of a cell's top
+// left corner is
+//   world_x = origin_x + column * pixel_width + row * row_rotation
+//   world_y = origin_y + column * column_rotation + row * pixel_height
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeoTransform {
+    pub origin_x: f64,
+    pub pixel_width: f64,
+    pub row_rotation: f64,
+    pub origin_y: f64,
+    pub column_rotation: f64,
+    pub pixel_height: f64,
+}
+
+impl Default for GeoTransform {
+    fn default() -> Self {
+        Self {
+            origin_x: 0.0,
+            pixel_width: 1.0,
+            row_rotation: 0.0,
+            origin_y: 0.0,
+            column_rotation: 0.0,
+            pixel_height: 1.0,
+        }
+    }
+}
+
+impl GeoTransform {
+    pub fn to_world(&self, x: isize, y: isize) -> (f64, f64) {
+        let (row, column) = (x as f64, y as f64);
+        (
+            self.origin_x + column * self.pixel_width + row * self.row_rotation,
+            self.origin_y + column * self.column_rotation + row * self.pixel_height,
+        )
+    }
+}
+
+// A loaded heightmap. Cells holding the nodata value are left empty, but the
+// grid bounds always cover the whole raster.
+#[derive(Clone)]
+pub struct Raster {
+    pub grid: Grid<f32>,
+    pub transform: GeoTransform,
+    pub nodata: Option<f32>,
+}
+
+#[derive(Debug)]
+pub enum RasterError {
+    Io(io::Error),
+    Png(png::DecodingError),
+    InvalidHeader(String),
+    InvalidValue(String),
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for RasterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RasterError::Io(error) => write!(f, "{error}"),
+            RasterError::Png(error) => write!(f, "{error}"),
+            RasterError::InvalidHeader(header) => write!(f, "invalid raster header: {header}"),
+            RasterError::InvalidValue(value) => write!(f, "invalid raster value: {value}"),
+            RasterError::Unsupported(what) => write!(f, "unsupported raster format: {what}"),
+        }
+    }
+}
+
+impl Error for RasterError {}
+
+impl From<io::Error> for RasterError {
+    fn from(error: io::Error) -> Self {
+        RasterError::Io(error)
+    }
+}
+
+impl From<png::DecodingError> for RasterError {
+    fn from(error: png::DecodingError) -> Self {
+        RasterError::Png(error)
+    }
+}
+
+// The sample layout of headerless raw heightmaps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RawFormat {
+    U16Le,
+    U16Be,
+    F32Le,
+    F32Be,
+}
+
+impl RawFormat {
+    fn sample_size(self) -> usize {
+        match self {
+            RawFormat::U16Le | RawFormat::U16Be => 2,
+            RawFormat::F32Le | RawFormat::F32Be => 4,
+        }
+    }
+
+    fn decode(self, bytes: &[u8]) -> f32 {
+        match self {
+            RawFormat::U16Le => u16::from_le_bytes([bytes[0], bytes[1]]) as f32,
+            RawFormat::U16Be => u16::from_be_bytes([bytes[0], bytes[1]]) as f32,
+            RawFormat::F32Le => f32::from_le_bytes(bytes.try_into().unwrap()),
+            RawFormat::F32Be => f32::from_be_bytes(bytes.try_into().unwrap()),
+        }
+    }
+}
+
+fn raster(
+    rows: usize,
+    columns: usize,
+    values: impl IntoIterator<Item = f32>,
+    transform: GeoTransform,
+    nodata: Option<f32>,
+) -> Raster {
+    let mut grid = Grid::with_bounds(Rect::new(0, 0, rows as isize - 1, columns as isize - 1));
+    for (i, value) in values.into_iter().enumerate() {
+        if Some(value) != nodata {
+            grid.set((i / columns) as isize, (i % columns) as isize, value);
+        }
+    }
+
+    Raster {
+        grid,
+        transform,
+        nodata,
+    }
+}
+
+// Reads an ESRI ASCII grid. World y grows northwards there, so the transform
+// has a negative pixel height.
+pub fn read_asc(mut reader: impl BufRead) -> Result<Raster, RasterError> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let mut tokens = text.split_whitespace().peekable();
+
+    let (mut columns, mut rows, mut cell_size, mut nodata) = (None, None, None, None);
+    let (mut left, mut bottom, mut centered) = (None, None, false);
+    while let Some(key) = tokens.next_if(|token| token.starts_with(char::is_alphabetic)) {
+        let value = tokens
+            .next()
+            .ok_or_else(|| RasterError::InvalidHeader(format!("{key} has no value")))?;
+        let number = || {
+            value
+                .parse::<f64>()
+                .map_err(|_| RasterError::InvalidHeader(format!("{key} {value}")))
+        };
+        let count = || {
+            value
+                .parse::<usize>()
+                .ok()
+                .filter(|&count| count > 0)
+                .ok_or_else(|| RasterError::InvalidHeader(format!("{key} {value}")))
+        };
+
+        match key.to_ascii_lowercase().as_str() {
+            "ncols" => columns = Some(count()?),
+            "nrows" => rows = Some(count()?),
+            "cellsize" => cell_size = Some(number()?),
+            "nodata_value" => nodata = Some(number()? as f32),
+            "xllcorner" => left = Some(number()?),
+            "yllcorner" => bottom = Some(number()?),
+            "xllcenter" => (left, centered) = (Some(number()?), true),
+            "yllcenter" => (bottom, centered) = (Some(number()?), true),
+            _ => return Err(RasterError::InvalidHeader(key.to_string())),
+        }
+    }
+
+    let missing = |name: &str| RasterError::InvalidHeader(format!("missing {name}"));
+    let columns = columns.ok_or_else(|| missing("ncols"))?;
+    let rows = rows.ok_or_else(|| missing("nrows"))?;
+    let cell_size = cell_size.ok_or_else(|| missing("cellsize"))?;
+    let mut left = left.ok_or_else(|| missing("xllcorner"))?;
+    let mut bottom = bottom.ok_or_else(|| missing("yllcorner"))?;
+    if centered {
+        left -= cell_size / 2.0;
+        bottom -= cell_size / 2.0;
+    }
+
+    let values = tokens
+        .map(|token| {
+            token
+                .parse::<f32>()
+                .map_err(|_| RasterError::InvalidValue(token.to_string()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let cells = rows
+        .checked_mul(columns)
+        .filter(|&cells| cells > 0)
+        .ok_or_else(|| RasterError::InvalidHeader(format!("{columns}x{rows}")))?;
+    if values.len() != cells {
+        return Err(RasterError::InvalidValue(format!(
+            "expected {cells} cells, found {}",
+            values.len()
+        )));
+    }
+
+    let transform = GeoTransform {
+        origin_x: left,
+        pixel_width: cell_size,
+        origin_y: bottom + rows as f64 * cell_size,
+        pixel_height: -cell_size,
+        ..GeoTransform::default()
+    };
+    Ok(raster(rows, columns, values, transform, nodata))
+}
+
+// Reads `width` by `height` samples in row-major order with no header.
+pub fn read_raw(
+    reader: impl Read,
+    width: usize,
+    height: usize,
+    format: RawFormat,
+) -> Result<Raster, RasterError> {
+    let len = width
+        .checked_mul(height)
+        .and_then(|cells| cells.checked_mul(format.sample_size()))
+        .filter(|&len| len > 0)
+        .ok_or_else(|| RasterError::InvalidHeader(format!("{width}x{height}")))?;
+
+    // Only the bytes actually read are allocated, however large the size.
+    let mut bytes = vec![];
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+    }
+    let values = bytes
+        .chunks_exact(format.sample_size())
+        .map(|sample| format.decode(sample));
+    Ok(raster(height, width, values, GeoTransform::default(), None))
+}
+
+// Reads an 8 or 16 bit grayscale PNG, keeping the raw sample values.
+pub fn read_png(reader: impl BufRead + Seek) -> Result<Raster, RasterError> {
+    let mut reader = png::Decoder::new(reader).read_info()?;
+    let mut buffer = vec![
+        0;
+        reader
+            .output_buffer_size()
+            .ok_or(RasterError::Unsupported("image too large"))?
+    ];
+    let info = reader.next_frame(&mut buffer)?;
+    if info.color_type != png::ColorType::Grayscale {
+        return Err(RasterError::Unsupported(
+            "only grayscale images are heightmaps",
+        ));
+    }
+
+    let bytes = &buffer[..info.buffer_size()];
+    let (width, height) = (info.width as usize, info.height as usize);
+    let values: Vec<f32> = match info.bit_depth {
+        png::BitDepth::Eight => bytes.iter().map(|&sample| sample as f32).collect(),
+        png::BitDepth::Sixteen => bytes
+            .chunks_exact(2)
+            .map(|sample| u16::from_be_bytes([sample[0], sample[1]]) as f32)
+            .collect(),
+        _ => return Err(RasterError::Unsupported("bit depths below 8")),
+    };
+    Ok(raster(height, width, values, GeoTransform::default(), None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_asc, read_png, read_raw, RasterError, RawFormat};
+
+    #[test]
+    fn reads_ascii_grids() {
+        let asc = "ncols 3\nnrows 2\nxllcorner 100.0\nyllcorner 50.0\ncellsize 10\n\
+                   NODATA_value -9999\n1 2 3\n4 -9999 6\n";
+        let raster = read_asc(asc.as_bytes()).unwrap();
+
+        assert_eq!(raster.grid.bounds().width(), 3);
+        assert_eq!(raster.grid.bounds().height(), 2);
+        assert_eq!(raster.grid.get(0, 2), Some(&3.0));
+        assert_eq!(raster.grid.get(1, 1), None);
+        assert_eq!(raster.nodata, Some(-9999.0));
+        assert_eq!(raster.transform.to_world(0, 0), (100.0, 70.0));
+        assert_eq!(raster.transform.to_world(2, 3), (130.0, 50.0));
+
+        assert!(read_asc("ncols 2\nnrows 2\n1 2 3".as_bytes()).is_err());
+        let huge = "ncols 4294967296\nnrows 4294967296\nxllcorner 0\nyllcorner 0\ncellsize 1\n1";
+        assert!(matches!(
+            read_asc(huge.as_bytes()),
+            Err(RasterError::InvalidHeader(_))
+        ));
+    }
+
+    #[test]
+    fn reads_raw_samples() {
+        let bytes = [1, 0, 0, 1, 255, 255, 2, 0];
+        let raster = read_raw(&bytes[..], 2, 2, RawFormat::U16Le).unwrap();
+        assert_eq!(raster.grid.get(0, 1), Some(&256.0));
+        assert_eq!(raster.grid.get(1, 0), Some(&65535.0));
+        assert!(read_raw(&bytes[..], 3, 2, RawFormat::U16Le).is_err());
+        assert!(matches!(
+            read_raw(&bytes[..], usize::MAX, 2, RawFormat::U16Le),
+            Err(RasterError::InvalidHeader(_))
+        ));
+        assert!(read_raw(&bytes[..], usize::MAX / 4, 1, RawFormat::U16Le).is_err());
+    }
+
+    #[test]
+    fn reads_sixteen_bit_pngs() {
+        let mut png = vec![];
+        let mut encoder = png::Encoder::new(&mut png, 2, 1);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Sixteen);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&[0x12, 0x34, 0xff, 0xfe]).unwrap();
+        writer.finish().unwrap();
+
+        let raster = read_png(std::io::Cursor::new(png)).unwrap();
+        assert_eq!(raster.grid.get(0, 0), Some(&4660.0));
+        assert_eq!(raster.grid.get(0, 1), Some(&65534.0));
+    }
+}
@@ -0,0 +1,165 @@
+use crate::grid::GridRead;
+use crate::rect::Rect;
+use std::io::{self, Write};
+
+// Captures grid states as RGBA frames of a fixed region and writes them out
+// as an animated GIF or APNG. Each cell becomes a `scale` by `scale` block
+// of pixels, rows (x) running down the image.
+pub struct Recorder {
+    region: Rect,
+    scale: usize,
+    delay_ms: u16,
+    background: [u8; 4],
+    frames: Vec<Vec<u8>>,
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+impl Recorder {
+    pub fn new(region: Rect) -> Self {
+        Self {
+            region,
+            scale: 1,
+            delay_ms: 100,
+            background: [0, 0, 0, 255],
+            frames: vec![],
+        }
+    }
+
+    pub fn with_scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    pub fn with_frame_delay(mut self, delay_ms: u16) -> Self {
+        self.delay_ms = delay_ms;
+        self
+    }
+
+    // The color of empty cells.
+    pub fn with_background(mut self, background: [u8; 4]) -> Self {
+        self.background = background;
+        self
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn width(&self) -> usize {
+        self.region.width() * self.scale
+    }
+
+    pub fn height(&self) -> usize {
+        self.region.height() * self.scale
+    }
+
+    pub fn capture<T, R: GridRead<T> + ?Sized>(&mut self, grid: &R, color: impl Fn(&T) -> [u8; 4]) {
+        let mut pixels = Vec::with_capacity(self.width() * self.height() * 4);
+        for x in self.region.min_x..=self.region.max_x {
+            let start = pixels.len();
+            for y in self.region.min_y..=self.region.max_y {
+                let rgba = grid.get(x, y).map_or(self.background, &color);
+                for _ in 0..self.scale {
+                    pixels.extend_from_slice(&rgba);
+                }
+            }
+            for _ in 1..self.scale {
+                pixels.extend_from_within(start..start + self.width() * 4);
+            }
+        }
+        self.frames.push(pixels);
+    }
+
+    // GIF has one bit of transparency and 256 colors per frame, so colors
+    // are quantized and alpha is rounded.
+    pub fn write_gif(&self, writer: impl Write) -> io::Result<()> {
+        let (width, height) = match (u16::try_from(self.width()), u16::try_from(self.height())) {
+            (Ok(width), Ok(height)) => (width, height),
+            _ => return Err(invalid("GIF frames are limited to 65535 pixels a side")),
+        };
+
+        let mut encoder =
+            gif::Encoder::new(writer, width, height, &[]).map_err(io::Error::other)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(io::Error::other)?;
+        for pixels in &self.frames {
+            let mut frame = gif::Frame::from_rgba_speed(width, height, &mut pixels.clone(), 10);
+            // GIF delays count hundredths of a second.
+            frame.delay = self.delay_ms / 10;
+            encoder.write_frame(&frame).map_err(io::Error::other)?;
+        }
+        Ok(())
+    }
+
+    pub fn write_apng(&self, writer: impl Write) -> io::Result<()> {
+        if self.frames.is_empty() {
+            return Err(invalid("no frames captured"));
+        }
+        let (width, height) = match (u32::try_from(self.width()), u32::try_from(self.height())) {
+            (Ok(width), Ok(height)) => (width, height),
+            _ => return Err(invalid("frame too large")),
+        };
+
+        let mut encoder = png::Encoder::new(writer, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_animated(self.frames.len() as u32, 0)?;
+        encoder.set_frame_delay(self.delay_ms, 1000)?;
+        let mut writer = encoder.write_header()?;
+        for pixels in &self.frames {
+            writer.write_image_data(pixels)?;
+        }
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Recorder;
+    use crate::grid::Grid;
+    use crate::rect::Rect;
+
+    fn recording() -> Recorder {
+        let mut recorder = Recorder::new(Rect::new(0, 0, 3, 4)).with_scale(2);
+        let mut grid = Grid::new();
+        for step in 0..3 {
+            grid.set(step, step, true);
+            recorder.capture(&grid, |_| [255, 0, 0, 255]);
+        }
+        recorder
+    }
+
+    #[test]
+    fn scales_cells_into_pixels() {
+        let recorder = recording();
+        assert_eq!((recorder.width(), recorder.height()), (10, 8));
+        assert_eq!(recorder.frame_count(), 3);
+
+        let frame = &recorder.frames[2];
+        assert_eq!(frame.len(), 10 * 8 * 4);
+        let pixel = |row: usize, column: usize| &frame[(row * 10 + column) * 4..][..4];
+        assert_eq!(pixel(3, 3), [255, 0, 0, 255]);
+        assert_eq!(pixel(3, 4), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn writes_animations() {
+        let recorder = recording();
+
+        let mut gif = vec![];
+        recorder.write_gif(&mut gif).unwrap();
+        assert!(gif.starts_with(b"GIF89a"));
+
+        let mut apng = vec![];
+        recorder.write_apng(&mut apng).unwrap();
+        let reader = png::Decoder::new(std::io::Cursor::new(apng))
+            .read_info()
+            .unwrap();
+        assert_eq!(reader.info().animation_control.unwrap().num_frames, 3);
+    }
+}
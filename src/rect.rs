@@ -0,0 +1,122 @@
+// An inclusive rectangle of grid coordinates. Following the grid's layout, the
+// height spans rows (x) and the width spans columns (y). Sizes saturate at
+// usize::MAX for rectangles spanning (nearly) the whole coordinate space.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect {
+    pub min_x: isize,
+    pub min_y: isize,
+    pub max_x: isize,
+    pub max_y: isize,
+}
+
+impl Rect {
+    pub fn new(min_x: isize, min_y: isize, max_x: isize, max_y: isize) -> Self {
+        Self {
+            min_x: min_x.min(max_x),
+            min_y: min_y.min(max_y),
+            max_x: min_x.max(max_x),
+            max_y: min_y.max(max_y),
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.max_x.abs_diff(self.min_x).saturating_add(1)
+    }
+
+    pub fn width(&self) -> usize {
+        self.max_y.abs_diff(self.min_y).saturating_add(1)
+    }
+
+    pub fn area(&self) -> usize {
+        self.width().saturating_mul(self.height())
+    }
+
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        x >= self.min_x && x <= self.max_x && y >= self.min_y && y <= self.max_y
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.min_x <= other.max_x
+            && other.min_x <= self.max_x
+            && self.min_y <= other.max_y
+            && other.min_y <= self.max_y
+    }
+
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        self.intersects(other).then(|| Rect {
+            min_x: self.min_x.max(other.min_x),
+            min_y: self.min_y.max(other.min_y),
+            max_x: self.max_x.min(other.max_x),
+            max_y: self.max_y.min(other.max_y),
+        })
+    }
+
+    // The smallest rectangle covering both.
+    pub fn union(&self, other: &Rect) -> Rect {
+        Rect {
+            min_x: self.min_x.min(other.min_x),
+            min_y: self.min_y.min(other.min_y),
+            max_x: self.max_x.max(other.max_x),
+            max_y: self.max_y.max(other.max_y),
+        }
+    }
+
+    pub fn center(&self) -> (isize, isize) {
+        (
+            self.min_x
+                .saturating_add_unsigned(self.max_x.abs_diff(self.min_x) / 2),
+            self.min_y
+                .saturating_add_unsigned(self.max_y.abs_diff(self.min_y) / 2),
+        )
+    }
+
+    // Row-major: x ascending, then y ascending.
+    pub fn iter(&self) -> impl Iterator<Item = (isize, isize)> {
+        let Rect {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        } = *self;
+
+        (min_x..=max_x).flat_map(move |x| (min_y..=max_y).map(move |y| (x, y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rect;
+
+    #[test]
+    fn rect_geometry() {
+        let rect = Rect::new(2, 3, -1, 0);
+
+        assert_eq!(rect, Rect::new(-1, 0, 2, 3));
+        assert_eq!((rect.height(), rect.width(), rect.area()), (4, 4, 16));
+        assert!(rect.contains(-1, 3) && !rect.contains(3, 0));
+        assert!(rect.intersects(&Rect::new(2, 3, 5, 5)));
+        assert!(!rect.intersects(&Rect::new(3, 0, 5, 5)));
+        assert_eq!(
+            rect.intersection(&Rect::new(2, 3, 5, 5)),
+            Some(Rect::new(2, 3, 2, 3))
+        );
+        assert_eq!(rect.intersection(&Rect::new(3, 0, 5, 5)), None);
+        assert_eq!(rect.union(&Rect::new(3, 0, 5, 5)), Rect::new(-1, 0, 5, 5));
+        assert_eq!(rect.center(), (0, 1));
+        assert_eq!(rect.iter().count(), 16);
+        assert_eq!(rect.iter().nth(4), Some((0, 0)));
+    }
+
+    #[test]
+    fn extreme_rects() {
+        let everything = Rect::new(isize::MIN, isize::MIN, isize::MAX, isize::MAX);
+        assert_eq!(everything.height(), usize::MAX);
+        assert_eq!(everything.area(), usize::MAX);
+        assert_eq!(everything.center(), (-1, -1));
+
+        let corner = Rect::new(isize::MAX - 1, 0, isize::MAX, 0);
+        assert_eq!(corner.center(), (isize::MAX - 1, 0));
+        assert_eq!(corner.iter().count(), 2);
+    }
+}
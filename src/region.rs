@@ -0,0 +1,205 @@
+use crate::coordset::CoordSet;
+use crate::grid::Grid;
+use crate::rect::Rect;
+
+// An area of any shape for grid operations: a union of (possibly
+// overlapping) rects, such as the rooms of a floor, or a set of single
+// cells, such as a spell template. Rect unions keep the rect fast paths of
+// `fill_rect` and `clear_rect`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Region {
+    Rects(Vec<Rect>),
+    Cells(CoordSet),
+}
+
+impl Region {
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        match self {
+            Region::Rects(rects) => rects.iter().any(|rect| rect.contains(x, y)),
+            Region::Cells(cells) => cells.contains(x, y),
+        }
+    }
+
+    // Each cell once, in row-major order.
+    pub fn iter(&self) -> Box<dyn Iterator<Item = (isize, isize)> + '_> {
+        let rects = match self {
+            Region::Rects(rects) => rects,
+            Region::Cells(cells) => return Box::new(cells.iter()),
+        };
+        let Some(bounds) = self.bounds() else {
+            return Box::new(std::iter::empty());
+        };
+
+        Box::new((bounds.min_x..=bounds.max_x).flat_map(move |x| {
+            let mut spans: Vec<(isize, isize)> = rects
+                .iter()
+                .filter(|rect| rect.min_x <= x && x <= rect.max_x)
+                .map(|rect| (rect.min_y, rect.max_y))
+                .collect();
+            spans.sort_unstable();
+
+            let mut merged: Vec<(isize, isize)> = vec![];
+            for (min, max) in spans {
+                match merged.last_mut() {
+                    Some(last) if min <= last.1.saturating_add(1) => last.1 = last.1.max(max),
+                    _ => merged.push((min, max)),
+                }
+            }
+            merged
+                .into_iter()
+                .flat_map(move |(min, max)| (min..=max).map(move |y| (x, y)))
+        }))
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Region::Rects(_) => self.iter().count(),
+            Region::Cells(cells) => cells.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Region::Rects(rects) => rects.is_empty(),
+            Region::Cells(cells) => cells.is_empty(),
+        }
+    }
+
+    pub fn bounds(&self) -> Option<Rect> {
+        match self {
+            Region::Rects(rects) => rects.iter().copied().reduce(|a, b| a.union(&b)),
+            Region::Cells(cells) => cells.bounds(),
+        }
+    }
+}
+
+impl From<Rect> for Region {
+    fn from(rect: Rect) -> Self {
+        Region::Rects(vec![rect])
+    }
+}
+
+impl From<Vec<Rect>> for Region {
+    fn from(rects: Vec<Rect>) -> Self {
+        Region::Rects(rects)
+    }
+}
+
+impl From<CoordSet> for Region {
+    fn from(cells: CoordSet) -> Self {
+        Region::Cells(cells)
+    }
+}
+
+impl<T> Grid<T> {
+    // The stored cells inside `region`, in row-major order.
+    pub fn iter_region<'a>(
+        &'a self,
+        region: &'a Region,
+    ) -> impl Iterator<Item = ((isize, isize), &'a T)> + 'a {
+        region
+            .iter()
+            .filter_map(move |(x, y)| self.get(x, y).map(|item| ((x, y), item)))
+    }
+
+    pub fn count_region<F: Fn(&T) -> bool>(&self, region: &Region, predicate: F) -> usize {
+        self.iter_region(region)
+            .filter(|(_, item)| predicate(item))
+            .count()
+    }
+
+    // Removes every cell of `region`, returning how many were stored.
+    pub fn clear_region(&mut self, region: &Region) -> usize {
+        match region {
+            Region::Rects(rects) => rects.iter().map(|&rect| self.clear_rect(rect)).sum(),
+            Region::Cells(cells) => cells
+                .iter()
+                .filter(|&(x, y)| self.remove(x, y).is_some())
+                .count(),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn fill_region(&mut self, region: &Region, value: T) {
+        match region {
+            Region::Rects(rects) => {
+                for &rect in rects {
+                    self.fill_rect(rect, value.clone());
+                }
+            }
+            Region::Cells(cells) => {
+                for (x, y) in cells.iter() {
+                    self.set(x, y, value.clone());
+                }
+            }
+        }
+    }
+
+    // A new grid with only the cells inside `region`.
+    pub fn crop_region(&self, region: &Region) -> Grid<T> {
+        let mut grid = match region.bounds() {
+            Some(bounds) => Grid::with_bounds(bounds),
+            None => Grid::new(),
+        };
+        for ((x, y), item) in self.iter_region(region) {
+            grid.set(x, y, item.clone());
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Region;
+    use crate::coordset::CoordSet;
+    use crate::grid::Grid;
+    use crate::rect::Rect;
+
+    #[test]
+    fn iterates_overlapping_rects_once() {
+        let region = Region::from(vec![
+            Rect::new(0, 0, 1, 2),
+            Rect::new(1, 2, 2, 4),
+            Rect::new(0, 5, 0, 5),
+        ]);
+        let cells: Vec<_> = region.iter().collect();
+        assert_eq!(
+            cells,
+            [
+                (0, 0),
+                (0, 1),
+                (0, 2),
+                (0, 5),
+                (1, 0),
+                (1, 1),
+                (1, 2),
+                (1, 3),
+                (1, 4),
+                (2, 2),
+                (2, 3),
+                (2, 4)
+            ]
+        );
+        assert_eq!(region.len(), 12);
+        assert!(region.contains(2, 3) && !region.contains(2, 1));
+        assert_eq!(region.bounds(), Some(Rect::new(0, 0, 2, 5)));
+    }
+
+    #[test]
+    fn edits_irregular_areas() {
+        let mut grid = Grid::new();
+        grid.fill_rect(Rect::new(0, 0, 3, 3), 0);
+
+        let floor = Region::from(vec![Rect::new(0, 0, 1, 1), Rect::new(1, 1, 2, 2)]);
+        grid.fill_region(&floor, 1);
+        assert_eq!(grid.count_region(&floor, |&item| item == 1), 7);
+        assert_eq!(grid.iter().filter(|(_, &item)| item == 1).count(), 7);
+
+        let template: CoordSet = [(3, 3), (3, 0), (9, 9)].into_iter().collect();
+        let template = Region::from(template);
+        assert_eq!(grid.crop_region(&template).len(), 2);
+        assert_eq!(grid.clear_region(&template), 2);
+        assert_eq!(grid.len(), 14);
+    }
+}
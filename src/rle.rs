@@ -0,0 +1,208 @@
+use crate::grid::{Grid, GridRead, GridWrite};
+use crate::rect::Rect;
+use std::collections::BTreeMap;
+
+#[derive(Clone)]
+struct Run<T> {
+    start: isize,
+    end: isize,
+    value: T,
+}
+
+// Stores each row as runs of equal values along y, so maps with large
+// uniform areas take memory per run rather than per cell. Filling a span
+// costs as much as the runs it touches, however long it is. Adjacent runs of
+// equal values are always merged. As a `GridWrite` backend it can be built
+// with `GridBuilder::build_into`.
+#[derive(Clone)]
+pub struct RleGrid<T> {
+    rows: BTreeMap<isize, Vec<Run<T>>>,
+}
+
+impl<T: PartialEq + Clone> RleGrid<T> {
+    pub fn new() -> Self {
+        Self {
+            rows: BTreeMap::new(),
+        }
+    }
+
+    fn run_at(&self, x: isize, y: isize) -> Option<&Run<T>> {
+        let row = self.rows.get(&x)?;
+        let index = row.partition_point(|run| run.start <= y).checked_sub(1)?;
+        Some(&row[index]).filter(|run| run.end >= y)
+    }
+
+    // Sets, or with None clears, the cells of row `x` from `min_y` to `max_y`.
+    pub fn fill_span(&mut self, x: isize, min_y: isize, max_y: isize, value: Option<T>) {
+        let (min_y, max_y) = (min_y.min(max_y), min_y.max(max_y));
+        let row = self.rows.entry(x).or_default();
+        let first = row.partition_point(|run| run.end < min_y);
+        let last = row.partition_point(|run| run.start <= max_y);
+
+        let mut runs = vec![];
+        if first < last && row[first].start < min_y {
+            let head = &row[first];
+            runs.push(Run {
+                end: min_y - 1,
+                ..head.clone()
+            });
+        }
+        if let Some(value) = value {
+            runs.push(Run {
+                start: min_y,
+                end: max_y,
+                value,
+            });
+        }
+        if first < last && row[last - 1].end > max_y {
+            let tail = &row[last - 1];
+            runs.push(Run {
+                start: max_y + 1,
+                ..tail.clone()
+            });
+        }
+
+        let inserted = runs.len();
+        row.splice(first..last, runs);
+
+        // Only the new runs can merge, with each other or their neighbors.
+        let mut index = first.saturating_sub(1);
+        while index + 1 < row.len() && index <= first + inserted {
+            let (left, right) = (&row[index], &row[index + 1]);
+            if left.end.checked_add(1) == Some(right.start) && left.value == right.value {
+                row[index].end = row[index + 1].end;
+                row.remove(index + 1);
+            } else {
+                index += 1;
+            }
+        }
+
+        if row.is_empty() {
+            self.rows.remove(&x);
+        }
+    }
+
+    pub fn fill_rect(&mut self, rect: Rect, value: Option<T>) {
+        for x in rect.min_x..=rect.max_x {
+            self.fill_span(x, rect.min_y, rect.max_y, value.clone());
+        }
+    }
+
+    // The runs in row-major order, as (x, min_y, max_y, value).
+    pub fn runs(&self) -> impl Iterator<Item = (isize, isize, isize, &T)> {
+        self.rows.iter().flat_map(|(&x, row)| {
+            row.iter()
+                .map(move |run| (x, run.start, run.end, &run.value))
+        })
+    }
+
+    pub fn run_count(&self) -> usize {
+        self.rows.values().map(Vec::len).sum()
+    }
+
+    // Every stored cell in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = ((isize, isize), &T)> {
+        self.runs()
+            .flat_map(|(x, min_y, max_y, value)| (min_y..=max_y).map(move |y| ((x, y), value)))
+    }
+
+    pub fn to_grid(&self) -> Grid<T> {
+        let mut grid = Grid::new();
+        for ((x, y), value) in self.iter() {
+            grid.set(x, y, value.clone());
+        }
+        grid
+    }
+}
+
+impl<T: PartialEq + Clone> GridRead<T> for RleGrid<T> {
+    fn get(&self, x: isize, y: isize) -> Option<&T> {
+        Some(&self.run_at(x, y)?.value)
+    }
+
+    // The rectangle covering every run, or the origin when there are none.
+    fn bounds(&self) -> Rect {
+        let (Some((&min_x, _)), Some((&max_x, _))) =
+            (self.rows.first_key_value(), self.rows.last_key_value())
+        else {
+            return Rect::new(0, 0, 0, 0);
+        };
+
+        let min_y = self.rows.values().map(|row| row[0].start).min().unwrap();
+        let max_y = self
+            .rows
+            .values()
+            .map(|row| row[row.len() - 1].end)
+            .max()
+            .unwrap();
+        Rect::new(min_x, min_y, max_x, max_y)
+    }
+}
+
+impl<T: PartialEq + Clone> GridWrite<T> for RleGrid<T> {
+    fn set(&mut self, x: isize, y: isize, item: T) {
+        self.fill_span(x, y, y, Some(item));
+    }
+
+    fn remove(&mut self, x: isize, y: isize) -> Option<T> {
+        let value = self.run_at(x, y)?.value.clone();
+        self.fill_span(x, y, y, None);
+        Some(value)
+    }
+}
+
+impl<T: PartialEq + Clone> From<&Grid<T>> for RleGrid<T> {
+    fn from(grid: &Grid<T>) -> Self {
+        let mut rle = RleGrid::new();
+        for ((x, y), value) in grid.iter() {
+            rle.set(x, y, value.clone());
+        }
+        rle
+    }
+}
+
+impl<T: PartialEq + Clone> Default for RleGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RleGrid;
+    use crate::grid::{Grid, GridRead, GridWrite};
+    use crate::rect::Rect;
+
+    #[test]
+    fn merges_and_splits_runs() {
+        let mut map = RleGrid::new();
+        map.fill_rect(Rect::new(0, -10, 2, 10), Some('~'));
+        assert_eq!(map.run_count(), 3);
+
+        map.set(1, 0, '#');
+        assert_eq!(map.run_count(), 5);
+        assert_eq!(map.get(1, 0), Some(&'#'));
+        assert_eq!(map.get(1, 1), Some(&'~'));
+
+        map.set(1, 0, '~');
+        assert_eq!(map.run_count(), 3);
+
+        assert_eq!(map.remove(2, 10), Some('~'));
+        map.fill_span(0, -20, 20, None);
+        assert_eq!(map.bounds(), Rect::new(1, -10, 2, 10));
+        assert_eq!(map.runs().last(), Some((2, -10, 9, &'~')));
+    }
+
+    #[test]
+    fn matches_a_grid() {
+        let mut grid = Grid::new();
+        for (x, y) in Rect::new(-3, -3, 3, 3).iter() {
+            grid.set(x, y, (x.abs() + y.abs()) / 3);
+        }
+
+        let rle = RleGrid::from(&grid);
+        assert!(rle.run_count() < grid.len() / 2);
+        assert!(rle.iter().eq(grid.iter()));
+        assert_eq!(rle.to_grid().bounds(), grid.bounds());
+    }
+}
@@ -0,0 +1,145 @@
+use crate::grid::{Grid, GridRead, GridWrite};
+use crate::rect::Rect;
+
+// A grid that clears in constant time, for scratch data rebuilt every frame
+// or every search. Each cell remembers the generation it was written in, and
+// `clear_fast` starts a new generation, so older cells read as empty without
+// touching memory. Their storage is reused when the cells are set again.
+#[derive(Clone)]
+pub struct ScratchGrid<T> {
+    grid: Grid<(u32, T)>,
+    generation: u32,
+    bounds: Option<Rect>,
+    len: usize,
+}
+
+impl<T> Default for ScratchGrid<T> {
+    fn default() -> Self {
+        Self {
+            grid: Grid::new(),
+            generation: 0,
+            bounds: None,
+            len: 0,
+        }
+    }
+}
+
+impl<T> ScratchGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Empties the grid in O(1). Once every 2^32 clears the generations run
+    // out and the storage is really cleared.
+    pub fn clear_fast(&mut self) {
+        match self.generation.checked_add(1) {
+            Some(generation) => self.generation = generation,
+            None => *self = Self::new(),
+        }
+        self.bounds = None;
+        self.len = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // The bounds of the cells set since the last clear; includes the origin
+    // like a new `Grid`.
+    pub fn bounds(&self) -> Rect {
+        self.bounds.unwrap_or(Rect::new(0, 0, 0, 0))
+    }
+
+    pub fn get(&self, x: isize, y: isize) -> Option<&T> {
+        match self.grid.get(x, y) {
+            Some((generation, item)) if *generation == self.generation => Some(item),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, x: isize, y: isize) -> Option<&mut T> {
+        match self.grid.get_mut(x, y) {
+            Some((generation, item)) if *generation == self.generation => Some(item),
+            _ => None,
+        }
+    }
+
+    pub fn set(&mut self, x: isize, y: isize, item: T) {
+        if self.get(x, y).is_none() {
+            self.len += 1;
+        }
+        self.grid.set(x, y, (self.generation, item));
+
+        self.bounds = Some(self.bounds().union(&Rect::new(x, y, x, y)));
+    }
+
+    pub fn remove(&mut self, x: isize, y: isize) -> Option<T> {
+        self.get(x, y)?;
+        self.len -= 1;
+        self.grid.remove(x, y).map(|(_, item)| item)
+    }
+
+    // The cells of the current generation, in the same order as `Grid::iter`.
+    pub fn iter(&self) -> impl Iterator<Item = ((isize, isize), &T)> {
+        self.grid
+            .iter()
+            .filter(|(_, (generation, _))| *generation == self.generation)
+            .map(|(position, (_, item))| (position, item))
+    }
+}
+
+impl<T> GridRead<T> for ScratchGrid<T> {
+    fn get(&self, x: isize, y: isize) -> Option<&T> {
+        ScratchGrid::get(self, x, y)
+    }
+
+    fn bounds(&self) -> Rect {
+        ScratchGrid::bounds(self)
+    }
+}
+
+impl<T> GridWrite<T> for ScratchGrid<T> {
+    fn set(&mut self, x: isize, y: isize, item: T) {
+        ScratchGrid::set(self, x, y, item);
+    }
+
+    fn remove(&mut self, x: isize, y: isize) -> Option<T> {
+        ScratchGrid::remove(self, x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ScratchGrid;
+    use crate::rect::Rect;
+
+    #[test]
+    fn clears_without_touching_cells() {
+        let mut scratch = ScratchGrid::new();
+        scratch.set(3, -2, 'a');
+        scratch.set(-1, 5, 'b');
+        assert_eq!(scratch.len(), 2);
+        assert_eq!(scratch.bounds(), Rect::new(-1, -2, 3, 5));
+
+        let memory = scratch.grid.memory_usage();
+        scratch.clear_fast();
+        assert!(scratch.is_empty());
+        assert_eq!(scratch.get(3, -2), None);
+        assert_eq!(scratch.remove(-1, 5), None);
+        assert_eq!(scratch.bounds(), Rect::new(0, 0, 0, 0));
+
+        scratch.set(3, -2, 'c');
+        assert_eq!(scratch.get(3, -2), Some(&'c'));
+        assert_eq!(scratch.iter().count(), 1);
+        assert_eq!(scratch.grid.memory_usage(), memory);
+
+        scratch.generation = u32::MAX;
+        scratch.clear_fast();
+        assert_eq!(scratch.generation, 0);
+        assert_eq!(scratch.grid.len(), 0);
+    }
+}
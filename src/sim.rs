@@ -0,0 +1,240 @@
+use crate::grid::Grid;
+use crate::rect::Rect;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::collections::HashSet;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Order {
+    // Rows from the highest x (the bottom) upwards, alternating the column
+    // direction every tick so movement has no sideways bias.
+    BottomUp,
+    Random,
+}
+
+// Cells moved into during a tick are not updated again until the next tick,
+// so a particle moves at most once per tick regardless of the visiting order.
+pub struct Simulation<T> {
+    grid: Grid<T>,
+    bounds: Rect,
+    order: Order,
+    rng: StdRng,
+    tick: u64,
+}
+
+pub struct Context<'a, T> {
+    grid: &'a mut Grid<T>,
+    bounds: Rect,
+    touched: HashSet<(isize, isize)>,
+    rng: &'a mut StdRng,
+}
+
+impl<T> Context<'_, T> {
+    pub fn get(&self, (x, y): (isize, isize)) -> Option<&T> {
+        self.grid.get(x, y)
+    }
+
+    pub fn get_mut(&mut self, (x, y): (isize, isize)) -> Option<&mut T> {
+        self.grid.get_mut(x, y)
+    }
+
+    pub fn is_free(&self, (x, y): (isize, isize)) -> bool {
+        self.bounds.contains(x, y) && self.grid.get(x, y).is_none()
+    }
+
+    pub fn rng(&mut self) -> &mut StdRng {
+        self.rng
+    }
+
+    // Moves the value at `from` into the empty cell `to`. Fails if there is
+    // nothing to move, `to` is occupied or `to` lies outside the simulation.
+    pub fn try_move(&mut self, from: (isize, isize), to: (isize, isize)) -> bool {
+        if !self.is_free(to) {
+            return false;
+        }
+
+        match self.grid.remove(from.0, from.1) {
+            Some(item) => {
+                self.grid.set(to.0, to.1, item);
+                self.touched.insert(to);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Exchanges two cells, either of which may be empty, as long as both lie
+    // inside the simulation.
+    pub fn swap(&mut self, a: (isize, isize), b: (isize, isize)) -> bool {
+        if !self.bounds.contains(a.0, a.1) || !self.bounds.contains(b.0, b.1) {
+            return false;
+        }
+
+        let first = self.grid.remove(a.0, a.1);
+        let second = self.grid.remove(b.0, b.1);
+
+        for (position, item) in [(b, first), (a, second)] {
+            if let Some(item) = item {
+                self.grid.set(position.0, position.1, item);
+                self.touched.insert(position);
+            }
+        }
+
+        true
+    }
+}
+
+impl<T> Simulation<T> {
+    // The simulation is confined to the grid's bounds at creation.
+    pub fn new(grid: Grid<T>, order: Order, seed: u64) -> Self {
+        Self {
+            bounds: grid.bounds(),
+            grid,
+            order,
+            rng: StdRng::seed_from_u64(seed),
+            tick: 0,
+        }
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    pub fn into_grid(self) -> Grid<T> {
+        self.grid
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    fn schedule(&mut self) -> Vec<(isize, isize)> {
+        let bounds = self.bounds;
+        let mut cells: Vec<(isize, isize)> = match self.order {
+            Order::BottomUp => {
+                let reverse = self.tick % 2 == 1;
+                (bounds.min_x..=bounds.max_x)
+                    .rev()
+                    .flat_map(|x| {
+                        let columns = bounds.min_y..=bounds.max_y;
+                        let columns: Vec<isize> = if reverse {
+                            columns.rev().collect()
+                        } else {
+                            columns.collect()
+                        };
+                        columns.into_iter().map(move |y| (x, y))
+                    })
+                    .collect()
+            }
+            Order::Random => bounds.iter().collect(),
+        };
+
+        cells.retain(|&(x, y)| self.grid.get(x, y).is_some());
+        if self.order == Order::Random {
+            cells.shuffle(&mut self.rng);
+        }
+
+        cells
+    }
+
+    pub fn step<F>(&mut self, mut update: F)
+    where
+        F: FnMut(&mut Context<T>, (isize, isize)),
+    {
+        let cells = self.schedule();
+        let mut context = Context {
+            grid: &mut self.grid,
+            bounds: self.bounds,
+            touched: HashSet::new(),
+            rng: &mut self.rng,
+        };
+
+        for position in cells {
+            if context.touched.contains(&position) || context.get(position).is_none() {
+                continue;
+            }
+
+            update(&mut context, position);
+        }
+
+        self.tick += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Context, Order, Simulation};
+    use crate::grid::Grid;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Particle {
+        Sand,
+        Stone,
+    }
+
+    fn fall(context: &mut Context<Particle>, (x, y): (isize, isize)) {
+        if context.get((x, y)) == Some(&Particle::Sand) {
+            let _ = context.try_move((x, y), (x + 1, y))
+                || context.try_move((x, y), (x + 1, y - 1))
+                || context.try_move((x, y), (x + 1, y + 1));
+        }
+    }
+
+    fn world() -> Grid<Particle> {
+        let mut grid = Grid::new();
+        for y in -3..=3 {
+            grid.set(6, y, Particle::Stone);
+        }
+        for x in 0..3 {
+            grid.set(x, 0, Particle::Sand);
+        }
+        grid
+    }
+
+    #[test]
+    fn sand_falls_one_cell_per_tick() {
+        let mut simulation = Simulation::new(world(), Order::Random, 1);
+        simulation.step(fall);
+
+        let grid = simulation.grid();
+        let sand: Vec<(isize, isize)> = grid
+            .bounds()
+            .iter()
+            .filter(|&(x, y)| grid.get(x, y) == Some(&Particle::Sand))
+            .collect();
+        assert_eq!(sand.len(), 3);
+        assert!(sand.iter().all(|&(x, _)| x <= 3));
+    }
+
+    #[test]
+    fn sand_piles_up_on_stone() {
+        let mut simulation = Simulation::new(world(), Order::BottomUp, 1);
+        for _ in 0..10 {
+            simulation.step(fall);
+        }
+
+        let grid = simulation.into_grid();
+        for y in -1..=1 {
+            assert_eq!(grid.get(5, y), Some(&Particle::Sand));
+        }
+        assert!((0..5).all(|x| grid.get(x, 0).is_none()));
+    }
+
+    #[test]
+    fn swap_marks_both_cells() {
+        let mut grid = Grid::new();
+        grid.set(0, 0, 1);
+        grid.set(0, 1, 2);
+
+        let mut simulation = Simulation::new(grid, Order::BottomUp, 0);
+        let mut visits = 0;
+        simulation.step(|context, (x, y)| {
+            visits += 1;
+            assert!(context.swap((x, y), (x, y + 1)));
+        });
+
+        assert_eq!(visits, 1);
+        assert_eq!(simulation.grid().get(0, 0), Some(&2));
+    }
+}
@@ -0,0 +1,123 @@
+use crate::grid::{Grid, Symmetry};
+use crate::rect::Rect;
+
+// A small prefab placed onto a larger grid, such as a house or a tree. The
+// anchor is the stamp cell that lands on the target position; empty stamp
+// cells leave the target untouched.
+#[derive(Clone, Debug)]
+pub struct Stamp<T> {
+    grid: Grid<T>,
+    anchor: (isize, isize),
+}
+
+impl<T> Stamp<T> {
+    pub fn new(grid: Grid<T>, anchor: (isize, isize)) -> Self {
+        Self { grid, anchor }
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    pub fn anchor(&self) -> (isize, isize) {
+        self.anchor
+    }
+
+    // The target cells covered when the anchor lands on `at`.
+    pub fn footprint(&self, at: (isize, isize)) -> Rect {
+        let bounds = self.grid.bounds();
+        let (dx, dy) = (at.0 - self.anchor.0, at.1 - self.anchor.1);
+        Rect::new(
+            bounds.min_x + dx,
+            bounds.min_y + dy,
+            bounds.max_x + dx,
+            bounds.max_y + dy,
+        )
+    }
+}
+
+impl<T: Clone> Stamp<T> {
+    // The stamp rotated or flipped around its anchor, which becomes the
+    // origin of the new stamp grid.
+    pub fn transformed(&self, symmetry: Symmetry) -> Stamp<T> {
+        let mut grid = Grid::new();
+        for ((x, y), item) in self.grid.iter() {
+            let (x, y) = symmetry.apply((x - self.anchor.0, y - self.anchor.1));
+            grid.set(x, y, item.clone());
+        }
+        Stamp {
+            grid,
+            anchor: (0, 0),
+        }
+    }
+
+    // All eight orientations, for decorators picking one at random.
+    pub fn variants(&self) -> Vec<Stamp<T>> {
+        Symmetry::ALL
+            .iter()
+            .map(|&symmetry| self.transformed(symmetry))
+            .collect()
+    }
+}
+
+impl<T> Grid<T> {
+    // Places the stamp with its anchor on `at`. Every stamp cell is combined
+    // with the target cell by `blend(target, stamp)`, whose result is
+    // stored; `|_, cell| cell.clone()` simply overwrites.
+    pub fn apply_stamp<F>(&mut self, stamp: &Stamp<T>, at: (isize, isize), mut blend: F)
+    where
+        F: FnMut(Option<&T>, &T) -> T,
+    {
+        let (dx, dy) = (at.0 - stamp.anchor.0, at.1 - stamp.anchor.1);
+        for ((x, y), item) in stamp.grid.iter() {
+            let (x, y) = (x + dx, y + dy);
+            let blended = blend(self.get(x, y), item);
+            self.set(x, y, blended);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stamp;
+    use crate::grid::{Grid, Symmetry};
+    use crate::rect::Rect;
+
+    fn tree() -> Stamp<char> {
+        // A trunk with leaves above it, anchored at the trunk.
+        let mut grid = Grid::new();
+        grid.set(0, 0, '*');
+        grid.set(1, 0, '|');
+        Stamp::new(grid, (1, 0))
+    }
+
+    #[test]
+    fn places_at_anchor() {
+        let mut world = Grid::new();
+        world.set(4, 7, '.');
+        world.set(3, 7, '.');
+
+        let stamp = tree();
+        assert_eq!(stamp.footprint((4, 7)), Rect::new(3, 7, 4, 7));
+        world.apply_stamp(&stamp, (4, 7), |target, cell| match target {
+            Some('.') => *cell,
+            _ => '?',
+        });
+        assert_eq!(world.get(4, 7), Some(&'|'));
+        assert_eq!(world.get(3, 7), Some(&'*'));
+        assert_eq!(world.len(), 2);
+    }
+
+    #[test]
+    fn rotates_around_anchor() {
+        let rotated = tree().transformed(Symmetry::Rotate90);
+        assert_eq!(rotated.anchor(), (0, 0));
+        assert_eq!(rotated.grid().get(0, 0), Some(&'|'));
+        assert_eq!(rotated.grid().get(0, 1), Some(&'*'));
+
+        let mut world = Grid::new();
+        world.apply_stamp(&rotated, (5, 5), |_, cell| *cell);
+        assert_eq!(world.get(5, 6), Some(&'*'));
+        assert_eq!(tree().variants().len(), 8);
+    }
+}
@@ -0,0 +1,70 @@
+use crate::grid::Grid;
+use crate::rect::Rect;
+use proptest::arbitrary::{any, Arbitrary};
+use proptest::collection;
+use proptest::strategy::{BoxedStrategy, Strategy};
+use std::fmt::Debug;
+
+// Grids with exactly `bounds`, each cell empty or drawn from `cell`.
+// Shrinking empties cells and simplifies the remaining values.
+pub fn grid<S>(bounds: Rect, cell: S) -> impl Strategy<Value = Grid<S::Value>>
+where
+    S: Strategy,
+    S::Value: Clone,
+{
+    collection::vec(proptest::option::of(cell), bounds.area()).prop_map(move |cells| {
+        let mut grid = Grid::with_bounds(bounds);
+        for ((x, y), cell) in bounds.iter().zip(cells) {
+            if let Some(item) = cell {
+                grid.set(x, y, item);
+            }
+        }
+        grid
+    })
+}
+
+// Grids of up to 8 by 8 cells near the origin.
+impl<T: Arbitrary + Clone + Debug + 'static> Arbitrary for Grid<T> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Grid<T>>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (-4..=4isize, -4..=4isize, 0..8isize, 0..8isize)
+            .prop_flat_map(|(min_x, min_y, height, width)| {
+                grid(
+                    Rect::new(min_x, min_y, min_x + height, min_y + width),
+                    any::<T>(),
+                )
+            })
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::grid;
+    use crate::grid::Grid;
+    use crate::rect::Rect;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn keeps_the_requested_bounds(grid in grid(Rect::new(-2, 3, 5, 4), 0..10u8)) {
+            prop_assert_eq!(grid.bounds(), Rect::new(-2, 3, 5, 4));
+            prop_assert!(grid.iter().all(|(_, &value)| value < 10));
+        }
+
+        #[test]
+        fn len_counts_stored_cells(grid in any::<Grid<u8>>()) {
+            prop_assert_eq!(grid.len(), grid.iter().count());
+            prop_assert_eq!(grid.validate(), Ok(()));
+        }
+
+        #[test]
+        fn deltas_patch_any_state(older in any::<Grid<u8>>(), newer in any::<Grid<u8>>()) {
+            let mut patched = older.clone();
+            patched.apply_delta(&newer.delta_from(&older));
+            prop_assert!(patched.iter().eq(newer.iter()));
+        }
+    }
+}
@@ -0,0 +1,418 @@
+use crate::grid::Grid;
+use crate::rect::Rect;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+// Stream layout, all integers little-endian:
+//   b"PHGS", version u8, bounds as four i64, chunk size u32
+//   then per non-empty chunk: chunk x and y as i64, payload length u64 and
+//   the payload: a presence bit per cell of the chunk in row-major order,
+//   followed by the present values.
+// Chunks are squares aligned to multiples of the chunk size and are written
+// in row-major order, so the writer only holds one row of chunks in memory
+// and the reader one chunk.
+
+const MAGIC: &[u8; 4] = b"PHGS";
+const VERSION: u8 = 1;
+const CHUNK_SIZE: u32 = 64;
+
+// Values with a fixed binary encoding.
+pub trait Codec: Sized {
+    fn encode(&self, out: &mut Vec<u8>);
+    fn decode(input: &mut &[u8]) -> io::Result<Self>;
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> io::Result<&'a [u8]> {
+    if input.len() < len {
+        return Err(invalid("truncated value"));
+    }
+    let (bytes, rest) = input.split_at(len);
+    *input = rest;
+    Ok(bytes)
+}
+
+macro_rules! number_codec {
+    ($($type:ty),*) => {$(
+        impl Codec for $type {
+            fn encode(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn decode(input: &mut &[u8]) -> io::Result<Self> {
+                let bytes = take(input, size_of::<$type>())?;
+                Ok(<$type>::from_le_bytes(bytes.try_into().unwrap()))
+            }
+        }
+    )*};
+}
+
+number_codec!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+impl Codec for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+
+    fn decode(input: &mut &[u8]) -> io::Result<Self> {
+        match u8::decode(input)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(invalid("invalid bool")),
+        }
+    }
+}
+
+impl Codec for char {
+    fn encode(&self, out: &mut Vec<u8>) {
+        u32::from(*self).encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> io::Result<Self> {
+        char::from_u32(u32::decode(input)?).ok_or_else(|| invalid("invalid char"))
+    }
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_i64(reader: &mut impl Read) -> io::Result<isize> {
+    let value = i64::from_le_bytes(read_array(reader)?);
+    isize::try_from(value).map_err(|_| invalid("coordinate out of range"))
+}
+
+// The cells of chunk (cx, cy) inside `bounds`.
+fn chunk_rect(bounds: Rect, size: u32, (cx, cy): (isize, isize)) -> Option<Rect> {
+    let size = size as isize;
+    let chunk = Rect::new(
+        cx.saturating_mul(size),
+        cy.saturating_mul(size),
+        cx.saturating_mul(size).saturating_add(size - 1),
+        cy.saturating_mul(size).saturating_add(size - 1),
+    );
+    chunk.intersection(&bounds)
+}
+
+struct Header {
+    bounds: Rect,
+    chunk_size: u32,
+}
+
+fn read_header(reader: &mut impl Read) -> io::Result<Header> {
+    if &read_array::<4>(reader)? != MAGIC {
+        return Err(invalid("not a grid stream"));
+    }
+    if read_array::<1>(reader)?[0] != VERSION {
+        return Err(invalid("unsupported grid stream version"));
+    }
+
+    let (min_x, min_y) = (read_i64(reader)?, read_i64(reader)?);
+    let (max_x, max_y) = (read_i64(reader)?, read_i64(reader)?);
+    let chunk_size = u32::from_le_bytes(read_array(reader)?);
+    if chunk_size == 0 {
+        return Err(invalid("empty chunks"));
+    }
+    if min_x > max_x || min_y > max_y {
+        return Err(invalid("stream bounds are inverted"));
+    }
+
+    Ok(Header {
+        bounds: Rect::new(min_x, min_y, max_x, max_y),
+        chunk_size,
+    })
+}
+
+// Reads the next chunk header, or None at the end of the stream.
+fn read_chunk_header(reader: &mut impl Read) -> io::Result<Option<((isize, isize), u64)>> {
+    let mut first = [0; 8];
+    match reader.read(&mut first[..1])? {
+        0 => return Ok(None),
+        _ => reader.read_exact(&mut first[1..])?,
+    }
+
+    let cx =
+        isize::try_from(i64::from_le_bytes(first)).map_err(|_| invalid("chunk out of range"))?;
+    let cy = read_i64(reader)?;
+    let len = u64::from_le_bytes(read_array(reader)?);
+    Ok(Some(((cx, cy), len)))
+}
+
+// Reads a chunk payload of `len` bytes. Only bytes actually in the stream are
+// allocated, so a corrupt length cannot exhaust memory.
+fn read_payload(reader: &mut impl Read, len: u64, payload: &mut Vec<u8>) -> io::Result<()> {
+    payload.clear();
+    reader.take(len).read_to_end(payload)?;
+    if payload.len() as u64 != len {
+        return Err(invalid("truncated chunk"));
+    }
+    Ok(())
+}
+
+fn decode_chunk<T: Codec>(rect: Rect, mut payload: &[u8], grid: &mut Grid<T>) -> io::Result<()> {
+    let presence = take(&mut payload, rect.area().div_ceil(8))?;
+    for (i, (x, y)) in rect.iter().enumerate() {
+        if presence[i / 8] & (1 << (i % 8)) != 0 {
+            grid.try_set(x, y, T::decode(&mut payload)?)
+                .map_err(|error| invalid(&error.to_string()))?;
+        }
+    }
+    Ok(())
+}
+
+// Writes the chunks of chunk row `cx` collected so far, in column order.
+fn write_chunks(
+    writer: &mut impl Write,
+    cx: isize,
+    chunks: &mut BTreeMap<isize, Vec<u8>>,
+) -> io::Result<()> {
+    for (cy, payload) in std::mem::take(chunks) {
+        writer.write_all(&(cx as i64).to_le_bytes())?;
+        writer.write_all(&(cy as i64).to_le_bytes())?;
+        writer.write_all(&(payload.len() as u64).to_le_bytes())?;
+        writer.write_all(&payload)?;
+    }
+    Ok(())
+}
+
+impl<T: Codec> Grid<T> {
+    pub fn write_stream(&self, writer: impl Write) -> io::Result<()> {
+        self.write_stream_chunked(writer, CHUNK_SIZE)
+    }
+
+    // Larger chunks make lazy loading coarser but the stream smaller.
+    pub fn write_stream_chunked(&self, mut writer: impl Write, chunk_size: u32) -> io::Result<()> {
+        assert!(chunk_size > 0, "chunks must not be empty");
+        let bounds = self.bounds();
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        for value in [bounds.min_x, bounds.min_y, bounds.max_x, bounds.max_y] {
+            writer.write_all(&(value as i64).to_le_bytes())?;
+        }
+        writer.write_all(&chunk_size.to_le_bytes())?;
+
+        // Stored cells come in row-major order, so each row of chunks is
+        // complete once the first cell below it shows up.
+        let size = chunk_size as isize;
+        let mut row = None;
+        let mut chunks = BTreeMap::new();
+        for ((x, y), item) in self.iter() {
+            let (cx, cy) = (x.div_euclid(size), y.div_euclid(size));
+            if row != Some(cx) {
+                if let Some(row) = row {
+                    write_chunks(&mut writer, row, &mut chunks)?;
+                }
+                row = Some(cx);
+            }
+
+            let rect = chunk_rect(bounds, chunk_size, (cx, cy)).unwrap();
+            let payload = chunks
+                .entry(cy)
+                .or_insert_with(|| vec![0; rect.area().div_ceil(8)]);
+            let i = (x - rect.min_x) as usize * rect.width() + (y - rect.min_y) as usize;
+            payload[i / 8] |= 1 << (i % 8);
+            item.encode(payload);
+        }
+        if let Some(row) = row {
+            write_chunks(&mut writer, row, &mut chunks)?;
+        }
+
+        writer.flush()
+    }
+
+    pub fn read_stream(mut reader: impl Read) -> io::Result<Grid<T>> {
+        let header = read_header(&mut reader)?;
+        let mut grid = Grid::try_with_bounds(header.bounds)
+            .map_err(|_| invalid("stream bounds are too large"))?;
+
+        let mut payload = vec![];
+        while let Some((chunk, len)) = read_chunk_header(&mut reader)? {
+            let rect = chunk_rect(header.bounds, header.chunk_size, chunk)
+                .ok_or_else(|| invalid("chunk outside the bounds"))?;
+            read_payload(&mut reader, len, &mut payload)?;
+            decode_chunk(rect, &payload, &mut grid)?;
+        }
+
+        Ok(grid)
+    }
+}
+
+// A grid backed by a stream written with `write_stream`, loading each chunk
+// the first time one of its cells is read. Opening only reads the chunk
+// headers. With a cache limit, the chunks loaded longest ago are dropped
+// again, so memory stays bounded however large the stream is.
+pub struct LazyGrid<T, R> {
+    reader: R,
+    bounds: Rect,
+    chunk_size: u32,
+    offsets: HashMap<(isize, isize), (u64, u64)>,
+    loaded: HashMap<(isize, isize), Grid<T>>,
+    order: VecDeque<(isize, isize)>,
+    cache_limit: Option<usize>,
+}
+
+impl<T: Codec, R: Read + Seek> LazyGrid<T, R> {
+    pub fn open(mut reader: R) -> io::Result<Self> {
+        let header = read_header(&mut reader)?;
+        let mut offsets = HashMap::new();
+
+        while let Some((chunk, len)) = read_chunk_header(&mut reader)? {
+            let offset = reader.stream_position()?;
+            offsets.insert(chunk, (offset, len));
+            let skip = i64::try_from(len).map_err(|_| invalid("chunk too long"))?;
+            reader.seek(SeekFrom::Current(skip))?;
+        }
+
+        Ok(Self {
+            reader,
+            bounds: header.bounds,
+            chunk_size: header.chunk_size,
+            offsets,
+            loaded: HashMap::new(),
+            order: VecDeque::new(),
+            cache_limit: None,
+        })
+    }
+
+    // Keeps at most `chunks` chunks loaded.
+    pub fn with_cache_limit(mut self, chunks: usize) -> Self {
+        assert!(chunks > 0, "the cache must hold a chunk");
+        self.cache_limit = Some(chunks);
+        self
+    }
+
+    pub fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    pub fn loaded_chunks(&self) -> usize {
+        self.loaded.len()
+    }
+
+    pub fn get(&mut self, x: isize, y: isize) -> io::Result<Option<&T>> {
+        if !self.bounds.contains(x, y) {
+            return Ok(None);
+        }
+
+        let size = self.chunk_size as isize;
+        let chunk = (x.div_euclid(size), y.div_euclid(size));
+        let Some(&(offset, len)) = self.offsets.get(&chunk) else {
+            return Ok(None);
+        };
+
+        if !self.loaded.contains_key(&chunk) {
+            if self.cache_limit == Some(self.loaded.len()) {
+                let oldest = self.order.pop_front().unwrap();
+                self.loaded.remove(&oldest);
+            }
+
+            let mut payload = vec![];
+            self.reader.seek(SeekFrom::Start(offset))?;
+            read_payload(&mut self.reader, len, &mut payload)?;
+
+            let rect = chunk_rect(self.bounds, self.chunk_size, chunk).unwrap();
+            let mut grid = Grid::with_bounds(rect);
+            decode_chunk(rect, &payload, &mut grid)?;
+
+            self.loaded.insert(chunk, grid);
+            self.order.push_back(chunk);
+        }
+
+        Ok(self.loaded[&chunk].get(x, y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LazyGrid;
+    use crate::grid::Grid;
+    use crate::rect::Rect;
+    use std::io::Cursor;
+
+    fn sample() -> Grid<f32> {
+        let mut grid = Grid::new();
+        for (x, y) in Rect::new(-20, -5, 30, 70).iter() {
+            if (x + y) % 3 != 0 {
+                grid.set(x, y, (x * y) as f32 / 2.0);
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn round_trips_through_a_stream() {
+        let grid = sample();
+        let mut bytes = vec![];
+        grid.write_stream_chunked(&mut bytes, 16).unwrap();
+
+        let read = Grid::<f32>::read_stream(bytes.as_slice()).unwrap();
+        assert_eq!(read.bounds(), grid.bounds());
+        assert!(read.iter().eq(grid.iter()));
+
+        assert!(Grid::<f32>::read_stream(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Grid::<f32>::read_stream(&b"nope"[..]).is_err());
+
+        // Only stored cells are written, not the default around them.
+        let mut sparse = Grid::new_with_default(1.0f32);
+        sparse.set(-100, 0, 2.0);
+        sparse.set(0, 100, 3.0);
+        let mut bytes = vec![];
+        sparse.write_stream(&mut bytes).unwrap();
+        let read = Grid::<f32>::read_stream(bytes.as_slice()).unwrap();
+        assert!(read.iter().eq(sparse.iter()));
+        assert_eq!(read.len(), 2);
+    }
+
+    #[test]
+    fn rejects_hostile_streams() {
+        let header = |bounds: [i64; 4]| {
+            let mut bytes = b"PHGS\x01".to_vec();
+            for value in bounds {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            bytes.extend_from_slice(&64u32.to_le_bytes());
+            bytes
+        };
+
+        let wide = header([i64::MIN, 0, i64::MAX, 0]);
+        assert!(Grid::<f32>::read_stream(wide.as_slice()).is_err());
+        assert!(Grid::<f32>::read_stream(header([1, 0, 0, 0]).as_slice()).is_err());
+
+        // A chunk claiming far more bytes than the stream holds.
+        let mut huge = header([0, 0, 3, 3]);
+        for value in [0i64, 0] {
+            huge.extend_from_slice(&value.to_le_bytes());
+        }
+        huge.extend_from_slice(&(u64::MAX / 2).to_le_bytes());
+        huge.push(1);
+        assert!(Grid::<f32>::read_stream(huge.as_slice()).is_err());
+        let mut lazy = LazyGrid::<f32, _>::open(Cursor::new(huge)).unwrap();
+        assert!(lazy.get(0, 0).is_err());
+    }
+
+    #[test]
+    fn loads_chunks_on_demand() {
+        let grid = sample();
+        let mut bytes = vec![];
+        grid.write_stream_chunked(&mut bytes, 16).unwrap();
+
+        let mut lazy = LazyGrid::<f32, _>::open(Cursor::new(bytes))
+            .unwrap()
+            .with_cache_limit(2);
+        assert_eq!(lazy.loaded_chunks(), 0);
+        assert_eq!(lazy.bounds(), grid.bounds());
+
+        for (x, y) in [(0, 1), (-20, 70), (30, 30), (0, 2)] {
+            assert_eq!(lazy.get(x, y).unwrap(), grid.get(x, y));
+        }
+        assert_eq!(lazy.loaded_chunks(), 2);
+        assert_eq!(lazy.get(0, 0).unwrap(), None);
+        assert_eq!(lazy.get(100, 0).unwrap(), None);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::grid::Grid;
+use crate::rect::Rect;
+
+// Prefix sums and counts over a numeric grid's bounds, so the sum of any
+// rect is four lookups however large it is. Built once from a grid and not
+// updated by later edits to it. Empty cells add nothing to a sum and are
+// not counted. Infinite and NaN cells are counted apart from the finite
+// sums, so they only affect the sums of rects containing them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SummedAreaTable {
+    bounds: Rect,
+    // One more row and column than the bounds, with zeros in front.
+    sums: Vec<f64>,
+    counts: Vec<usize>,
+    // Prefix counts of +inf, -inf and NaN cells, left empty while there are
+    // none.
+    special: Vec<[usize; 3]>,
+}
+
+impl SummedAreaTable {
+    pub fn new<T: Copy + Into<f64>>(grid: &Grid<T>) -> Self {
+        let bounds = grid.bounds();
+        let stride = bounds.width() + 1;
+        let mut sums = vec![0.0; (bounds.height() + 1) * stride];
+        let mut counts = vec![0; sums.len()];
+        let mut special: Vec<[usize; 3]> = vec![];
+
+        for (x, y) in bounds.iter() {
+            let (i, j) = (
+                (x - bounds.min_x) as usize + 1,
+                (y - bounds.min_y) as usize + 1,
+            );
+            let (value, count) = grid.get(x, y).map_or((0.0, 0), |&item| (item.into(), 1));
+            let at = i * stride + j;
+            let (up, left, corner) = (at - stride, at - 1, at - stride - 1);
+
+            let kind = if value == f64::INFINITY {
+                Some(0)
+            } else if value == f64::NEG_INFINITY {
+                Some(1)
+            } else if value.is_nan() {
+                Some(2)
+            } else {
+                None
+            };
+            if kind.is_some() && special.is_empty() {
+                special = vec![[0; 3]; sums.len()];
+            }
+            if !special.is_empty() {
+                let prefix = std::array::from_fn(|k| {
+                    let own = usize::from(kind == Some(k));
+                    own + special[up][k] + special[left][k] - special[corner][k]
+                });
+                special[at] = prefix;
+            }
+            let value = if kind.is_some() { 0.0 } else { value };
+
+            sums[at] = value + sums[up] + sums[left] - sums[corner];
+            counts[at] = count + counts[up] + counts[left] - counts[corner];
+        }
+
+        Self {
+            bounds,
+            sums,
+            counts,
+            special,
+        }
+    }
+
+    pub fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    // The sum of the cells in `rect`; the parts outside the bounds add
+    // nothing. Infinite and NaN cells inside give the sum IEEE addition
+    // would.
+    pub fn sum_rect(&self, rect: Rect) -> f64 {
+        self.corners(rect).map_or(0.0, |[a, b, c, d]| {
+            let sum = self.sums[d] - self.sums[b] - self.sums[c] + self.sums[a];
+            if self.special.is_empty() {
+                return sum;
+            }
+
+            let count = |k: usize| {
+                self.special[d][k] + self.special[a][k] - self.special[b][k] - self.special[c][k]
+            };
+            match (count(0), count(1), count(2)) {
+                (0, 0, 0) => sum,
+                (_, 0, 0) => f64::INFINITY,
+                (0, _, 0) => f64::NEG_INFINITY,
+                _ => f64::NAN,
+            }
+        })
+    }
+
+    // How many stored cells `rect` holds.
+    pub fn count_rect(&self, rect: Rect) -> usize {
+        self.corners(rect).map_or(0, |[a, b, c, d]| {
+            self.counts[d] + self.counts[a] - self.counts[b] - self.counts[c]
+        })
+    }
+
+    // The mean of the stored cells in `rect`, None when there are none.
+    pub fn mean_rect(&self, rect: Rect) -> Option<f64> {
+        let count = self.count_rect(rect);
+        (count > 0).then(|| self.sum_rect(rect) / count as f64)
+    }
+
+    // The table indices just outside the top left, top right, bottom left
+    // and bottom right corners of `rect` clipped to the bounds.
+    fn corners(&self, rect: Rect) -> Option<[usize; 4]> {
+        let rect = rect.intersection(&self.bounds)?;
+        let stride = self.bounds.width() + 1;
+        let row = |x: isize| (x - self.bounds.min_x) as usize * stride;
+        let column = |y: isize| (y - self.bounds.min_y) as usize;
+        let (top, bottom) = (row(rect.min_x), row(rect.max_x + 1));
+        let (left, right) = (column(rect.min_y), column(rect.max_y + 1));
+        Some([top + left, top + right, bottom + left, bottom + right])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SummedAreaTable;
+    use crate::grid::Grid;
+    use crate::rect::Rect;
+
+    #[test]
+    fn sums_rects() {
+        let mut grid = Grid::new();
+        for (x, y) in Rect::new(-1, 2, 2, 5).iter() {
+            grid.set(x, y, (x * y) as f32);
+        }
+        grid.remove(0, 3);
+        let table = SummedAreaTable::new(&grid);
+        assert_eq!(table.bounds(), grid.bounds());
+
+        for rect in [
+            Rect::new(-1, 2, 2, 5),
+            Rect::new(0, 3, 1, 4),
+            Rect::new(1, 5, 1, 5),
+        ] {
+            let cells: Vec<f32> = rect
+                .iter()
+                .filter_map(|(x, y)| grid.get(x, y))
+                .copied()
+                .collect();
+            assert_eq!(table.sum_rect(rect), cells.iter().sum::<f32>() as f64);
+            assert_eq!(table.count_rect(rect), cells.len());
+        }
+
+        // Clipped to the bounds.
+        assert_eq!(table.sum_rect(Rect::new(2, 5, 9, 9)), 10.0);
+        assert_eq!(table.sum_rect(Rect::new(5, 5, 9, 9)), 0.0);
+        assert_eq!(table.mean_rect(Rect::new(0, 3, 0, 3)), None);
+    }
+
+    #[test]
+    fn keeps_non_finite_cells_to_their_rects() {
+        let mut grid = Grid::new();
+        for (x, y) in Rect::new(0, 0, 3, 3).iter() {
+            grid.set(x, y, 1.0);
+        }
+        grid.set(1, 1, f64::INFINITY);
+        grid.set(2, 2, f64::NAN);
+        let table = SummedAreaTable::new(&grid);
+
+        assert_eq!(table.sum_rect(Rect::new(0, 2, 1, 3)), 4.0);
+        assert_eq!(table.sum_rect(Rect::new(3, 0, 3, 3)), 4.0);
+        assert_eq!(table.sum_rect(Rect::new(0, 0, 1, 1)), f64::INFINITY);
+        assert!(table.sum_rect(Rect::new(0, 0, 3, 3)).is_nan());
+        assert_eq!(table.count_rect(Rect::new(0, 0, 3, 3)), 16);
+
+        grid.set(0, 0, f64::NEG_INFINITY);
+        let table = SummedAreaTable::new(&grid);
+        assert!(table.sum_rect(Rect::new(0, 0, 1, 1)).is_nan());
+        assert_eq!(table.sum_rect(Rect::new(0, 0, 0, 3)), f64::NEG_INFINITY);
+    }
+}
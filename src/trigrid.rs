@@ -0,0 +1,156 @@
+use crate::grid::{Grid, GridRead, GridWrite};
+use crate::rect::Rect;
+
+// A grid of triangles. Cells use the usual (row, column) coordinates and
+// alternate orientation along each row: (x, y) points up when x + y is even
+// and down otherwise, so (0, 0) points up. Each triangle shares an edge with
+// its left and right neighbors and with the cell below it (pointing up) or
+// above it (pointing down). Storage is a plain `Grid`.
+#[derive(Clone, Debug, Default)]
+pub struct TriGrid<T> {
+    grid: Grid<T>,
+}
+
+pub fn points_up(x: isize, y: isize) -> bool {
+    (x + y).rem_euclid(2) == 0
+}
+
+// The three cells sharing an edge with (x, y): left, right, then the one
+// across the horizontal edge.
+pub fn tri_neighbors(x: isize, y: isize) -> [(isize, isize); 3] {
+    let across = if points_up(x, y) { x + 1 } else { x - 1 };
+    [(x, y - 1), (x, y + 1), (across, y)]
+}
+
+// The number of edge crossings on a shortest walk between two triangles.
+// Every crossing moves across one line of the three families bounding the
+// triangles, so the distance is the sum of the lines between them.
+pub fn tri_distance(a: (isize, isize), b: (isize, isize)) -> usize {
+    let lanes = |(x, y): (isize, isize)| (x, (x + y).div_euclid(2), (y - x - 1).div_euclid(2));
+    let ((ax, ap, aq), (bx, bp, bq)) = (lanes(a), lanes(b));
+    ax.abs_diff(bx) + ap.abs_diff(bp) + aq.abs_diff(bq)
+}
+
+impl<T> TriGrid<T> {
+    pub fn new() -> Self {
+        Self { grid: Grid::new() }
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    pub fn into_grid(self) -> Grid<T> {
+        self.grid
+    }
+
+    pub fn len(&self) -> usize {
+        self.grid.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.grid.len() == 0
+    }
+
+    pub fn bounds(&self) -> Rect {
+        self.grid.bounds()
+    }
+
+    pub fn get(&self, x: isize, y: isize) -> Option<&T> {
+        self.grid.get(x, y)
+    }
+
+    pub fn get_mut(&mut self, x: isize, y: isize) -> Option<&mut T> {
+        self.grid.get_mut(x, y)
+    }
+
+    pub fn set(&mut self, x: isize, y: isize, item: T) {
+        self.grid.set(x, y, item);
+    }
+
+    pub fn remove(&mut self, x: isize, y: isize) -> Option<T> {
+        self.grid.remove(x, y)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((isize, isize), &T)> {
+        self.grid.iter()
+    }
+
+    // The stored edge neighbors of (x, y).
+    pub fn neighbors(&self, x: isize, y: isize) -> impl Iterator<Item = ((isize, isize), &T)> {
+        tri_neighbors(x, y)
+            .into_iter()
+            .filter_map(|(x, y)| self.get(x, y).map(|item| ((x, y), item)))
+    }
+}
+
+impl<T> From<Grid<T>> for TriGrid<T> {
+    fn from(grid: Grid<T>) -> Self {
+        Self { grid }
+    }
+}
+
+impl<T> GridRead<T> for TriGrid<T> {
+    fn get(&self, x: isize, y: isize) -> Option<&T> {
+        TriGrid::get(self, x, y)
+    }
+
+    fn bounds(&self) -> Rect {
+        TriGrid::bounds(self)
+    }
+}
+
+impl<T> GridWrite<T> for TriGrid<T> {
+    fn set(&mut self, x: isize, y: isize, item: T) {
+        TriGrid::set(self, x, y, item);
+    }
+
+    fn remove(&mut self, x: isize, y: isize) -> Option<T> {
+        TriGrid::remove(self, x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{points_up, tri_distance, tri_neighbors, TriGrid};
+    use std::collections::{HashMap, VecDeque};
+
+    #[test]
+    fn neighbors_are_mutual() {
+        assert!(points_up(0, 0) && !points_up(0, 1) && !points_up(-1, 0));
+        assert_eq!(tri_neighbors(0, 0), [(0, -1), (0, 1), (1, 0)]);
+        for cell in [(0, 0), (-3, 4), (2, -5)] {
+            for neighbor in tri_neighbors(cell.0, cell.1) {
+                assert!(tri_neighbors(neighbor.0, neighbor.1).contains(&cell));
+                assert_eq!(tri_distance(cell, neighbor), 1);
+            }
+        }
+
+        let mut grid = TriGrid::new();
+        grid.set(0, 1, 'a');
+        grid.set(-1, 0, 'b');
+        let found: Vec<_> = grid.neighbors(0, 0).collect();
+        assert_eq!(found, [((0, 1), &'a')]);
+    }
+
+    #[test]
+    fn distance_matches_breadth_first_search() {
+        let start = (1, -2);
+        let mut steps = HashMap::from([(start, 0)]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(cell) = queue.pop_front() {
+            for next in tri_neighbors(cell.0, cell.1) {
+                if next.0.abs() <= 6 && next.1.abs() <= 6 && !steps.contains_key(&next) {
+                    steps.insert(next, steps[&cell] + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        for x in -3..=3 {
+            for y in -3..=3 {
+                assert_eq!(tri_distance(start, (x, y)), steps[&(x, y)], "({x}, {y})");
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+// Rows and columns of cells covered by one chunk: 8 rows of one 64-bit word.
+const CHUNK_ROWS: isize = 8;
+const CHUNK_COLUMNS: isize = 64;
+
+#[derive(Clone, Debug)]
+struct Chunk {
+    stamp: u32,
+    words: [u64; CHUNK_ROWS as usize],
+}
+
+// A set of visited cells for searches: a bitset split into 8 x 64 chunks that
+// are hashed by position and only allocated once a cell in them is inserted,
+// so memory follows the cells visited rather than the area they span. Each
+// chunk carries a generation stamp so `clear` is O(1); a chunk whose stamp is
+// older than the current generation reads as empty.
+#[derive(Clone, Debug, Default)]
+pub struct VisitedGrid {
+    chunks: HashMap<(isize, isize), Chunk>,
+    generation: u32,
+}
+
+impl VisitedGrid {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The chunk holding the cell, the row within it and the bit of the row.
+    fn position(x: isize, y: isize) -> ((isize, isize), usize, u64) {
+        (
+            (x.div_euclid(CHUNK_ROWS), y.div_euclid(CHUNK_COLUMNS)),
+            x.rem_euclid(CHUNK_ROWS) as usize,
+            1 << y.rem_euclid(CHUNK_COLUMNS),
+        )
+    }
+
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        let (key, row, bit) = Self::position(x, y);
+        self.chunks
+            .get(&key)
+            .is_some_and(|chunk| chunk.stamp == self.generation && chunk.words[row] & bit != 0)
+    }
+
+    // Marks the cell as visited, returning whether it was new, like
+    // `HashSet::insert`.
+    pub fn insert(&mut self, x: isize, y: isize) -> bool {
+        let (key, row, bit) = Self::position(x, y);
+        let chunk = self.chunks.entry(key).or_insert(Chunk {
+            stamp: self.generation,
+            words: [0; CHUNK_ROWS as usize],
+        });
+        if chunk.stamp != self.generation {
+            chunk.stamp = self.generation;
+            chunk.words = [0; CHUNK_ROWS as usize];
+        }
+
+        let word = chunk.words[row];
+        chunk.words[row] = word | bit;
+        word & bit == 0
+    }
+
+    // Forgets every visited cell in O(1), keeping the chunks for reuse.
+    // Once every 2^32 clears the stamps run out and the chunks are dropped.
+    pub fn clear(&mut self) {
+        match self.generation.checked_add(1) {
+            Some(generation) => self.generation = generation,
+            None => {
+                self.generation = 0;
+                self.chunks.clear();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VisitedGrid;
+
+    #[test]
+    fn clears_and_reuses_chunks() {
+        let mut visited = VisitedGrid::new();
+        assert!(visited.insert(-2, 99));
+        assert!(!visited.insert(-2, 99));
+        assert!(visited.contains(-2, 99));
+        assert!(!visited.contains(-2, 98));
+        assert!(!visited.contains(6, 99));
+
+        visited.clear();
+        assert!(!visited.contains(-2, 99));
+        assert!(visited.insert(-2, 99));
+        assert!(visited.insert(10, -7));
+        assert!(visited.contains(-2, 99));
+        assert!(!visited.contains(0, 0));
+
+        visited.generation = u32::MAX;
+        visited.clear();
+        assert!(!visited.contains(10, -7));
+        assert!(visited.chunks.is_empty());
+    }
+
+    #[test]
+    fn far_apart_cells_stay_small() {
+        let mut visited = VisitedGrid::new();
+        for (x, y) in [
+            (0, 0),
+            (10_000_000, 10_000_000),
+            (isize::MIN, isize::MIN),
+            (isize::MAX, isize::MAX),
+        ] {
+            assert!(visited.insert(x, y));
+            assert!(visited.contains(x, y));
+        }
+        assert!(!visited.contains(isize::MAX, isize::MAX - 1));
+        assert_eq!(visited.chunks.len(), 4);
+    }
+}